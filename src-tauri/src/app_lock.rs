@@ -0,0 +1,291 @@
+//! App lock.
+//!
+//! While locked, commands that read or change days, collections or their history
+//! refuse with an error until `unlock` is called with the passphrase, and the
+//! encryption keys are dropped from memory. The app locks on startup, after a
+//! configurable idle time and when the system wakes from sleep, so the window can be
+//! left open without exposing notes. The lock uses the encryption passphrase, so it
+//! can only be turned on once encryption is enabled.
+
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+use std::time::{Duration, SystemTime};
+use tauri::Emitter;
+
+/// Preference file holding the app lock settings
+const APP_LOCK_SETTINGS_FILE: &str = "app_lock.json";
+
+/// How often the background task checks for idleness and sleep
+const CHECK_INTERVAL: Duration = Duration::from_secs(15);
+
+/// Wall-clock time beyond `CHECK_INTERVAL` between two checks that means the system
+/// was asleep (or the process suspended) in between
+const SLEEP_GAP: Duration = Duration::from_secs(30);
+
+const LOCKED_ERROR: &str = "App is locked: unlock it with your passphrase first";
+
+static LOCKED: AtomicBool = AtomicBool::new(false);
+
+/// When the user last did something. Wall-clock time, so time asleep counts as idle.
+static LAST_ACTIVITY: Mutex<Option<SystemTime>> = Mutex::new(None);
+
+fn default_idle_minutes() -> u32 {
+    10
+}
+
+fn default_lock_on_sleep() -> bool {
+    true
+}
+
+/// User-configurable app lock settings
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct AppLockSettings {
+    #[serde(default)]
+    pub enabled: bool,
+    /// Minutes without activity before the app locks; 0 never locks when idle
+    #[serde(default = "default_idle_minutes")]
+    pub idle_minutes: u32,
+    #[serde(default = "default_lock_on_sleep")]
+    pub lock_on_sleep: bool,
+}
+
+impl Default for AppLockSettings {
+    fn default() -> Self {
+        AppLockSettings {
+            enabled: false,
+            idle_minutes: default_idle_minutes(),
+            lock_on_sleep: default_lock_on_sleep(),
+        }
+    }
+}
+
+fn load_settings(data_dir: &Path) -> Result<AppLockSettings, String> {
+    let file_path = data_dir.join(APP_LOCK_SETTINGS_FILE);
+    if !file_path.exists() {
+        return Ok(AppLockSettings::default());
+    }
+
+    let content = fs::read_to_string(&file_path)
+        .map_err(|e| format!("Failed to read app lock settings: {}", e))?;
+    serde_json::from_str(&content).map_err(|e| format!("Failed to parse app lock settings: {}", e))
+}
+
+fn touch() {
+    *LAST_ACTIVITY.lock().unwrap_or_else(|e| e.into_inner()) = Some(SystemTime::now());
+}
+
+fn idle_for() -> Duration {
+    let last = *LAST_ACTIVITY.lock().unwrap_or_else(|e| e.into_inner());
+    last.and_then(|last| last.elapsed().ok())
+        .unwrap_or_default()
+}
+
+/// Refuse while the app is locked; otherwise count the call as activity.
+///
+/// Called first thing by every command that serves or changes day data.
+pub(crate) fn ensure_unlocked() -> Result<(), String> {
+    if LOCKED.load(Ordering::SeqCst) {
+        return Err(LOCKED_ERROR.to_string());
+    }
+    touch();
+    Ok(())
+}
+
+/// Whether the app is locked, without counting as activity.
+pub(crate) fn is_locked() -> bool {
+    LOCKED.load(Ordering::SeqCst)
+}
+
+/// Lift the lock, after `unlock` checked the passphrase.
+pub(crate) fn mark_unlocked() {
+    LOCKED.store(false, Ordering::SeqCst);
+    touch();
+}
+
+/// Lock the app and tell the frontend to hide its data.
+fn lock(app: &tauri::AppHandle) -> Result<(), String> {
+    LOCKED.store(true, Ordering::SeqCst);
+    crate::encryption::forget_keys();
+    app.emit("app-locked", ())
+        .map_err(|e| format!("Failed to emit app lock event: {}", e))
+}
+
+/// Start locked if the app lock is on for the active data directory, called from
+/// `setup` before the frontend loads anything.
+pub fn lock_on_startup(app: &tauri::AppHandle) -> Result<(), String> {
+    let data_dir = crate::resolve_data_dir(app)?;
+    if load_settings(&data_dir)?.enabled {
+        LOCKED.store(true, Ordering::SeqCst);
+    }
+    Ok(())
+}
+
+/// Whether the app should lock, given how long the user has been idle and the
+/// wall-clock time since the previous check.
+fn should_lock(settings: &AppLockSettings, idle: Duration, since_last_check: Duration) -> bool {
+    if !settings.enabled {
+        return false;
+    }
+
+    let slept = since_last_check > CHECK_INTERVAL + SLEEP_GAP;
+    let idle_limit = Duration::from_secs(u64::from(settings.idle_minutes) * 60);
+    (settings.lock_on_sleep && slept) || (settings.idle_minutes > 0 && idle >= idle_limit)
+}
+
+/// Background task locking the app when idle or after system sleep, started from
+/// `setup`.
+///
+/// Sleep is noticed as a jump in wall-clock time between two checks, since the task
+/// doesn't run while the system is asleep.
+pub async fn run_app_lock_scheduler(app: tauri::AppHandle) {
+    touch();
+    let mut last_check = SystemTime::now();
+
+    loop {
+        tokio::time::sleep(CHECK_INTERVAL).await;
+        let now = SystemTime::now();
+        let since_last_check = now.duration_since(last_check).unwrap_or_default();
+        last_check = now;
+
+        if LOCKED.load(Ordering::SeqCst) {
+            continue;
+        }
+
+        let result: Result<(), String> = crate::resolve_data_dir(&app)
+            .and_then(|data_dir| load_settings(&data_dir))
+            .and_then(|settings| {
+                if should_lock(&settings, idle_for(), since_last_check) {
+                    lock(&app)
+                } else {
+                    Ok(())
+                }
+            });
+
+        if let Err(e) = result {
+            #[cfg(debug_assertions)]
+            eprintln!("App lock check failed: {}", e);
+            let _ = e;
+        }
+    }
+}
+
+/// Load the app lock settings.
+///
+/// # Arguments
+/// * `app` - Tauri app handle for accessing app data directory
+///
+/// # Errors
+/// Returns an error if the settings file cannot be read.
+#[tauri::command]
+pub fn load_app_lock_settings(app: tauri::AppHandle) -> Result<AppLockSettings, String> {
+    let data_dir = crate::resolve_data_dir(&app)?;
+    load_settings(&data_dir)
+}
+
+/// Save the app lock settings.
+///
+/// # Arguments
+/// * `settings` - The settings to store
+/// * `app` - Tauri app handle for accessing app data directory
+///
+/// # Errors
+/// Returns an error if the lock is turned on without encryption, or saving fails.
+#[tauri::command]
+pub fn save_app_lock_settings(
+    settings: AppLockSettings,
+    app: tauri::AppHandle,
+) -> Result<(), String> {
+    let data_dir = crate::resolve_data_dir(&app)?;
+    save_settings(&data_dir, &settings)
+}
+
+fn save_settings(data_dir: &Path, settings: &AppLockSettings) -> Result<(), String> {
+    if settings.enabled && !crate::encryption::is_enabled(data_dir)? {
+        return Err("Enable encryption first: the app lock uses its passphrase".to_string());
+    }
+
+    let json_content = serde_json::to_string_pretty(settings)
+        .map_err(|e| format!("Failed to serialize app lock settings: {}", e))?;
+    fs::write(data_dir.join(APP_LOCK_SETTINGS_FILE), json_content)
+        .map_err(|e| format!("Failed to write app lock settings: {}", e))
+}
+
+/// Lock the app now.
+///
+/// # Arguments
+/// * `app` - Tauri app handle for accessing app data directory
+///
+/// # Errors
+/// Returns an error if the app lock is not enabled.
+#[tauri::command]
+pub fn lock_app(app: tauri::AppHandle) -> Result<(), String> {
+    let data_dir = crate::resolve_data_dir(&app)?;
+    if !load_settings(&data_dir)?.enabled {
+        return Err("App lock is not enabled".to_string());
+    }
+    lock(&app)
+}
+
+/// Note user activity in the window, postponing the idle lock.
+#[tauri::command]
+pub fn record_activity() {
+    if !LOCKED.load(Ordering::SeqCst) {
+        touch();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_should_lock() {
+        let settings = AppLockSettings {
+            enabled: true,
+            idle_minutes: 5,
+            lock_on_sleep: true,
+        };
+        let minutes = |m: u64| Duration::from_secs(m * 60);
+
+        assert!(!should_lock(&settings, minutes(4), CHECK_INTERVAL));
+        assert!(should_lock(&settings, minutes(5), CHECK_INTERVAL));
+        // Woken from sleep
+        assert!(should_lock(&settings, Duration::ZERO, minutes(30)));
+
+        let no_sleep_lock = AppLockSettings {
+            lock_on_sleep: false,
+            ..settings.clone()
+        };
+        assert!(!should_lock(&no_sleep_lock, Duration::ZERO, minutes(30)));
+        let never_idle = AppLockSettings {
+            idle_minutes: 0,
+            ..settings.clone()
+        };
+        assert!(!should_lock(&never_idle, minutes(600), CHECK_INTERVAL));
+        let disabled = AppLockSettings {
+            enabled: false,
+            ..settings
+        };
+        assert!(!should_lock(&disabled, minutes(600), minutes(30)));
+    }
+
+    #[test]
+    fn test_enabling_requires_encryption() {
+        let temp_dir = TempDir::new().unwrap();
+        let settings = AppLockSettings {
+            enabled: true,
+            ..AppLockSettings::default()
+        };
+
+        assert!(save_settings(temp_dir.path(), &settings).is_err());
+        save_settings(temp_dir.path(), &AppLockSettings::default()).unwrap();
+        assert_eq!(
+            load_settings(temp_dir.path()).unwrap(),
+            AppLockSettings::default()
+        );
+    }
+}
@@ -0,0 +1,881 @@
+//! Collections beyond the daily log.
+//!
+//! Besides day files, todos can live in a monthly log (`month-YYYY-MM.json`) for things
+//! planned for a month without a specific day, or in the future log
+//! (`future-log.json`) for "someday" items. Named collections (`collection-<id>.json`)
+//! hold reference lists with no date at all, like "Books to read". Each collection is
+//! one JSON file at the top level of the data directory. Todos move between any two
+//! places through [`crate::transfer_todos`], the same path `move_todo_to_date` uses.
+//! Todos removed from a collection, or deleted along with it, go to the trash.
+
+use crate::{read_day_file, write_day_file, DayData, TodoItem, TodoStatus};
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// File name of the future log
+const FUTURE_LOG_FILE: &str = "future-log.json";
+
+/// File name prefix of monthly logs
+const MONTH_LOG_PREFIX: &str = "month-";
+
+/// File name prefix of named collections
+const COLLECTION_PREFIX: &str = "collection-";
+
+/// Somewhere a todo can live
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum Location {
+    /// The daily log of a date
+    Day(NaiveDate),
+    /// The monthly log of a month in YYYY-MM format
+    Month(String),
+    /// The future log, for todos without a date
+    FutureLog,
+    /// A named collection, by ID
+    Collection(String),
+}
+
+impl Location {
+    /// The date of a daily log location.
+    pub(crate) fn date(&self) -> Option<NaiveDate> {
+        match self {
+            Location::Day(date) => Some(*date),
+            _ => None,
+        }
+    }
+
+    fn collection_path(&self, data_dir: &Path) -> Result<PathBuf, String> {
+        match self {
+            Location::Day(_) => Err("A day is not a collection".to_string()),
+            Location::Month(month) => {
+                parse_month(month)?;
+                Ok(data_dir.join(format!("{}{}.json", MONTH_LOG_PREFIX, month)))
+            }
+            Location::FutureLog => Ok(data_dir.join(FUTURE_LOG_FILE)),
+            Location::Collection(id) => named_collection_path(data_dir, id),
+        }
+    }
+
+    fn default_name(&self) -> String {
+        match self {
+            Location::Day(date) => date.format("%Y-%m-%d").to_string(),
+            Location::Month(month) => month.clone(),
+            Location::FutureLog => "Future log".to_string(),
+            Location::Collection(id) => id.clone(),
+        }
+    }
+
+    /// Load the todo list stored at this location.
+    pub(crate) fn open(&self, data_dir: &Path) -> Result<TodoList, String> {
+        match self {
+            Location::Day(date) => Ok(TodoList::Day(read_day_file(data_dir, *date)?)),
+            Location::Collection(id) => {
+                let path = self.collection_path(data_dir)?;
+                let collection = read_collection_file(&path)?
+                    .ok_or_else(|| format!("Collection {} not found", id))?;
+                Ok(TodoList::Collection(path, collection))
+            }
+            _ => {
+                let path = self.collection_path(data_dir)?;
+                let collection = read_collection_file(&path)?
+                    .unwrap_or_else(|| CollectionData::empty(self.default_name()));
+                Ok(TodoList::Collection(path, collection))
+            }
+        }
+    }
+
+    /// Load the todo list at this location like [`Location::open`], but start a named
+    /// collection that no longer exists afresh instead of failing.
+    pub(crate) fn open_or_recreate(&self, data_dir: &Path) -> Result<TodoList, String> {
+        match self {
+            Location::Collection(_) => {
+                let path = self.collection_path(data_dir)?;
+                let collection = read_collection_file(&path)?
+                    .unwrap_or_else(|| CollectionData::empty(self.default_name()));
+                Ok(TodoList::Collection(path, collection))
+            }
+            _ => self.open(data_dir),
+        }
+    }
+}
+
+impl std::fmt::Display for Location {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Location::Day(date) => write!(f, "{}", date.format("%Y-%m-%d")),
+            Location::Month(month) => write!(f, "the {} log", month),
+            Location::FutureLog => write!(f, "the future log"),
+            Location::Collection(id) => write!(f, "collection {}", id),
+        }
+    }
+}
+
+/// Validate a month in YYYY-MM format and return its first day.
+fn parse_month(month: &str) -> Result<NaiveDate, String> {
+    NaiveDate::parse_from_str(&format!("{}-01", month), "%Y-%m-%d")
+        .ok()
+        .filter(|_| month.len() == 7)
+        .ok_or_else(|| format!("Invalid month format: {}", month))
+}
+
+/// Path of a named collection's file, rejecting IDs that aren't slugs.
+fn named_collection_path(data_dir: &Path, id: &str) -> Result<PathBuf, String> {
+    let valid = !id.is_empty()
+        && id
+            .chars()
+            .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-');
+    if !valid {
+        return Err(format!("Invalid collection ID: {}", id));
+    }
+    Ok(data_dir.join(format!("{}{}.json", COLLECTION_PREFIX, id)))
+}
+
+/// Every day, monthly log, the future log and every named collection that has a file.
+pub(crate) fn all_locations(data_dir: &Path) -> Result<Vec<Location>, String> {
+    let mut locations: Vec<Location> = crate::list_day_dates(data_dir)?
+        .into_iter()
+        .map(Location::Day)
+        .collect();
+
+    let entries =
+        fs::read_dir(data_dir).map_err(|e| format!("Failed to read data directory: {}", e))?;
+    for entry in entries.filter_map(|entry| entry.ok()) {
+        let name = entry.file_name().to_string_lossy().to_string();
+        let location = if name == FUTURE_LOG_FILE {
+            Location::FutureLog
+        } else if let Some(month) = name
+            .strip_prefix(MONTH_LOG_PREFIX)
+            .and_then(|rest| rest.strip_suffix(".json"))
+        {
+            Location::Month(month.to_string())
+        } else if let Some(id) = name
+            .strip_prefix(COLLECTION_PREFIX)
+            .and_then(|rest| rest.strip_suffix(".json"))
+        {
+            Location::Collection(id.to_string())
+        } else {
+            continue;
+        };
+        if location.collection_path(data_dir).is_ok() {
+            locations.push(location);
+        }
+    }
+    Ok(locations)
+}
+
+/// Turn a collection name into a file-safe ID, e.g. "Books to read" -> "books-to-read".
+pub(crate) fn slugify(name: &str) -> String {
+    let slug: String = name
+        .to_lowercase()
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '-' })
+        .collect();
+    let slug = slug
+        .split('-')
+        .filter(|part| !part.is_empty())
+        .collect::<Vec<_>>()
+        .join("-");
+
+    if slug.is_empty() {
+        "collection".to_string()
+    } else {
+        slug
+    }
+}
+
+/// A named collection as listed in the sidebar
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct CollectionSummary {
+    pub id: String,
+    pub name: String,
+    /// Number of open todos in the collection
+    pub open_count: usize,
+}
+
+/// The contents of a collection
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct CollectionData {
+    pub name: String,
+    #[serde(default)]
+    pub todos: Vec<TodoItem>,
+    #[serde(default)]
+    pub notes: String,
+}
+
+impl CollectionData {
+    fn empty(name: String) -> Self {
+        CollectionData {
+            name,
+            todos: Vec::new(),
+            notes: String::new(),
+        }
+    }
+}
+
+/// A loaded day or collection, saved back with [`TodoList::save`]
+pub(crate) enum TodoList {
+    Day(DayData),
+    Collection(PathBuf, CollectionData),
+}
+
+impl TodoList {
+    pub(crate) fn todos(&self) -> &[TodoItem] {
+        match self {
+            TodoList::Day(day_data) => &day_data.todos,
+            TodoList::Collection(_, collection) => &collection.todos,
+        }
+    }
+
+    pub(crate) fn todos_mut(&mut self) -> &mut Vec<TodoItem> {
+        match self {
+            TodoList::Day(day_data) => &mut day_data.todos,
+            TodoList::Collection(_, collection) => &mut collection.todos,
+        }
+    }
+
+    pub(crate) fn save(&self, data_dir: &Path) -> Result<(), String> {
+        match self {
+            TodoList::Day(day_data) => write_day_file(data_dir, day_data),
+            TodoList::Collection(path, collection) => write_collection_file(path, collection),
+        }
+    }
+}
+
+/// The data directory a collection file lives in, at its top level.
+fn collection_data_dir(path: &Path) -> &Path {
+    path.parent().unwrap_or(Path::new(""))
+}
+
+fn read_collection_file(path: &Path) -> Result<Option<CollectionData>, String> {
+    if !path.exists() {
+        return Ok(None);
+    }
+
+    let content =
+        fs::read_to_string(path).map_err(|e| format!("Failed to read collection: {}", e))?;
+    let content = crate::encryption::open(collection_data_dir(path), &content)?;
+    serde_json::from_str(&content)
+        .map(Some)
+        .map_err(|e| format!("Failed to parse collection: {}", e))
+}
+
+/// Write a collection file atomically, like day files.
+fn write_collection_file(path: &Path, collection: &CollectionData) -> Result<(), String> {
+    let json_content = serde_json::to_string_pretty(collection)
+        .map_err(|e| format!("Failed to serialize collection: {}", e))?;
+    let json_content = crate::encryption::seal(collection_data_dir(path), &json_content)?;
+
+    let tmp_path = path.with_extension("json.tmp");
+    fs::write(&tmp_path, json_content).map_err(|e| format!("Failed to write file: {}", e))?;
+    fs::rename(&tmp_path, path).map_err(|e| format!("Failed to replace file: {}", e))
+}
+
+/// Replace the todos and notes of a collection, keeping its name. Removed todos go to
+/// the trash.
+async fn replace_collection_contents(
+    location: Location,
+    collection: CollectionData,
+    data_dir: &Path,
+) -> Result<(), String> {
+    let _guard = crate::DAY_FILES_LOCK.lock().await;
+    let path = location.collection_path(data_dir)?;
+    let (name, stored) = match (read_collection_file(&path)?, &location) {
+        (Some(existing), _) => (existing.name, existing.todos),
+        (None, Location::Collection(id)) => return Err(format!("Collection {} not found", id)),
+        (None, _) => (location.default_name(), Vec::new()),
+    };
+    crate::trash::track_removed(data_dir, &location, stored, &collection.todos)?;
+
+    write_collection_file(
+        &path,
+        &CollectionData {
+            name,
+            todos: collection.todos,
+            notes: collection.notes,
+        },
+    )
+}
+
+/// List the months that have a monthly log, oldest first.
+///
+/// # Arguments
+/// * `data_dir` - Path to the app data directory
+///
+/// # Returns
+/// Months in YYYY-MM format.
+///
+/// # Errors
+/// Returns an error if the data directory cannot be read.
+#[tauri::command]
+pub async fn list_month_logs(data_dir: String) -> Result<Vec<String>, String> {
+    crate::app_lock::ensure_unlocked()?;
+    let entries =
+        fs::read_dir(&data_dir).map_err(|e| format!("Failed to read data directory: {}", e))?;
+
+    let mut months: Vec<String> = entries
+        .filter_map(|entry| entry.ok())
+        .filter_map(|entry| {
+            let name = entry.file_name().to_string_lossy().to_string();
+            let month = name.strip_prefix(MONTH_LOG_PREFIX)?.strip_suffix(".json")?;
+            parse_month(month).ok().map(|_| month.to_string())
+        })
+        .collect();
+
+    months.sort_unstable();
+    Ok(months)
+}
+
+/// Load the monthly log of a month.
+///
+/// # Arguments
+/// * `month` - Month in YYYY-MM format
+/// * `data_dir` - Path to the app data directory
+///
+/// # Returns
+/// The month's collection, or an empty one if it has no log yet.
+///
+/// # Errors
+/// Returns an error if the month is invalid or the file cannot be read.
+#[tauri::command]
+pub async fn load_month_log(month: String, data_dir: String) -> Result<CollectionData, String> {
+    crate::app_lock::ensure_unlocked()?;
+    let location = Location::Month(month);
+    let path = location.collection_path(Path::new(&data_dir))?;
+    Ok(read_collection_file(&path)?
+        .unwrap_or_else(|| CollectionData::empty(location.default_name())))
+}
+
+/// Save the todos and notes of a monthly log.
+///
+/// # Arguments
+/// * `month` - Month in YYYY-MM format
+/// * `month_log` - The collection contents to store
+/// * `data_dir` - Path to the app data directory
+///
+/// # Errors
+/// Returns an error if the month is invalid or the file cannot be written.
+#[tauri::command]
+pub async fn save_month_log(
+    month: String,
+    month_log: CollectionData,
+    data_dir: String,
+) -> Result<(), String> {
+    crate::app_lock::ensure_unlocked()?;
+    replace_collection_contents(Location::Month(month), month_log, Path::new(&data_dir)).await
+}
+
+/// Load the future log.
+///
+/// # Arguments
+/// * `data_dir` - Path to the app data directory
+///
+/// # Errors
+/// Returns an error if the file cannot be read.
+#[tauri::command]
+pub async fn load_future_log(data_dir: String) -> Result<CollectionData, String> {
+    crate::app_lock::ensure_unlocked()?;
+    let location = Location::FutureLog;
+    let path = location.collection_path(Path::new(&data_dir))?;
+    Ok(read_collection_file(&path)?
+        .unwrap_or_else(|| CollectionData::empty(location.default_name())))
+}
+
+/// Save the todos and notes of the future log.
+///
+/// # Arguments
+/// * `future_log` - The collection contents to store
+/// * `data_dir` - Path to the app data directory
+///
+/// # Errors
+/// Returns an error if the file cannot be written.
+#[tauri::command]
+pub async fn save_future_log(future_log: CollectionData, data_dir: String) -> Result<(), String> {
+    crate::app_lock::ensure_unlocked()?;
+    replace_collection_contents(Location::FutureLog, future_log, Path::new(&data_dir)).await
+}
+
+/// Schedule a todo from a monthly log or the future log onto a day.
+///
+/// The collection keeps a "scheduled" entry (<) pointing to the day, as in a paper
+/// bullet journal.
+///
+/// # Arguments
+/// * `todo_id` - The unique ID of the todo item
+/// * `from` - The collection the todo is in
+/// * `date` - Destination date in YYYY-MM-DD format
+/// * `data_dir` - Path to the app data directory
+///
+/// # Returns
+/// The todo as it now appears on the day.
+///
+/// # Errors
+/// Returns an error if the date is invalid, `from` is a day, the todo is not found or
+/// has already been moved, or file operations fail.
+#[tauri::command]
+pub async fn schedule_todo(
+    todo_id: String,
+    from: Location,
+    date: String,
+    data_dir: String,
+) -> Result<TodoItem, String> {
+    crate::app_lock::ensure_unlocked()?;
+    let date = NaiveDate::parse_from_str(&date, "%Y-%m-%d")
+        .map_err(|e| format!("Invalid date format: {}", e))?;
+    if from.date().is_some() {
+        return Err("Use move_todo_to_date to move todos between days".to_string());
+    }
+
+    let _guard = crate::DAY_FILES_LOCK.lock().await;
+    let mut moved = crate::transfer_todos(
+        Path::new(&data_dir),
+        &from,
+        &Location::Day(date),
+        &[todo_id],
+    )?;
+    Ok(moved.remove(0))
+}
+
+/// List the named collections, sorted by name.
+///
+/// # Arguments
+/// * `data_dir` - Path to the app data directory
+///
+/// # Errors
+/// Returns an error if the data directory or a collection file cannot be read.
+#[tauri::command]
+pub async fn list_collections(data_dir: String) -> Result<Vec<CollectionSummary>, String> {
+    crate::app_lock::ensure_unlocked()?;
+    let data_dir = Path::new(&data_dir);
+    let entries =
+        fs::read_dir(data_dir).map_err(|e| format!("Failed to read data directory: {}", e))?;
+
+    let mut summaries = Vec::new();
+    for entry in entries.filter_map(|entry| entry.ok()) {
+        let file_name = entry.file_name().to_string_lossy().to_string();
+        let Some(id) = file_name
+            .strip_prefix(COLLECTION_PREFIX)
+            .and_then(|rest| rest.strip_suffix(".json"))
+        else {
+            continue;
+        };
+
+        if let Some(collection) = read_collection_file(&entry.path())? {
+            summaries.push(CollectionSummary {
+                id: id.to_string(),
+                name: collection.name,
+                open_count: collection
+                    .todos
+                    .iter()
+                    .filter(|t| t.status == TodoStatus::Open)
+                    .count(),
+            });
+        }
+    }
+
+    summaries.sort_by_key(|summary| summary.name.to_lowercase());
+    Ok(summaries)
+}
+
+/// Create an empty named collection.
+///
+/// The ID is derived from the name and stays the same if the collection is renamed.
+///
+/// # Arguments
+/// * `name` - Display name of the collection
+/// * `data_dir` - Path to the app data directory
+///
+/// # Returns
+/// The new collection's summary.
+///
+/// # Errors
+/// Returns an error if the name is empty or the file cannot be written.
+#[tauri::command]
+pub async fn create_collection(
+    name: String,
+    data_dir: String,
+) -> Result<CollectionSummary, String> {
+    crate::app_lock::ensure_unlocked()?;
+    let name = name.trim().to_string();
+    if name.is_empty() {
+        return Err("Collection name cannot be empty".to_string());
+    }
+    let data_dir = Path::new(&data_dir);
+
+    let _guard = crate::DAY_FILES_LOCK.lock().await;
+    let base = slugify(&name);
+    let mut id = base.clone();
+    let mut suffix = 2;
+    while named_collection_path(data_dir, &id)?.exists() {
+        id = format!("{}-{}", base, suffix);
+        suffix += 1;
+    }
+
+    write_collection_file(
+        &named_collection_path(data_dir, &id)?,
+        &CollectionData::empty(name.clone()),
+    )?;
+
+    Ok(CollectionSummary {
+        id,
+        name,
+        open_count: 0,
+    })
+}
+
+/// Load a named collection.
+///
+/// # Arguments
+/// * `id` - ID of the collection
+/// * `data_dir` - Path to the app data directory
+///
+/// # Errors
+/// Returns an error if the collection doesn't exist or cannot be read.
+#[tauri::command]
+pub async fn load_collection(id: String, data_dir: String) -> Result<CollectionData, String> {
+    crate::app_lock::ensure_unlocked()?;
+    let path = named_collection_path(Path::new(&data_dir), &id)?;
+    read_collection_file(&path)?.ok_or_else(|| format!("Collection {} not found", id))
+}
+
+/// Save the todos and notes of a named collection.
+///
+/// # Arguments
+/// * `id` - ID of the collection
+/// * `collection` - The collection contents to store
+/// * `data_dir` - Path to the app data directory
+///
+/// # Errors
+/// Returns an error if the collection doesn't exist or cannot be written.
+#[tauri::command]
+pub async fn save_collection(
+    id: String,
+    collection: CollectionData,
+    data_dir: String,
+) -> Result<(), String> {
+    crate::app_lock::ensure_unlocked()?;
+    replace_collection_contents(Location::Collection(id), collection, Path::new(&data_dir)).await
+}
+
+/// Rename a named collection. Its ID is unchanged.
+///
+/// # Arguments
+/// * `id` - ID of the collection
+/// * `name` - New display name
+/// * `data_dir` - Path to the app data directory
+///
+/// # Errors
+/// Returns an error if the name is empty, the collection doesn't exist, or saving fails.
+#[tauri::command]
+pub async fn rename_collection(id: String, name: String, data_dir: String) -> Result<(), String> {
+    crate::app_lock::ensure_unlocked()?;
+    let name = name.trim().to_string();
+    if name.is_empty() {
+        return Err("Collection name cannot be empty".to_string());
+    }
+
+    let _guard = crate::DAY_FILES_LOCK.lock().await;
+    let path = named_collection_path(Path::new(&data_dir), &id)?;
+    let mut collection =
+        read_collection_file(&path)?.ok_or_else(|| format!("Collection {} not found", id))?;
+    collection.name = name;
+    write_collection_file(&path, &collection)
+}
+
+/// Delete a named collection, moving its todos to the trash.
+///
+/// # Arguments
+/// * `id` - ID of the collection
+/// * `data_dir` - Path to the app data directory
+///
+/// # Errors
+/// Returns an error if the collection doesn't exist or cannot be removed.
+#[tauri::command]
+pub async fn delete_collection(id: String, data_dir: String) -> Result<(), String> {
+    crate::app_lock::ensure_unlocked()?;
+    let _guard = crate::DAY_FILES_LOCK.lock().await;
+    let data_dir = Path::new(&data_dir);
+    let path = named_collection_path(data_dir, &id)?;
+    let collection =
+        read_collection_file(&path)?.ok_or_else(|| format!("Collection {} not found", id))?;
+    crate::trash::move_to_trash(data_dir, &Location::Collection(id), collection.todos)?;
+    fs::remove_file(&path).map_err(|e| format!("Failed to delete collection: {}", e))
+}
+
+/// Move a todo between any two places: days, monthly logs, the future log and named
+/// collections. Used for drag and drop.
+///
+/// # Arguments
+/// * `todo_id` - The unique ID of the todo item
+/// * `from` - Where the todo is now
+/// * `to` - Where it should go
+/// * `data_dir` - Path to the app data directory
+///
+/// # Returns
+/// The todo as it now appears at the destination.
+///
+/// # Errors
+/// Returns an error if both locations are the same, a location is invalid, the todo is
+/// not found or has already been moved, or file operations fail.
+#[tauri::command]
+pub async fn move_todo(
+    todo_id: String,
+    from: Location,
+    to: Location,
+    data_dir: String,
+) -> Result<TodoItem, String> {
+    crate::app_lock::ensure_unlocked()?;
+    if from == to {
+        return Err("Source and destination are the same".to_string());
+    }
+
+    let _guard = crate::DAY_FILES_LOCK.lock().await;
+    let mut moved = crate::transfer_todos(Path::new(&data_dir), &from, &to, &[todo_id])?;
+    Ok(moved.remove(0))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::load_day_data;
+    use tempfile::TempDir;
+
+    #[tokio::test]
+    async fn test_month_and_future_logs() {
+        let temp_dir = TempDir::new().unwrap();
+        let data_dir = temp_dir.path().to_string_lossy().to_string();
+
+        let empty = load_future_log(data_dir.clone()).await.unwrap();
+        assert_eq!(empty.name, "Future log");
+        assert!(empty.todos.is_empty());
+
+        let month_log = CollectionData {
+            name: String::new(),
+            todos: vec![TodoItem::new("Renew insurance".to_string())],
+            notes: "Quiet month".to_string(),
+        };
+        save_month_log("2024-03".to_string(), month_log, data_dir.clone())
+            .await
+            .unwrap();
+        save_future_log(CollectionData::empty(String::new()), data_dir.clone())
+            .await
+            .unwrap();
+
+        let loaded = load_month_log("2024-03".to_string(), data_dir.clone())
+            .await
+            .unwrap();
+        assert_eq!(loaded.name, "2024-03");
+        assert_eq!(loaded.todos[0].text, "Renew insurance");
+        assert_eq!(
+            list_month_logs(data_dir.clone()).await.unwrap(),
+            vec!["2024-03"]
+        );
+
+        // Collections are not mistaken for days
+        assert!(crate::list_day_dates(temp_dir.path()).unwrap().is_empty());
+
+        let invalid = load_month_log("2024-3".to_string(), data_dir).await;
+        assert!(invalid.unwrap_err().contains("Invalid month"));
+    }
+
+    #[tokio::test]
+    async fn test_schedule_todo_from_future_log() {
+        let temp_dir = TempDir::new().unwrap();
+        let data_dir = temp_dir.path().to_string_lossy().to_string();
+
+        let todo = TodoItem::new("Learn Rust".to_string());
+        let future_log = CollectionData {
+            name: String::new(),
+            todos: vec![todo.clone()],
+            notes: String::new(),
+        };
+        save_future_log(future_log, data_dir.clone()).await.unwrap();
+
+        let scheduled = schedule_todo(
+            todo.id.clone(),
+            Location::FutureLog,
+            "2024-02-10".to_string(),
+            data_dir.clone(),
+        )
+        .await
+        .unwrap();
+        assert_eq!(scheduled.id, todo.id);
+        // Only moves between days count as postponing
+        assert!(scheduled.migrated_through.is_empty());
+
+        let day = load_day_data("2024-02-10".to_string(), data_dir.clone(), None)
+            .await
+            .unwrap();
+        assert_eq!(day.todos[0].id, todo.id);
+
+        let future_log = load_future_log(data_dir.clone()).await.unwrap();
+        assert_eq!(future_log.todos[0].status, TodoStatus::Scheduled);
+        assert_eq!(
+            future_log.todos[0].migrated_to,
+            NaiveDate::from_ymd_opt(2024, 2, 10)
+        );
+
+        // The scheduled entry can't be scheduled again
+        let again = schedule_todo(
+            future_log.todos[0].id.clone(),
+            Location::FutureLog,
+            "2024-02-11".to_string(),
+            data_dir,
+        )
+        .await;
+        assert!(again.unwrap_err().contains("already been moved"));
+    }
+
+    #[tokio::test]
+    async fn test_removed_collection_todos_go_to_trash() {
+        let temp_dir = TempDir::new().unwrap();
+        let data_dir = temp_dir.path().to_string_lossy().to_string();
+
+        let books = create_collection("Books".to_string(), data_dir.clone())
+            .await
+            .unwrap();
+        let dune = TodoItem::new("Dune".to_string());
+        let emma = TodoItem::new("Emma".to_string());
+        let collection = CollectionData {
+            name: String::new(),
+            todos: vec![dune.clone(), emma.clone()],
+            notes: String::new(),
+        };
+        save_collection(books.id.clone(), collection, data_dir.clone())
+            .await
+            .unwrap();
+        let collection = CollectionData {
+            name: String::new(),
+            todos: vec![dune.clone()],
+            notes: String::new(),
+        };
+        save_collection(books.id.clone(), collection, data_dir.clone())
+            .await
+            .unwrap();
+        delete_collection(books.id.clone(), data_dir.clone())
+            .await
+            .unwrap();
+
+        let trash = crate::trash::list_trash(data_dir.clone()).await.unwrap();
+        assert_eq!(trash.len(), 2);
+        assert!(trash
+            .iter()
+            .all(|entry| entry.origin == Location::Collection(books.id.clone())));
+
+        // Restoring recreates the deleted collection
+        crate::trash::restore_from_trash(emma.id.clone(), data_dir.clone())
+            .await
+            .unwrap();
+        let restored = load_collection(books.id, data_dir).await.unwrap();
+        assert_eq!(restored.todos.len(), 1);
+        assert_eq!(restored.todos[0].id, emma.id);
+    }
+
+    #[tokio::test]
+    async fn test_failed_move_keeps_todo() {
+        let temp_dir = TempDir::new().unwrap();
+        let data_dir = temp_dir.path().to_string_lossy().to_string();
+        let date = NaiveDate::from_ymd_opt(2024, 1, 15).unwrap();
+        let todo = TodoItem {
+            notes: "Details".to_string(),
+            ..TodoItem::new("Call bank".to_string())
+        };
+        let day_data = DayData {
+            date,
+            todos: vec![todo.clone()],
+            notes: String::new(),
+        };
+        write_day_file(temp_dir.path(), &day_data).unwrap();
+
+        for to in [
+            Location::Collection("nope".to_string()),
+            Location::Month("2024-13".to_string()),
+        ] {
+            let result = move_todo(todo.id.clone(), Location::Day(date), to, data_dir.clone());
+            assert!(result.await.is_err());
+        }
+
+        let day_data = read_day_file(temp_dir.path(), date).unwrap();
+        assert_eq!(day_data.todos.len(), 1);
+        assert_eq!(day_data.todos[0].id, todo.id);
+        assert_eq!(day_data.todos[0].notes, "Details");
+        assert_eq!(day_data.todos[0].status, TodoStatus::Open);
+    }
+
+    #[tokio::test]
+    async fn test_named_collections_and_moves() {
+        let temp_dir = TempDir::new().unwrap();
+        let data_dir = temp_dir.path().to_string_lossy().to_string();
+
+        let books = create_collection("Books to read".to_string(), data_dir.clone())
+            .await
+            .unwrap();
+        assert_eq!(books.id, "books-to-read");
+        let duplicate = create_collection("Books to Read!".to_string(), data_dir.clone())
+            .await
+            .unwrap();
+        assert_eq!(duplicate.id, "books-to-read-2");
+        delete_collection(duplicate.id, data_dir.clone())
+            .await
+            .unwrap();
+
+        let todo = TodoItem::new("Dune".to_string());
+        let collection = CollectionData {
+            name: String::new(),
+            todos: vec![todo.clone()],
+            notes: String::new(),
+        };
+        save_collection(books.id.clone(), collection, data_dir.clone())
+            .await
+            .unwrap();
+        rename_collection(books.id.clone(), "Reading".to_string(), data_dir.clone())
+            .await
+            .unwrap();
+
+        let listed = list_collections(data_dir.clone()).await.unwrap();
+        assert_eq!(
+            listed,
+            vec![CollectionSummary {
+                id: "books-to-read".to_string(),
+                name: "Reading".to_string(),
+                open_count: 1,
+            }]
+        );
+
+        // Drag onto a day and back into the collection
+        let day = Location::Day(NaiveDate::from_ymd_opt(2024, 2, 10).unwrap());
+        let books_location = Location::Collection(books.id.clone());
+        move_todo(
+            todo.id.clone(),
+            books_location.clone(),
+            day.clone(),
+            data_dir.clone(),
+        )
+        .await
+        .unwrap();
+        let moved_back = move_todo(todo.id.clone(), day, books_location, data_dir.clone())
+            .await
+            .unwrap();
+        assert!(moved_back.migrated_through.is_empty());
+
+        let loaded = load_collection(books.id.clone(), data_dir.clone())
+            .await
+            .unwrap();
+        assert_eq!(loaded.todos[0].id, todo.id);
+        assert_eq!(loaded.todos[1].status, TodoStatus::Scheduled);
+
+        let missing = move_todo(
+            todo.id,
+            Location::Collection("nope".to_string()),
+            Location::FutureLog,
+            data_dir.clone(),
+        )
+        .await;
+        assert!(missing.unwrap_err().contains("not found"));
+        assert!(load_collection("../day".to_string(), data_dir)
+            .await
+            .unwrap_err()
+            .contains("Invalid collection ID"));
+    }
+}
@@ -0,0 +1,839 @@
+//! Conflict-free day documents for syncing through a shared folder.
+//!
+//! When enabled, every write of a day file is also recorded in a CRDT document under
+//! `crdt/<replica>/YYYY-MM-DD.json`, where each device (replica) only ever writes its
+//! own folder. Loading a day merges the documents of all replicas and rewrites the day
+//! file from the result, so concurrent edits on two machines converge no matter in
+//! which order the folders are synced.
+//!
+//! Todos form an ordered map by ID: each field of a todo, its presence and its position
+//! are last-writer-wins registers ordered by Lamport timestamps. Notes are a sequence of
+//! characters (an RGA), so concurrent edits to different parts of the notes are all
+//! kept. Deleted characters are dropped again once no replica's document still shows
+//! them and nothing visible was typed after them.
+
+use crate::{DayData, TodoItem};
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+
+/// Directory in the data directory holding each replica's documents
+pub(crate) const CRDT_DIR: &str = "crdt";
+
+/// Preference file holding the CRDT sync settings
+const CRDT_SETTINGS_FILE: &str = "crdt_settings.json";
+
+/// File in the app config directory naming this device's replica
+const REPLICA_ID_FILE: &str = "replica_id";
+
+/// This device's replica ID, set up in `setup`
+static REPLICA_ID: OnceLock<String> = OnceLock::new();
+
+/// CRDT sync settings
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Default)]
+pub struct CrdtSettings {
+    #[serde(default)]
+    pub enabled: bool,
+}
+
+/// A Lamport timestamp: a counter, with the replica ID breaking ties
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+struct Stamp(u64, String);
+
+/// A last-writer-wins register
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq)]
+struct Register<T> {
+    stamp: Stamp,
+    value: T,
+}
+
+impl<T: Clone> Register<T> {
+    fn merge(&mut self, other: &Register<T>) {
+        if other.stamp > self.stamp {
+            *self = other.clone();
+        }
+    }
+}
+
+/// A todo in a day document
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq)]
+struct TodoEntry {
+    /// False once the todo is deleted
+    present: Register<bool>,
+    /// Sort key; todos are ordered by key, then ID
+    position: Register<String>,
+    /// The todo's JSON fields other than `id`, `null` when unset
+    fields: BTreeMap<String, Register<Value>>,
+}
+
+/// A character of the notes
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+struct NoteChar {
+    id: Stamp,
+    /// The character this one was typed after, `None` at the start
+    after: Option<Stamp>,
+    ch: char,
+    deleted: bool,
+}
+
+/// A day's data as a CRDT
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub(crate) struct DayDocument {
+    date: NaiveDate,
+    /// Highest counter seen, for the next local timestamp
+    clock: u64,
+    todos: BTreeMap<String, TodoEntry>,
+    /// Sorted by ID so equal documents serialize identically
+    notes: Vec<NoteChar>,
+}
+
+impl DayDocument {
+    fn new(date: NaiveDate) -> Self {
+        DayDocument {
+            date,
+            clock: 0,
+            todos: BTreeMap::new(),
+            notes: Vec::new(),
+        }
+    }
+
+    fn tick(&mut self, replica: &str) -> Stamp {
+        self.clock += 1;
+        Stamp(self.clock, replica.to_string())
+    }
+
+    /// Merge another replica's document into this one.
+    ///
+    /// Commutative, associative and idempotent, so replicas converge whatever order
+    /// documents are merged in.
+    pub(crate) fn merge(&mut self, other: &DayDocument) {
+        self.clock = self.clock.max(other.clock);
+
+        for (id, theirs) in &other.todos {
+            let entry = self.todos.entry(id.clone()).or_default();
+            entry.present.merge(&theirs.present);
+            entry.position.merge(&theirs.position);
+            for (key, field) in &theirs.fields {
+                entry.fields.entry(key.clone()).or_default().merge(field);
+            }
+        }
+
+        let mut chars: BTreeMap<Stamp, NoteChar> =
+            self.notes.drain(..).map(|c| (c.id.clone(), c)).collect();
+        for theirs in &other.notes {
+            chars
+                .entry(theirs.id.clone())
+                .and_modify(|c| c.deleted |= theirs.deleted)
+                .or_insert_with(|| theirs.clone());
+        }
+        self.notes = chars.into_values().collect();
+    }
+
+    /// Indices of the note characters in text order, deleted ones included.
+    fn note_order(&self) -> Vec<usize> {
+        let mut children: HashMap<Option<&Stamp>, Vec<usize>> = HashMap::new();
+        for (i, c) in self.notes.iter().enumerate() {
+            children.entry(c.after.as_ref()).or_default().push(i);
+        }
+
+        // Later insertions after the same character come first
+        let mut order = Vec::with_capacity(self.notes.len());
+        let mut stack: Vec<usize> = Vec::new();
+        let push_children = |stack: &mut Vec<usize>, parent: Option<&Stamp>| {
+            if let Some(kids) = children.get(&parent) {
+                let mut kids = kids.clone();
+                kids.sort_by(|a, b| self.notes[*a].id.cmp(&self.notes[*b].id));
+                stack.extend(kids);
+            }
+        };
+        push_children(&mut stack, None);
+        while let Some(i) = stack.pop() {
+            order.push(i);
+            push_children(&mut stack, Some(&self.notes[i].id));
+        }
+        order
+    }
+
+    /// Drop deleted note characters that no document in `others` still shows, unless a
+    /// character that stays was typed after them.
+    fn compact_notes(&mut self, others: &[DayDocument]) {
+        let shown: HashSet<&Stamp> = others
+            .iter()
+            .flat_map(|other| &other.notes)
+            .filter(|c| !c.deleted)
+            .map(|c| &c.id)
+            .collect();
+        let index: HashMap<&Stamp, usize> = self
+            .notes
+            .iter()
+            .enumerate()
+            .map(|(i, c)| (&c.id, i))
+            .collect();
+
+        // Children come after their parent in text order, so walk it backwards
+        let mut remove = vec![false; self.notes.len()];
+        let mut parent_of_kept = vec![false; self.notes.len()];
+        for i in self.note_order().into_iter().rev() {
+            let c = &self.notes[i];
+            remove[i] = c.deleted && !parent_of_kept[i] && !shown.contains(&c.id);
+            if !remove[i] {
+                if let Some(parent) = c.after.as_ref().and_then(|p| index.get(p)) {
+                    parent_of_kept[*parent] = true;
+                }
+            }
+        }
+
+        let notes = std::mem::take(&mut self.notes);
+        self.notes = notes
+            .into_iter()
+            .zip(remove)
+            .filter(|(_, remove)| !remove)
+            .map(|(c, _)| c)
+            .collect();
+    }
+
+    /// The visible todos in order, with their IDs.
+    fn todo_order(&self) -> Vec<(&String, &TodoEntry)> {
+        let mut todos: Vec<(&String, &TodoEntry)> = self
+            .todos
+            .iter()
+            .filter(|(_, entry)| entry.present.value)
+            .collect();
+        todos.sort_by(|a, b| (&a.1.position.value, a.0).cmp(&(&b.1.position.value, b.0)));
+        todos
+    }
+
+    /// The day's data as currently represented by the document.
+    pub(crate) fn to_day_data(&self) -> Result<DayData, String> {
+        let mut todos = Vec::new();
+        for (id, entry) in self.todo_order() {
+            let mut object: serde_json::Map<String, Value> = entry
+                .fields
+                .iter()
+                .filter(|(_, field)| !field.value.is_null())
+                .map(|(key, field)| (key.clone(), field.value.clone()))
+                .collect();
+            object.insert("id".to_string(), Value::String(id.clone()));
+
+            let todo: TodoItem = serde_json::from_value(Value::Object(object))
+                .map_err(|e| format!("Failed to read day document: {}", e))?;
+            todos.push(todo);
+        }
+
+        let notes = self
+            .note_order()
+            .into_iter()
+            .map(|i| &self.notes[i])
+            .filter(|c| !c.deleted)
+            .map(|c| c.ch)
+            .collect();
+
+        Ok(DayData {
+            date: self.date,
+            todos,
+            notes,
+        })
+    }
+
+    /// Record the changes that turn the document into `day_data`, as `replica`.
+    pub(crate) fn record(&mut self, replica: &str, day_data: &DayData) -> Result<(), String> {
+        // Deletions
+        let kept: Vec<&str> = day_data.todos.iter().map(|t| t.id.as_str()).collect();
+        let deleted: Vec<String> = self
+            .todo_order()
+            .into_iter()
+            .filter(|(id, _)| !kept.contains(&id.as_str()))
+            .map(|(id, _)| id.clone())
+            .collect();
+        for id in deleted {
+            let stamp = self.tick(replica);
+            if let Some(entry) = self.todos.get_mut(&id) {
+                entry.present = Register {
+                    stamp,
+                    value: false,
+                };
+            }
+        }
+
+        // Additions and edits, field by field
+        for todo in &day_data.todos {
+            let Value::Object(mut object) = serde_json::to_value(todo)
+                .map_err(|e| format!("Failed to serialize data: {}", e))?
+            else {
+                return Err("Failed to serialize data: todo is not an object".to_string());
+            };
+            object.remove("id");
+
+            let mut keys: Vec<String> = object.keys().cloned().collect();
+            if let Some(entry) = self.todos.get(&todo.id) {
+                keys.extend(
+                    entry
+                        .fields
+                        .keys()
+                        .filter(|k| !object.contains_key(*k))
+                        .cloned(),
+                );
+            }
+
+            for key in keys {
+                let value = object.remove(&key).unwrap_or(Value::Null);
+                let current = self
+                    .todos
+                    .get(&todo.id)
+                    .and_then(|entry| entry.fields.get(&key))
+                    .map_or(&Value::Null, |field| &field.value);
+                if *current != value {
+                    let stamp = self.tick(replica);
+                    let entry = self.todos.entry(todo.id.clone()).or_default();
+                    entry.fields.insert(key, Register { stamp, value });
+                }
+            }
+
+            if !self.todos.get(&todo.id).is_some_and(|e| e.present.value) {
+                let stamp = self.tick(replica);
+                self.todos.entry(todo.id.clone()).or_default().present =
+                    Register { stamp, value: true };
+            }
+        }
+
+        self.record_order(replica, &kept);
+        self.record_notes(replica, &day_data.notes);
+        Ok(())
+    }
+
+    /// Give todos new sort keys where needed to match `ids`' order, keeping the keys
+    /// of the longest run already in order.
+    fn record_order(&mut self, replica: &str, ids: &[&str]) {
+        // Keys from other replicas' documents are not trusted; invalid ones are replaced
+        let keys: Vec<String> = ids
+            .iter()
+            .map(|id| self.todos[*id].position.value.clone())
+            .map(|key| {
+                if is_valid_key(&key) {
+                    key
+                } else {
+                    String::new()
+                }
+            })
+            .collect();
+        let keep = longest_increasing(&keys);
+
+        let mut previous: Option<String> = None;
+        for (i, id) in ids.iter().enumerate() {
+            let key = if keep[i] {
+                keys[i].clone()
+            } else {
+                let next = (i + 1..ids.len())
+                    .find(|j| keep[*j])
+                    .map(|j| keys[j].as_str());
+                let key = key_between(previous.as_deref(), next);
+                let stamp = self.tick(replica);
+                self.todos.get_mut(*id).unwrap().position = Register {
+                    stamp,
+                    value: key.clone(),
+                };
+                key
+            };
+            previous = Some(key);
+        }
+    }
+
+    /// Replace the notes with `text`, as a deletion and an insertion around the
+    /// unchanged start and end.
+    fn record_notes(&mut self, replica: &str, text: &str) {
+        let visible: Vec<usize> = self
+            .note_order()
+            .into_iter()
+            .filter(|i| !self.notes[*i].deleted)
+            .collect();
+        let old: Vec<char> = visible.iter().map(|i| self.notes[*i].ch).collect();
+        let new: Vec<char> = text.chars().collect();
+
+        let prefix = old.iter().zip(&new).take_while(|(a, b)| a == b).count();
+        let suffix = old[prefix..]
+            .iter()
+            .rev()
+            .zip(new[prefix..].iter().rev())
+            .take_while(|(a, b)| a == b)
+            .count();
+
+        for i in &visible[prefix..old.len() - suffix] {
+            self.notes[*i].deleted = true;
+        }
+
+        let mut after = prefix
+            .checked_sub(1)
+            .map(|p| self.notes[visible[p]].id.clone());
+        for ch in &new[prefix..new.len() - suffix] {
+            let id = self.tick(replica);
+            self.notes.push(NoteChar {
+                id: id.clone(),
+                after,
+                ch: *ch,
+                deleted: false,
+            });
+            after = Some(id);
+        }
+    }
+}
+
+/// Which items of `keys` form the longest strictly increasing run. Empty keys never do.
+fn longest_increasing(keys: &[String]) -> Vec<bool> {
+    let mut length = vec![0usize; keys.len()];
+    let mut previous: Vec<Option<usize>> = vec![None; keys.len()];
+    for i in 0..keys.len() {
+        if keys[i].is_empty() {
+            continue;
+        }
+        length[i] = 1;
+        for j in 0..i {
+            if length[j] > 0 && keys[j] < keys[i] && length[j] + 1 > length[i] {
+                length[i] = length[j] + 1;
+                previous[i] = Some(j);
+            }
+        }
+    }
+
+    let mut keep = vec![false; keys.len()];
+    let mut current = (0..keys.len())
+        .filter(|i| length[*i] > 0)
+        .max_by_key(|i| length[*i]);
+    while let Some(i) = current {
+        keep[i] = true;
+        current = previous[i];
+    }
+    keep
+}
+
+/// Whether `key` is a sort key `key_between` could have made: a non-empty string of
+/// `a`-`z` not ending in `a`.
+fn is_valid_key(key: &str) -> bool {
+    !key.is_empty() && key.bytes().all(|b| b.is_ascii_lowercase()) && !key.ends_with('a')
+}
+
+/// A sort key strictly between `low` and `high` (unbounded when `None`).
+///
+/// Keys are strings of `a`-`z` that never end in `a`, so there is always room for
+/// another key before or between existing ones. Both bounds must be valid keys.
+fn key_between(low: Option<&str>, high: Option<&str>) -> String {
+    let low: Vec<u8> = low.unwrap_or("").bytes().map(|b| b - b'a').collect();
+    let high: Option<Vec<u8>> = high.map(|h| h.bytes().map(|b| b - b'a').collect());
+    if let Some(high) = &high {
+        if low >= *high {
+            return key_between(Some(&digits_to_key(&low)), None);
+        }
+    }
+
+    let mut key = Vec::new();
+    let mut bounded = high.is_some();
+    for i in 0.. {
+        let l = low.get(i).copied().unwrap_or(0);
+        let h = match &high {
+            Some(high) if bounded => high.get(i).copied().unwrap_or(0),
+            _ => 26,
+        };
+        if h >= l + 2 {
+            key.push((l + h) / 2);
+            break;
+        }
+        key.push(l);
+        if h > l {
+            bounded = false;
+        }
+    }
+    digits_to_key(&key)
+}
+
+fn digits_to_key(digits: &[u8]) -> String {
+    digits.iter().map(|d| (b'a' + d) as char).collect()
+}
+
+fn replica_dir(data_dir: &Path, replica: &str) -> PathBuf {
+    data_dir.join(CRDT_DIR).join(replica)
+}
+
+fn document_path(data_dir: &Path, replica: &str, date: NaiveDate) -> PathBuf {
+    replica_dir(data_dir, replica).join(format!("{}.json", date.format("%Y-%m-%d")))
+}
+
+fn read_document(data_dir: &Path, path: &Path) -> Result<Option<DayDocument>, String> {
+    if !path.exists() {
+        return Ok(None);
+    }
+
+    let content =
+        fs::read_to_string(path).map_err(|e| format!("Failed to read day document: {}", e))?;
+    let content = crate::encryption::open(data_dir, &content)?;
+    serde_json::from_str(&content)
+        .map(Some)
+        .map_err(|e| format!("Failed to parse day document: {}", e))
+}
+
+fn write_document(data_dir: &Path, replica: &str, document: &DayDocument) -> Result<(), String> {
+    let dir = replica_dir(data_dir, replica);
+    fs::create_dir_all(&dir).map_err(|e| format!("Failed to create directory: {}", e))?;
+
+    let json_content = serde_json::to_string(document)
+        .map_err(|e| format!("Failed to serialize day document: {}", e))?;
+    let json_content = crate::encryption::seal(data_dir, &json_content)?;
+    let file_path = document_path(data_dir, replica, document.date);
+    let temp_path = file_path.with_extension("json.tmp");
+    fs::write(&temp_path, json_content).map_err(|e| format!("Failed to write file: {}", e))?;
+    fs::rename(&temp_path, &file_path).map_err(|e| format!("Failed to write file: {}", e))
+}
+
+/// IDs of every replica with a folder in the data directory.
+fn replicas(data_dir: &Path) -> Result<Vec<String>, String> {
+    let root = data_dir.join(CRDT_DIR);
+    if !root.exists() {
+        return Ok(Vec::new());
+    }
+
+    let entries =
+        fs::read_dir(&root).map_err(|e| format!("Failed to read day documents: {}", e))?;
+    Ok(entries
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| entry.path().is_dir())
+        .map(|entry| entry.file_name().to_string_lossy().to_string())
+        .collect())
+}
+
+fn load_settings(data_dir: &Path) -> Result<CrdtSettings, String> {
+    let file_path = data_dir.join(CRDT_SETTINGS_FILE);
+    if !file_path.exists() {
+        return Ok(CrdtSettings::default());
+    }
+
+    let content = fs::read_to_string(&file_path)
+        .map_err(|e| format!("Failed to read CRDT settings: {}", e))?;
+    serde_json::from_str(&content).map_err(|e| format!("Failed to parse CRDT settings: {}", e))
+}
+
+/// This device's replica ID, if CRDT sync is enabled for the data directory.
+fn active_replica(data_dir: &Path) -> Result<Option<&'static str>, String> {
+    match REPLICA_ID.get() {
+        Some(replica) if load_settings(data_dir)?.enabled => Ok(Some(replica)),
+        _ => Ok(None),
+    }
+}
+
+/// This device's replica ID, also identifying it to LAN peers.
+pub(crate) fn device_id() -> Option<&'static str> {
+    REPLICA_ID.get().map(String::as_str)
+}
+
+/// Load or create this device's replica ID, stored outside the (shared) data directory.
+pub fn init_replica_id(app: &tauri::AppHandle) -> Result<(), String> {
+    let (config_dir, _) = crate::workspaces::app_dirs(app)?;
+    let file_path = config_dir.join(REPLICA_ID_FILE);
+
+    let replica = match fs::read_to_string(&file_path) {
+        Ok(replica) if !replica.trim().is_empty() => replica.trim().to_string(),
+        _ => {
+            let replica = uuid::Uuid::new_v4().simple().to_string();
+            fs::create_dir_all(&config_dir)
+                .map_err(|e| format!("Failed to create config directory: {}", e))?;
+            fs::write(&file_path, &replica)
+                .map_err(|e| format!("Failed to write replica ID: {}", e))?;
+            replica
+        }
+    };
+
+    let _ = REPLICA_ID.set(replica);
+    Ok(())
+}
+
+/// Record a day file write in `replica`'s document.
+pub(crate) fn record_change(
+    data_dir: &Path,
+    replica: &str,
+    day_data: &DayData,
+) -> Result<(), String> {
+    let path = document_path(data_dir, replica, day_data.date);
+    let mut document =
+        read_document(data_dir, &path)?.unwrap_or_else(|| DayDocument::new(day_data.date));
+    let before = document.clone();
+
+    document.record(replica, day_data)?;
+    if document != before {
+        write_document(data_dir, replica, &document)?;
+    }
+    Ok(())
+}
+
+/// Record a day file write, if CRDT sync is enabled. Called by `write_day_file`.
+pub(crate) fn record_local_change(data_dir: &Path, day_data: &DayData) -> Result<(), String> {
+    match active_replica(data_dir)? {
+        Some(replica) => record_change(data_dir, replica, day_data),
+        None => Ok(()),
+    }
+}
+
+/// Merge every replica's document for a date into `replica`'s and rewrite the day
+/// file from the result.
+///
+/// # Returns
+/// Whether the day file changed.
+pub(crate) fn merge_day(data_dir: &Path, replica: &str, date: NaiveDate) -> Result<bool, String> {
+    let own_path = document_path(data_dir, replica, date);
+    let own = read_document(data_dir, &own_path)?;
+    let mut document = own.clone().unwrap_or_else(|| DayDocument::new(date));
+
+    let mut others = Vec::new();
+    for other in replicas(data_dir)?.iter().filter(|r| *r != replica) {
+        if let Some(theirs) = read_document(data_dir, &document_path(data_dir, other, date))? {
+            document.merge(&theirs);
+            others.push(theirs);
+        }
+    }
+    if own.is_none() && others.is_empty() {
+        return Ok(false);
+    }
+    document.compact_notes(&others);
+    if own.as_ref() != Some(&document) {
+        write_document(data_dir, replica, &document)?;
+    }
+
+    let merged = document.to_day_data()?;
+    let current = crate::read_day_file(data_dir, date)?;
+    let as_json = |day: &DayData| serde_json::to_value(day).ok();
+    if as_json(&merged) == as_json(&current) {
+        return Ok(false);
+    }
+
+    crate::store_day_file(data_dir, &merged)?;
+    Ok(true)
+}
+
+/// Merge a day's documents before it is read, if CRDT sync is enabled.
+pub(crate) fn merge_local_day(data_dir: &Path, date: NaiveDate) -> Result<(), String> {
+    if let Some(replica) = active_replica(data_dir)? {
+        merge_day(data_dir, replica, date)?;
+    }
+    Ok(())
+}
+
+/// Load the CRDT sync settings.
+///
+/// # Arguments
+/// * `data_dir` - Path to the app data directory
+///
+/// # Errors
+/// Returns an error if the settings file cannot be read.
+#[tauri::command]
+pub async fn load_crdt_settings(data_dir: String) -> Result<CrdtSettings, String> {
+    load_settings(Path::new(&data_dir))
+}
+
+/// Save the CRDT sync settings.
+///
+/// # Arguments
+/// * `settings` - The settings to store
+/// * `data_dir` - Path to the app data directory
+///
+/// # Errors
+/// Returns an error if the settings cannot be written.
+#[tauri::command]
+pub async fn save_crdt_settings(settings: CrdtSettings, data_dir: String) -> Result<(), String> {
+    let json_content = serde_json::to_string_pretty(&settings)
+        .map_err(|e| format!("Failed to serialize CRDT settings: {}", e))?;
+    fs::write(Path::new(&data_dir).join(CRDT_SETTINGS_FILE), json_content)
+        .map_err(|e| format!("Failed to write CRDT settings: {}", e))
+}
+
+/// Merge the day documents of all replicas for every day, e.g. after the shared
+/// folder finished syncing.
+///
+/// # Arguments
+/// * `data_dir` - Path to the app data directory
+///
+/// # Returns
+/// The dates whose day files changed.
+///
+/// # Errors
+/// Returns an error if CRDT sync is disabled or file operations fail.
+#[tauri::command]
+pub async fn merge_day_documents(data_dir: String) -> Result<Vec<NaiveDate>, String> {
+    crate::app_lock::ensure_unlocked()?;
+    let data_dir = Path::new(&data_dir);
+    let replica = active_replica(data_dir)?.ok_or("CRDT sync is not enabled")?;
+
+    let _guard = crate::DAY_FILES_LOCK.lock().await;
+    let mut dates: Vec<NaiveDate> = Vec::new();
+    for other in replicas(data_dir)? {
+        let entries = fs::read_dir(replica_dir(data_dir, &other))
+            .map_err(|e| format!("Failed to read day documents: {}", e))?;
+        dates.extend(entries.filter_map(|entry| entry.ok()).filter_map(|entry| {
+            let name = entry.file_name().to_string_lossy().to_string();
+            NaiveDate::parse_from_str(name.strip_suffix(".json")?, "%Y-%m-%d").ok()
+        }));
+    }
+    dates.sort_unstable();
+    dates.dedup();
+
+    let mut changed = Vec::new();
+    for date in dates {
+        if merge_day(data_dir, replica, date)? {
+            changed.push(date);
+        }
+    }
+    Ok(changed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{read_day_file, store_day_file, TodoStatus};
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_key_between() {
+        let first = key_between(None, None);
+        let before = key_between(None, Some(&first));
+        let after = key_between(Some(&first), None);
+        let middle = key_between(Some(&before), Some(&first));
+        assert!(before < middle && middle < first && first < after);
+
+        let mut low = first.clone();
+        for _ in 0..50 {
+            let key = key_between(Some(&low), Some(&first.replace('n', "o")));
+            assert!(key > low && !key.ends_with('a'));
+            low = key;
+        }
+    }
+
+    #[test]
+    fn test_invalid_keys_are_replaced() {
+        let date = NaiveDate::from_ymd_opt(2024, 1, 15).unwrap();
+        let mut day_data = DayData {
+            date,
+            todos: vec![
+                TodoItem::new("Email".to_string()),
+                TodoItem::new("Gym".to_string()),
+            ],
+            notes: String::new(),
+        };
+        let mut document = DayDocument::new(date);
+        document.record("laptop", &day_data).unwrap();
+        for (entry, key) in document.todos.values_mut().zip(["A", "n!"]) {
+            entry.position.value = key.to_string();
+        }
+
+        day_data.todos.reverse();
+        document.record("laptop", &day_data).unwrap();
+        let texts: Vec<String> = document
+            .to_day_data()
+            .unwrap()
+            .todos
+            .into_iter()
+            .map(|t| t.text)
+            .collect();
+        assert_eq!(texts, vec!["Gym", "Email"]);
+        assert!(document
+            .todos
+            .values()
+            .all(|entry| is_valid_key(&entry.position.value)));
+    }
+
+    #[test]
+    fn test_deleted_notes_are_compacted() {
+        let temp_dir = TempDir::new().unwrap();
+        let data_dir = temp_dir.path();
+        let date = NaiveDate::from_ymd_opt(2024, 1, 15).unwrap();
+        let note_count = |replica| {
+            read_document(data_dir, &document_path(data_dir, replica, date))
+                .unwrap()
+                .unwrap()
+                .notes
+                .len()
+        };
+
+        let mut day_data = DayData {
+            date,
+            todos: Vec::new(),
+            notes: "Hello world".to_string(),
+        };
+        record_change(data_dir, "laptop", &day_data).unwrap();
+        merge_day(data_dir, "desktop", date).unwrap();
+
+        // Kept while the desktop still shows the deleted text
+        day_data.notes = "Hello".to_string();
+        record_change(data_dir, "laptop", &day_data).unwrap();
+        merge_day(data_dir, "laptop", date).unwrap();
+        assert_eq!(note_count("laptop"), 11);
+
+        merge_day(data_dir, "desktop", date).unwrap();
+        merge_day(data_dir, "laptop", date).unwrap();
+        assert_eq!(note_count("desktop"), 5);
+        assert_eq!(note_count("laptop"), 5);
+        assert_eq!(read_day_file(data_dir, date).unwrap().notes, "Hello");
+
+        // Characters typed after a deleted one keep it
+        day_data.notes = "Jello".to_string();
+        record_change(data_dir, "laptop", &day_data).unwrap();
+        merge_day(data_dir, "desktop", date).unwrap();
+        merge_day(data_dir, "laptop", date).unwrap();
+        assert_eq!(note_count("laptop"), 6);
+        assert_eq!(read_day_file(data_dir, date).unwrap().notes, "Jello");
+    }
+
+    #[test]
+    fn test_two_replicas_converge() {
+        let temp_dir = TempDir::new().unwrap();
+        let data_dir = temp_dir.path();
+        let date = NaiveDate::from_ymd_opt(2024, 1, 15).unwrap();
+
+        let email = TodoItem::new("Email".to_string());
+        let gym = TodoItem::new("Gym".to_string());
+        let day_data = DayData {
+            date,
+            todos: vec![email.clone(), gym.clone()],
+            notes: "Standup at 10".to_string(),
+        };
+        record_change(data_dir, "laptop", &day_data).unwrap();
+        store_day_file(data_dir, &day_data).unwrap();
+        merge_day(data_dir, "desktop", date).unwrap();
+
+        // Concurrent edits: the laptop completes a todo and edits the start of the
+        // notes, the desktop deletes another, adds one and appends to the notes
+        let mut laptop = day_data.clone();
+        laptop.todos[0].status = TodoStatus::Done;
+        laptop.notes = "Standup at 9:30".to_string();
+        record_change(data_dir, "laptop", &laptop).unwrap();
+
+        let mut desktop = day_data.clone();
+        desktop.todos.retain(|t| t.id != gym.id);
+        desktop.todos.insert(0, TodoItem::new("Read".to_string()));
+        desktop.notes = "Standup at 10\nLunch with Sam".to_string();
+        record_change(data_dir, "desktop", &desktop).unwrap();
+
+        let mut results = Vec::new();
+        for replica in ["laptop", "desktop"] {
+            merge_day(data_dir, replica, date).unwrap();
+            results.push(serde_json::to_value(read_day_file(data_dir, date).unwrap()).unwrap());
+        }
+        assert_eq!(results[0], results[1]);
+
+        let merged = read_day_file(data_dir, date).unwrap();
+        let texts: Vec<&str> = merged.todos.iter().map(|t| t.text.as_str()).collect();
+        assert_eq!(texts, vec!["Read", "Email"]);
+        assert_eq!(merged.todos[1].status, TodoStatus::Done);
+        assert_eq!(merged.notes, "Standup at 9:30\nLunch with Sam");
+
+        // Merging in either order gives the same document
+        let read = |replica| {
+            read_document(data_dir, &document_path(data_dir, replica, date))
+                .unwrap()
+                .unwrap()
+        };
+        let (mut a, mut b) = (read("laptop"), read("desktop"));
+        let (a_copy, b_copy) = (a.clone(), b.clone());
+        a.merge(&b_copy);
+        b.merge(&a_copy);
+        assert_eq!(a, b);
+    }
+}
@@ -0,0 +1,583 @@
+//! Optional encryption at rest.
+//!
+//! With encryption enabled, day files and their history and day documents, collections,
+//! templates, recurrences, the trash and the dark mode and zoom preferences are stored as
+//! sealed envelopes: XChaCha20-Poly1305 with a key derived from the user's passphrase by
+//! Argon2id. The key is never written to disk; `unlock` derives it once and keeps it in
+//! memory for the rest of the session. Files written before encryption was enabled are
+//! still read as plain JSON.
+//!
+//! The salt and a passphrase check live in `encryption.json`, which is synced along with
+//! the data, so every device holding the passphrase derives the same key. While a
+//! passphrase change re-encrypts the files, `encryption.json` also holds the old key
+//! sealed with the new one, so files not yet rewritten stay readable; if the change is
+//! interrupted, the next `unlock` finishes it.
+
+use argon2::Argon2;
+use chacha20poly1305::aead::rand_core::RngCore;
+use chacha20poly1305::aead::{Aead, AeadCore, KeyInit, OsRng};
+use chacha20poly1305::{XChaCha20Poly1305, XNonce};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+/// File holding the salt and passphrase check
+const ENCRYPTION_FILE: &str = "encryption.json";
+
+/// How every sealed file starts, telling it apart from plain JSON
+const ENVELOPE_PREFIX: &str = "{\"sealed\":";
+
+/// Current envelope format
+const ENVELOPE_VERSION: u8 = 1;
+
+/// Plaintext sealed into the passphrase check
+const VERIFIER_TEXT: &str = "todo-notes-tracker";
+
+/// Top-level files holding notes or preferences, besides day files and collections
+const PROTECTED_FILES: &[&str] = &[
+    "dark_mode.json",
+    "zoom_level.json",
+    "future-log.json",
+    "recurrences.json",
+    "templates.json",
+    "trash.json",
+];
+
+const LOCKED_ERROR: &str = "Data is locked: unlock it with your passphrase first";
+
+type Key = [u8; 32];
+
+/// Keys of the unlocked data directories, with the salt they were derived with.
+static KEYS: Mutex<BTreeMap<PathBuf, (String, Key)>> = Mutex::new(BTreeMap::new());
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+struct EncryptionSettings {
+    enabled: bool,
+    /// Hex-encoded Argon2 salt
+    salt: String,
+    /// `VERIFIER_TEXT` sealed with the key, to check passphrases
+    verifier: String,
+    /// The hex-encoded key of the previous passphrase sealed with the current key, while
+    /// files are being re-encrypted after a passphrase change
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    previous_key: Option<String>,
+}
+
+/// A sealed file
+#[derive(Debug, Serialize, Deserialize)]
+struct Envelope {
+    sealed: u8,
+    nonce: String,
+    data: String,
+}
+
+/// Whether a data directory is encrypted and its key is in memory
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct EncryptionStatus {
+    pub enabled: bool,
+    pub unlocked: bool,
+}
+
+fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+fn from_hex(text: &str) -> Result<Vec<u8>, String> {
+    if !text.len().is_multiple_of(2) {
+        return Err("Invalid hex data".to_string());
+    }
+    (0..text.len())
+        .step_by(2)
+        .map(|i| {
+            u8::from_str_radix(&text[i..i + 2], 16).map_err(|_| "Invalid hex data".to_string())
+        })
+        .collect()
+}
+
+fn load_settings(data_dir: &Path) -> Result<EncryptionSettings, String> {
+    let file_path = data_dir.join(ENCRYPTION_FILE);
+    if !file_path.exists() {
+        return Ok(EncryptionSettings::default());
+    }
+
+    let content = fs::read_to_string(&file_path)
+        .map_err(|e| format!("Failed to read encryption settings: {}", e))?;
+    serde_json::from_str(&content)
+        .map_err(|e| format!("Failed to parse encryption settings: {}", e))
+}
+
+fn save_settings(data_dir: &Path, settings: &EncryptionSettings) -> Result<(), String> {
+    let json_content = serde_json::to_string_pretty(settings)
+        .map_err(|e| format!("Failed to serialize encryption settings: {}", e))?;
+    write_atomically(&data_dir.join(ENCRYPTION_FILE), &json_content)
+}
+
+fn write_atomically(path: &Path, content: &str) -> Result<(), String> {
+    let temp_path = path.with_extension("json.tmp");
+    fs::write(&temp_path, content).map_err(|e| format!("Failed to write file: {}", e))?;
+    fs::rename(&temp_path, path).map_err(|e| format!("Failed to write file: {}", e))
+}
+
+fn derive_key(passphrase: &str, salt: &str) -> Result<Key, String> {
+    let mut key = [0u8; 32];
+    Argon2::default()
+        .hash_password_into(passphrase.as_bytes(), &from_hex(salt)?, &mut key)
+        .map_err(|e| format!("Failed to derive key: {}", e))?;
+    Ok(key)
+}
+
+fn seal_with(key: &Key, plaintext: &str) -> Result<String, String> {
+    let cipher = XChaCha20Poly1305::new(key.into());
+    let nonce = XChaCha20Poly1305::generate_nonce(&mut OsRng);
+    let data = cipher
+        .encrypt(&nonce, plaintext.as_bytes())
+        .map_err(|_| "Failed to encrypt data".to_string())?;
+
+    let envelope = Envelope {
+        sealed: ENVELOPE_VERSION,
+        nonce: to_hex(&nonce),
+        data: to_hex(&data),
+    };
+    serde_json::to_string(&envelope).map_err(|e| format!("Failed to serialize data: {}", e))
+}
+
+fn open_with(key: &Key, content: &str) -> Result<String, String> {
+    let envelope: Envelope = serde_json::from_str(content)
+        .map_err(|e| format!("Failed to parse encrypted data: {}", e))?;
+    if envelope.sealed != ENVELOPE_VERSION {
+        return Err(format!("Unsupported encryption format {}", envelope.sealed));
+    }
+
+    let nonce = from_hex(&envelope.nonce)?;
+    if nonce.len() != 24 {
+        return Err("Invalid encrypted data".to_string());
+    }
+    let plaintext = XChaCha20Poly1305::new(key.into())
+        .decrypt(
+            XNonce::from_slice(&nonce),
+            from_hex(&envelope.data)?.as_slice(),
+        )
+        .map_err(|_| "Failed to decrypt data: wrong passphrase or damaged file".to_string())?;
+    String::from_utf8(plaintext).map_err(|e| format!("Failed to decrypt data: {}", e))
+}
+
+fn is_sealed(content: &str) -> bool {
+    content.trim_start().starts_with(ENVELOPE_PREFIX)
+}
+
+/// The cached key for a data directory, if it was derived with the current salt.
+fn cached_key(data_dir: &Path, settings: &EncryptionSettings) -> Option<Key> {
+    let keys = KEYS.lock().unwrap_or_else(|e| e.into_inner());
+    keys.get(data_dir)
+        .filter(|(salt, _)| *salt == settings.salt)
+        .map(|(_, key)| *key)
+}
+
+fn cache_key(data_dir: &Path, salt: &str, key: Key) {
+    let mut keys = KEYS.lock().unwrap_or_else(|e| e.into_inner());
+    keys.insert(data_dir.to_path_buf(), (salt.to_string(), key));
+}
+
+/// Drop every cached key, e.g. when the app locks.
+pub(crate) fn forget_keys() {
+    KEYS.lock().unwrap_or_else(|e| e.into_inner()).clear();
+}
+
+/// Whether encryption is enabled for a data directory.
+pub(crate) fn is_enabled(data_dir: &Path) -> Result<bool, String> {
+    Ok(load_settings(data_dir)?.enabled)
+}
+
+/// Check a passphrase against the data directory's settings, returning its key.
+fn verify_passphrase(settings: &EncryptionSettings, passphrase: &str) -> Result<Key, String> {
+    let key = derive_key(passphrase, &settings.salt)?;
+    match open_with(&key, &settings.verifier) {
+        Ok(text) if text == VERIFIER_TEXT => Ok(key),
+        _ => Err("Wrong passphrase".to_string()),
+    }
+}
+
+/// Fresh settings and key for a passphrase.
+fn new_key(passphrase: &str) -> Result<(EncryptionSettings, Key), String> {
+    if passphrase.is_empty() {
+        return Err("Passphrase cannot be empty".to_string());
+    }
+
+    let mut salt = [0u8; 16];
+    OsRng.fill_bytes(&mut salt);
+    let salt = to_hex(&salt);
+    let key = derive_key(passphrase, &salt)?;
+    let settings = EncryptionSettings {
+        enabled: true,
+        verifier: seal_with(&key, VERIFIER_TEXT)?,
+        salt,
+        previous_key: None,
+    };
+    Ok((settings, key))
+}
+
+/// Encrypt file contents if encryption is enabled for the data directory.
+///
+/// # Errors
+/// Returns an error if the data is encrypted but locked, so nothing is ever written in
+/// plain text by mistake.
+pub(crate) fn seal(data_dir: &Path, plaintext: &str) -> Result<String, String> {
+    let settings = load_settings(data_dir)?;
+    if !settings.enabled {
+        return Ok(plaintext.to_string());
+    }
+
+    let key = cached_key(data_dir, &settings).ok_or(LOCKED_ERROR)?;
+    seal_with(&key, plaintext)
+}
+
+/// Decrypt file contents if they are sealed; plain JSON is returned unchanged.
+///
+/// # Errors
+/// Returns an error if the data is locked or the contents can't be decrypted.
+pub(crate) fn open(data_dir: &Path, content: &str) -> Result<String, String> {
+    if !is_sealed(content) {
+        return Ok(content.to_string());
+    }
+
+    let settings = load_settings(data_dir)?;
+    let key = cached_key(data_dir, &settings).ok_or(LOCKED_ERROR)?;
+    match (open_with(&key, content), &settings.previous_key) {
+        (Err(_), Some(previous_key)) => open_with(&unseal_key(&key, previous_key)?, content),
+        (result, _) => result,
+    }
+}
+
+/// Recover the previous passphrase's key, sealed with the current key.
+fn unseal_key(key: &Key, sealed: &str) -> Result<Key, String> {
+    from_hex(&open_with(key, sealed)?)?
+        .try_into()
+        .map_err(|_| "Invalid encryption settings".to_string())
+}
+
+/// Switch to a new passphrase, keeping the old key readable until every file has been
+/// re-encrypted by `finish_passphrase_change`.
+fn start_passphrase_change(
+    data_dir: &Path,
+    old_key: &Key,
+    new_passphrase: &str,
+) -> Result<Key, String> {
+    let (mut settings, key) = new_key(new_passphrase)?;
+    settings.previous_key = Some(seal_with(&key, &to_hex(old_key))?);
+    save_settings(data_dir, &settings)?;
+    cache_key(data_dir, &settings.salt, key);
+    Ok(key)
+}
+
+/// Re-encrypt every file still sealed with the previous key, then forget that key.
+/// Callers must hold `DAY_FILES_LOCK`.
+fn finish_passphrase_change(data_dir: &Path, key: &Key) -> Result<(), String> {
+    let mut settings = load_settings(data_dir)?;
+    let Some(previous_key) = &settings.previous_key else {
+        return Ok(());
+    };
+    let old_key = unseal_key(key, previous_key)?;
+
+    let mut files = Vec::new();
+    all_files(data_dir, &mut files)?;
+    for path in files {
+        if path.extension().is_none_or(|ext| ext != "json") {
+            continue;
+        }
+        let content =
+            fs::read_to_string(&path).map_err(|e| format!("Failed to read file: {}", e))?;
+        if is_sealed(&content) && open_with(key, &content).is_err() {
+            if let Ok(plaintext) = open_with(&old_key, &content) {
+                write_atomically(&path, &seal_with(key, &plaintext)?)?;
+            }
+        }
+    }
+
+    settings.previous_key = None;
+    save_settings(data_dir, &settings)
+}
+
+/// Every file under `dir`, except git's own.
+fn all_files(dir: &Path, files: &mut Vec<PathBuf>) -> Result<(), String> {
+    let entries = fs::read_dir(dir).map_err(|e| format!("Failed to read directory: {}", e))?;
+    for entry in entries.filter_map(|entry| entry.ok()) {
+        let path = entry.path();
+        if path.is_dir() {
+            if entry.file_name() != ".git" {
+                all_files(&path, files)?;
+            }
+        } else {
+            files.push(path);
+        }
+    }
+    Ok(())
+}
+
+/// Whether a file in the data directory (or a snapshot of it) holds notes or preferences.
+fn is_protected(data_dir: &Path, path: &Path) -> bool {
+    let Some(name) = path.file_name().and_then(|name| name.to_str()) else {
+        return false;
+    };
+    let Some(stem) = name.strip_suffix(".json") else {
+        return false;
+    };
+
+    let in_day_folder = path
+        .strip_prefix(data_dir)
+        .map(|relative| {
+            relative.components().any(|c| {
+                c.as_os_str() == crate::history::HISTORY_DIR
+                    || c.as_os_str() == crate::crdt::CRDT_DIR
+            })
+        })
+        .unwrap_or(false);
+
+    in_day_folder
+        || chrono::NaiveDate::parse_from_str(stem, "%Y-%m-%d").is_ok()
+        || PROTECTED_FILES.contains(&name)
+        || name.starts_with("month-")
+        || name.starts_with("collection-")
+}
+
+/// Whether the data directory is encrypted and whether it is unlocked.
+///
+/// # Arguments
+/// * `data_dir` - Path to the app data directory
+///
+/// # Errors
+/// Returns an error if the encryption settings cannot be read.
+#[tauri::command]
+pub async fn load_encryption_status(data_dir: String) -> Result<EncryptionStatus, String> {
+    let data_dir = Path::new(&data_dir);
+    let settings = load_settings(data_dir)?;
+    Ok(EncryptionStatus {
+        enabled: settings.enabled,
+        unlocked: settings.enabled && cached_key(data_dir, &settings).is_some(),
+    })
+}
+
+/// Turn on encryption and encrypt every existing file holding notes or preferences.
+///
+/// # Arguments
+/// * `passphrase` - Passphrase to derive the key from
+/// * `data_dir` - Path to the app data directory
+///
+/// # Errors
+/// Returns an error if encryption is already enabled, the passphrase is empty, or
+/// files cannot be read or written.
+#[tauri::command]
+pub async fn enable_encryption(passphrase: String, data_dir: String) -> Result<(), String> {
+    let data_dir = Path::new(&data_dir);
+    let _guard = crate::DAY_FILES_LOCK.lock().await;
+    if load_settings(data_dir)?.enabled {
+        return Err("Encryption is already enabled".to_string());
+    }
+
+    let (settings, key) = new_key(&passphrase)?;
+    save_settings(data_dir, &settings)?;
+    cache_key(data_dir, &settings.salt, key);
+
+    // Files written from here on are sealed; a crash part way leaves readable plain files
+    let mut files = Vec::new();
+    all_files(data_dir, &mut files)?;
+    for path in files.iter().filter(|path| is_protected(data_dir, path)) {
+        let content =
+            fs::read_to_string(path).map_err(|e| format!("Failed to read file: {}", e))?;
+        if !is_sealed(&content) {
+            write_atomically(path, &seal_with(&key, &content)?)?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Unlock the data directory for this session, lifting the app lock.
+///
+/// Also finishes a passphrase change that was interrupted part way.
+///
+/// # Arguments
+/// * `passphrase` - The passphrase encryption was enabled with
+/// * `data_dir` - Path to the app data directory
+///
+/// # Errors
+/// Returns an error if encryption is not enabled or the passphrase is wrong.
+#[tauri::command]
+pub async fn unlock(passphrase: String, data_dir: String) -> Result<(), String> {
+    let data_dir = Path::new(&data_dir);
+    let settings = load_settings(data_dir)?;
+    if !settings.enabled {
+        return Err("Encryption is not enabled".to_string());
+    }
+
+    let key = verify_passphrase(&settings, &passphrase)?;
+    cache_key(data_dir, &settings.salt, key);
+    if settings.previous_key.is_some() {
+        let _guard = crate::DAY_FILES_LOCK.lock().await;
+        finish_passphrase_change(data_dir, &key)?;
+    }
+    crate::app_lock::mark_unlocked();
+    // The startup rollover can't read encrypted days before this
+    crate::rollover::retry();
+    Ok(())
+}
+
+/// Change the passphrase, re-encrypting every encrypted file with the new key.
+///
+/// Everything is decrypted before anything is rewritten, so a file that can't be
+/// decrypted aborts the change without touching the others. The new key is saved
+/// before the files are rewritten, along with the old key sealed with it, so a change
+/// that fails part way leaves every file readable and is finished by the next
+/// `unlock`.
+///
+/// # Arguments
+/// * `old_passphrase` - The current passphrase
+/// * `new_passphrase` - The passphrase to switch to
+/// * `data_dir` - Path to the app data directory
+///
+/// # Errors
+/// Returns an error if encryption is not enabled, the current passphrase is wrong, the
+/// new one is empty, or files cannot be read, decrypted or written.
+#[tauri::command]
+pub async fn change_passphrase(
+    old_passphrase: String,
+    new_passphrase: String,
+    data_dir: String,
+) -> Result<(), String> {
+    let data_dir = Path::new(&data_dir);
+    let _guard = crate::DAY_FILES_LOCK.lock().await;
+    let settings = load_settings(data_dir)?;
+    if !settings.enabled {
+        return Err("Encryption is not enabled".to_string());
+    }
+    let old_key = verify_passphrase(&settings, &old_passphrase)?;
+
+    if settings.previous_key.is_some() {
+        finish_passphrase_change(data_dir, &old_key)?;
+    }
+
+    let mut files = Vec::new();
+    all_files(data_dir, &mut files)?;
+    for path in files {
+        if path.extension().is_none_or(|ext| ext != "json") {
+            continue;
+        }
+        let content =
+            fs::read_to_string(&path).map_err(|e| format!("Failed to read file: {}", e))?;
+        if is_sealed(&content) {
+            open_with(&old_key, &content).map_err(|e| format!("{}: {}", path.display(), e))?;
+        }
+    }
+
+    let new_key = start_passphrase_change(data_dir, &old_key, &new_passphrase)?;
+    finish_passphrase_change(data_dir, &new_key)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{read_day_file, write_day_file, DayData, TodoItem};
+    use chrono::NaiveDate;
+    use tempfile::TempDir;
+
+    fn forget_key(data_dir: &Path) {
+        KEYS.lock().unwrap().remove(data_dir);
+    }
+
+    fn sample_day(title: &str) -> DayData {
+        DayData {
+            date: NaiveDate::from_ymd_opt(2024, 1, 15).unwrap(),
+            todos: vec![TodoItem::new(title.to_string())],
+            notes: "Call Acme Corp".to_string(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_enable_encrypts_and_unlock_decrypts() {
+        let temp_dir = TempDir::new().unwrap();
+        let dir = temp_dir.path();
+        let dir_str = dir.to_string_lossy().to_string();
+        let day = sample_day("Email Acme");
+        write_day_file(dir, &day).unwrap();
+        fs::write(dir.join("zoom_level.json"), "{\"zoom_level\": 1.2}").unwrap();
+
+        enable_encryption("correct horse".to_string(), dir_str.clone())
+            .await
+            .unwrap();
+        for name in ["2024-01-15.json", "zoom_level.json"] {
+            let on_disk = fs::read_to_string(dir.join(name)).unwrap();
+            assert!(is_sealed(&on_disk));
+            assert!(!on_disk.contains("Acme") && !on_disk.contains("zoom_level"));
+        }
+        assert_eq!(read_day_file(dir, day.date).unwrap().notes, day.notes);
+
+        // A later save is sealed too, and its history revision with it
+        let mut changed = day.clone();
+        changed.notes = "Call Acme Corp back".to_string();
+        write_day_file(dir, &changed).unwrap();
+        let revision = dir.join("history/2024-01-15/000001.json");
+        assert!(is_sealed(&fs::read_to_string(revision).unwrap()));
+
+        forget_key(dir);
+        let status = load_encryption_status(dir_str.clone()).await.unwrap();
+        assert!(status.enabled && !status.unlocked);
+        assert!(read_day_file(dir, day.date).is_err());
+        assert!(write_day_file(dir, &day).is_err());
+
+        let wrong = unlock("wrong".to_string(), dir_str.clone()).await;
+        assert_eq!(wrong, Err("Wrong passphrase".to_string()));
+        unlock("correct horse".to_string(), dir_str).await.unwrap();
+        assert_eq!(read_day_file(dir, day.date).unwrap().notes, changed.notes);
+    }
+
+    #[tokio::test]
+    async fn test_change_passphrase_reencrypts() {
+        let temp_dir = TempDir::new().unwrap();
+        let dir = temp_dir.path();
+        let dir_str = dir.to_string_lossy().to_string();
+        let day = sample_day("Email Acme");
+        enable_encryption("old".to_string(), dir_str.clone())
+            .await
+            .unwrap();
+        write_day_file(dir, &day).unwrap();
+
+        let wrong = change_passphrase("nope".to_string(), "new".to_string(), dir_str.clone());
+        assert!(wrong.await.is_err());
+        change_passphrase("old".to_string(), "new".to_string(), dir_str.clone())
+            .await
+            .unwrap();
+        assert_eq!(read_day_file(dir, day.date).unwrap().todos.len(), 1);
+
+        forget_key(dir);
+        assert!(unlock("old".to_string(), dir_str.clone()).await.is_err());
+        unlock("new".to_string(), dir_str).await.unwrap();
+        assert_eq!(read_day_file(dir, day.date).unwrap().notes, day.notes);
+        assert!(load_settings(dir).unwrap().previous_key.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_interrupted_passphrase_change_is_finished_on_unlock() {
+        let temp_dir = TempDir::new().unwrap();
+        let dir = temp_dir.path();
+        let dir_str = dir.to_string_lossy().to_string();
+        let day = sample_day("Email Acme");
+        enable_encryption("old".to_string(), dir_str.clone())
+            .await
+            .unwrap();
+        write_day_file(dir, &day).unwrap();
+
+        // Stop after the new key is saved, before any file is rewritten
+        let old_key = verify_passphrase(&load_settings(dir).unwrap(), "old").unwrap();
+        let new_key = start_passphrase_change(dir, &old_key, "new").unwrap();
+        let on_disk = fs::read_to_string(dir.join("2024-01-15.json")).unwrap();
+        assert!(open_with(&new_key, &on_disk).is_err());
+        assert_eq!(read_day_file(dir, day.date).unwrap().notes, day.notes);
+
+        forget_key(dir);
+        unlock("new".to_string(), dir_str).await.unwrap();
+        let on_disk = fs::read_to_string(dir.join("2024-01-15.json")).unwrap();
+        assert!(open_with(&new_key, &on_disk).is_ok());
+        assert!(load_settings(dir).unwrap().previous_key.is_none());
+    }
+}
@@ -0,0 +1,177 @@
+//! Plain-text export of day data using bullet journal signifiers.
+
+use crate::{read_day_file, DayData, TodoItem, TodoStatus};
+use chrono::NaiveDate;
+use std::path::Path;
+
+/// Render a single todo as a Markdown list entry with its signifier.
+fn render_todo(todo: &TodoItem) -> String {
+    let mut entry = format!("{} {}", todo.status.signifier(), todo.text);
+    for tag in &todo.tags {
+        entry.push_str(&format!(" #{}", tag));
+    }
+    if let Some(project) = &todo.project {
+        entry.push_str(&format!(" +{}", project));
+    }
+    if !todo.priority.is_none() {
+        entry.push_str(&format!(" {}", todo.priority.marker()));
+    }
+    let mut line = match todo.status {
+        TodoStatus::Cancelled => format!("- ~~{}~~", entry),
+        _ => format!("- {}", entry),
+    };
+
+    if let Some(deadline) = todo.deadline {
+        line.push_str(&format!(" (due {}", deadline.date.format("%Y-%m-%d")));
+        if let Some(time) = deadline.time {
+            line.push_str(&format!(" {}", time.format("%H:%M")));
+        }
+        line.push(')');
+    }
+
+    if let Some(to_date) = todo.migrated_to {
+        line.push_str(&format!(" (→ {})", to_date.format("%Y-%m-%d")));
+    }
+
+    for note_line in todo.notes.lines().filter(|l| !l.trim().is_empty()) {
+        line.push_str(&format!("\n  > {}", note_line));
+    }
+
+    for subtask in &todo.subtasks {
+        let check = if subtask.completed { 'x' } else { ' ' };
+        line.push_str(&format!("\n  - [{}] {}", check, subtask.text));
+    }
+
+    line
+}
+
+/// Render a day as Markdown: a heading, the todo list and the day's notes.
+pub(crate) fn render_day_markdown(day_data: &DayData) -> String {
+    let mut output = format!("# {}\n", day_data.date.format("%Y-%m-%d"));
+
+    if !day_data.todos.is_empty() {
+        output.push('\n');
+        for todo in &day_data.todos {
+            output.push_str(&render_todo(todo));
+            output.push('\n');
+        }
+    }
+
+    if !day_data.notes.trim().is_empty() {
+        output.push_str("\n## Notes\n\n");
+        output.push_str(day_data.notes.trim_end());
+        output.push('\n');
+    }
+
+    output
+}
+
+/// Export a day as Markdown with bullet journal signifiers.
+///
+/// Open tasks are rendered as `•`, done as `×`, migrated as `>`, scheduled as `<`,
+/// and cancelled tasks are struck through.
+///
+/// # Arguments
+/// * `date` - Date string in YYYY-MM-DD format
+/// * `data_dir` - Path to the app data directory
+///
+/// # Returns
+/// The rendered Markdown document.
+///
+/// # Errors
+/// Returns an error if the date is invalid or the day file cannot be read.
+#[tauri::command]
+pub async fn export_day_markdown(date: String, data_dir: String) -> Result<String, String> {
+    crate::app_lock::ensure_unlocked()?;
+    let date = NaiveDate::parse_from_str(&date, "%Y-%m-%d")
+        .map_err(|e| format!("Invalid date format: {}", e))?;
+
+    let day_data = read_day_file(Path::new(&data_dir), date)?;
+    Ok(render_day_markdown(&day_data))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Subtask;
+
+    fn todo(text: &str, status: TodoStatus) -> TodoItem {
+        TodoItem {
+            status,
+            ..TodoItem::new(text.to_string())
+        }
+    }
+
+    #[test]
+    fn test_render_day_markdown_signifiers() {
+        let to_date = NaiveDate::from_ymd_opt(2024, 1, 16).unwrap();
+        let day_data = DayData {
+            date: NaiveDate::from_ymd_opt(2024, 1, 15).unwrap(),
+            todos: vec![
+                todo("Write report", TodoStatus::Open),
+                todo("Call dentist", TodoStatus::Done),
+                todo("Fix bug", TodoStatus::Open).moved_ghost(TodoStatus::Migrated, Some(to_date)),
+                todo("Book venue", TodoStatus::Scheduled),
+                todo("Old idea", TodoStatus::Cancelled),
+            ],
+            notes: "Good day".to_string(),
+        };
+
+        let markdown = render_day_markdown(&day_data);
+
+        assert_eq!(
+            markdown,
+            "# 2024-01-15\n\n\
+             - • Write report\n\
+             - × Call dentist\n\
+             - > Fix bug (→ 2024-01-16)\n\
+             - < Book venue\n\
+             - ~~• Old idea~~\n\
+             \n## Notes\n\nGood day\n"
+        );
+    }
+
+    #[test]
+    fn test_render_todo_notes_are_indented() {
+        let mut item = todo("Plan trip", TodoStatus::Open);
+        item.notes = "Flights\n\nHotel".to_string();
+
+        assert_eq!(render_todo(&item), "- • Plan trip\n  > Flights\n  > Hotel");
+    }
+
+    #[test]
+    fn test_render_todo_tags_project_and_deadline() {
+        let item = crate::tags::todo_from_input("Ship it +launch !! #work".to_string());
+
+        assert_eq!(render_todo(&item), "- • Ship it #work +launch !!");
+
+        let item = TodoItem {
+            deadline: Some(crate::Deadline {
+                date: NaiveDate::from_ymd_opt(2024, 1, 19).unwrap(),
+                time: chrono::NaiveTime::from_hms_opt(17, 0, 0),
+            }),
+            ..item
+        };
+        assert_eq!(
+            render_todo(&item),
+            "- • Ship it #work +launch !! (due 2024-01-19 17:00)"
+        );
+    }
+
+    #[test]
+    fn test_render_todo_subtasks_are_indented() {
+        let mut item = todo("Release 2.0", TodoStatus::Open);
+        item.subtasks = vec![
+            Subtask {
+                completed: true,
+                ..Subtask::new("Tag".to_string())
+            },
+            Subtask::new("Announce".to_string()),
+        ];
+
+        assert_eq!(
+            render_todo(&item),
+            "- • Release 2.0\n  - [x] Tag\n  - [ ] Announce"
+        );
+    }
+}
@@ -0,0 +1,723 @@
+//! Git-backed history and sync of the data directory.
+//!
+//! When enabled, the data directory is a git repository. Saves are committed in
+//! batches by a background task, and `sync_now` pulls from and pushes to a configured
+//! remote. Merge conflicts in JSON files are resolved per todo ID: changes to
+//! different todos on the same day are both kept, and when both sides changed the
+//! same field of the same todo, the local version wins. Uses the `git` command line
+//! tool, which must be installed.
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::fs;
+use std::path::Path;
+use std::process::Command;
+
+/// Preference file holding the git sync settings (not committed)
+const GIT_SYNC_SETTINGS_FILE: &str = "git_sync.json";
+
+/// How often the background task commits pending changes
+const COMMIT_INTERVAL: std::time::Duration = std::time::Duration::from_secs(60);
+
+/// Keeps two syncs from running at the same time, since fetching and pushing happen
+/// without `DAY_FILES_LOCK`
+static SYNC_LOCK: tokio::sync::Mutex<()> = tokio::sync::Mutex::const_new(());
+
+/// First line of a `.gitignore` written by the app
+const GITIGNORE_HEADER: &str = "# Managed by Todo Notes Tracker\n";
+
+/// Directories and temporary files that stay local to each machine, besides
+/// [`crate::LOCAL_ONLY_FILES`]
+const LOCAL_ONLY_PATTERNS: &[&str] = &["history/", "snapshots/", "webdav_base/", "*.tmp"];
+
+/// Everything that stays local to each machine, one `.gitignore` pattern each.
+fn gitignore_entries() -> impl Iterator<Item = &'static str> {
+    crate::LOCAL_ONLY_FILES
+        .iter()
+        .chain(LOCAL_ONLY_PATTERNS)
+        .copied()
+}
+
+fn default_branch() -> String {
+    "main".to_string()
+}
+
+/// Git sync settings
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct GitSyncSettings {
+    /// Whether the data directory is committed to git
+    #[serde(default)]
+    pub enabled: bool,
+    /// URL or path of the remote repository, if syncing
+    #[serde(default)]
+    pub remote: Option<String>,
+    #[serde(default = "default_branch")]
+    pub branch: String,
+}
+
+impl Default for GitSyncSettings {
+    fn default() -> Self {
+        GitSyncSettings {
+            enabled: false,
+            remote: None,
+            branch: default_branch(),
+        }
+    }
+}
+
+/// What a call to `sync_now` did
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct SyncReport {
+    /// Whether local changes were committed before syncing
+    pub committed: bool,
+    /// Whether changes from the remote were merged
+    pub pulled: bool,
+    /// Files whose merge conflicts were resolved automatically
+    pub resolved: Vec<String>,
+    pub pushed: bool,
+}
+
+fn load_settings(data_dir: &Path) -> Result<GitSyncSettings, String> {
+    let file_path = data_dir.join(GIT_SYNC_SETTINGS_FILE);
+    if !file_path.exists() {
+        return Ok(GitSyncSettings::default());
+    }
+
+    let content = fs::read_to_string(&file_path)
+        .map_err(|e| format!("Failed to read git sync settings: {}", e))?;
+    serde_json::from_str(&content).map_err(|e| format!("Failed to parse git sync settings: {}", e))
+}
+
+/// Run git in the data directory and return its standard output.
+fn git(repo: &Path, args: &[&str]) -> Result<String, String> {
+    let output = Command::new("git")
+        .arg("-C")
+        .arg(repo)
+        .args(args)
+        .env("GIT_TERMINAL_PROMPT", "0")
+        .output()
+        .map_err(|e| format!("Failed to run git: {}", e))?;
+
+    if !output.status.success() {
+        return Err(format!(
+            "git {} failed: {}",
+            args[0],
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).to_string())
+}
+
+/// Add the [`gitignore_entries`] missing from the data directory's `.gitignore`, e.g.
+/// ones introduced after the repository was set up, and stop tracking files they
+/// cover that were committed before.
+fn update_gitignore(data_dir: &Path) -> Result<(), String> {
+    let gitignore = data_dir.join(".gitignore");
+    let mut content = if gitignore.exists() {
+        fs::read_to_string(&gitignore).map_err(|e| format!("Failed to read .gitignore: {}", e))?
+    } else {
+        GITIGNORE_HEADER.to_string()
+    };
+
+    let present: Vec<&str> = content.lines().map(str::trim).collect();
+    let missing: Vec<&str> = gitignore_entries()
+        .filter(|entry| !present.contains(entry))
+        .collect();
+    if missing.is_empty() {
+        return Ok(());
+    }
+
+    if !content.is_empty() && !content.ends_with('\n') {
+        content.push('\n');
+    }
+    for entry in &missing {
+        content.push_str(entry);
+        content.push('\n');
+    }
+    fs::write(&gitignore, content).map_err(|e| format!("Failed to write .gitignore: {}", e))?;
+
+    for entry in missing {
+        git(
+            data_dir,
+            &[
+                "rm",
+                "-r",
+                "-q",
+                "--cached",
+                "--ignore-unmatch",
+                "--",
+                entry.trim_matches('/'),
+            ],
+        )?;
+    }
+    Ok(())
+}
+
+/// Turn the data directory into a repository set up for `settings`, if it isn't one.
+fn init_repo(data_dir: &Path, settings: &GitSyncSettings) -> Result<(), String> {
+    if !data_dir.join(".git").exists() {
+        git(data_dir, &["init", "-q"])?;
+        let head = format!("refs/heads/{}", settings.branch);
+        git(data_dir, &["symbolic-ref", "HEAD", &head])?;
+    }
+
+    // Let every conflicting JSON change reach resolve_conflicts instead of a line
+    // merge, which can produce invalid JSON
+    let info_dir = data_dir.join(".git").join("info");
+    fs::create_dir_all(&info_dir).map_err(|e| format!("Failed to configure git: {}", e))?;
+    fs::write(info_dir.join("attributes"), "*.json -merge\n")
+        .map_err(|e| format!("Failed to configure git: {}", e))?;
+
+    update_gitignore(data_dir)?;
+
+    // Commits need an author; don't depend on a global git config
+    if git(data_dir, &["config", "user.email"]).is_err() {
+        git(data_dir, &["config", "user.name", "Todo Notes Tracker"])?;
+        git(
+            data_dir,
+            &["config", "user.email", "todo-notes-tracker@localhost"],
+        )?;
+    }
+
+    if let Some(remote) = &settings.remote {
+        if git(data_dir, &["remote", "get-url", "origin"]).is_ok() {
+            git(data_dir, &["remote", "set-url", "origin", remote])?;
+        } else {
+            git(data_dir, &["remote", "add", "origin", remote])?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Commit everything that changed since the last commit.
+///
+/// # Returns
+/// Whether a commit was made.
+fn commit_pending(data_dir: &Path) -> Result<bool, String> {
+    git(data_dir, &["add", "-A"])?;
+    let status = git(data_dir, &["status", "--porcelain"])?;
+
+    let changed: Vec<&str> = status
+        .lines()
+        .filter_map(|line| line.get(3..))
+        .map(|path| path.trim_end_matches(".json"))
+        .collect();
+    if changed.is_empty() {
+        return Ok(false);
+    }
+
+    let mut message = format!(
+        "Update {}",
+        changed
+            .iter()
+            .take(5)
+            .cloned()
+            .collect::<Vec<_>>()
+            .join(", ")
+    );
+    if changed.len() > 5 {
+        message.push_str(&format!(" and {} more", changed.len() - 5));
+    }
+    git(data_dir, &["commit", "-q", "-m", &message])?;
+    Ok(true)
+}
+
+/// Three-way merge of JSON values, `None` meaning absent.
+///
+/// A side that didn't change from `base` takes the other side's version, including a
+/// deletion. Objects are merged key by key, arrays of objects with an `id` are merged
+/// by ID, and notes edited on both sides keep both edits. Anything else changed on both
+/// sides keeps `ours`; a value deleted on one side but changed on the other is kept.
+pub(crate) fn merge_value(
+    base: Option<&Value>,
+    ours: Option<&Value>,
+    theirs: Option<&Value>,
+) -> Option<Value> {
+    if ours == theirs || base == theirs {
+        return ours.cloned();
+    }
+    if base == ours {
+        return theirs.cloned();
+    }
+
+    match (ours, theirs) {
+        (Some(Value::Object(ours)), Some(Value::Object(theirs))) => {
+            let base = base.and_then(Value::as_object);
+            let mut merged = serde_json::Map::new();
+            for key in ours
+                .keys()
+                .chain(theirs.keys().filter(|k| !ours.contains_key(*k)))
+            {
+                let base_value = base.and_then(|b| b.get(key));
+                let value = match (key.as_str(), ours.get(key), theirs.get(key)) {
+                    ("notes", Some(Value::String(o)), Some(Value::String(t))) => {
+                        let b = base_value.and_then(Value::as_str).unwrap_or("");
+                        Some(Value::String(merge_notes(b, o, t)))
+                    }
+                    (_, o, t) => merge_value(base_value, o, t),
+                };
+                if let Some(value) = value {
+                    merged.insert(key.clone(), value);
+                }
+            }
+            Some(Value::Object(merged))
+        }
+        (Some(Value::Array(ours)), Some(Value::Array(theirs)))
+            if ours
+                .iter()
+                .chain(theirs.iter())
+                .all(|v| item_id(v).is_some()) =>
+        {
+            let base = base
+                .and_then(Value::as_array)
+                .map(Vec::as_slice)
+                .unwrap_or(&[]);
+            Some(Value::Array(merge_by_id(base, ours, theirs)))
+        }
+        (Some(value), _) | (None, Some(value)) => Some(value.clone()),
+        (None, None) => None,
+    }
+}
+
+fn item_id(value: &Value) -> Option<&str> {
+    value.get("id").and_then(Value::as_str)
+}
+
+/// Merge lists of todos (or subtasks) item by item.
+///
+/// Keeps the local order; items only the remote has are placed after the item that
+/// precedes them remotely.
+fn merge_by_id(base: &[Value], ours: &[Value], theirs: &[Value]) -> Vec<Value> {
+    let find = |items: &[Value], id: &str| items.iter().find(|v| item_id(v) == Some(id)).cloned();
+
+    let mut order: Vec<&str> = ours.iter().filter_map(item_id).collect();
+    for (i, id) in theirs.iter().filter_map(item_id).enumerate() {
+        if order.contains(&id) {
+            continue;
+        }
+        let position = theirs[..i]
+            .iter()
+            .rev()
+            .filter_map(item_id)
+            .find_map(|prev| order.iter().position(|o| *o == prev))
+            .map_or(0, |p| p + 1);
+        order.insert(position, id);
+    }
+
+    order
+        .into_iter()
+        .filter_map(|id| {
+            merge_value(
+                find(base, id).as_ref(),
+                find(ours, id).as_ref(),
+                find(theirs, id).as_ref(),
+            )
+        })
+        .collect()
+}
+
+/// Combine notes edited on both sides. Additions after the common text are appended.
+fn merge_notes(base: &str, ours: &str, theirs: &str) -> String {
+    if ours == theirs || theirs == base {
+        return ours.to_string();
+    }
+    if ours == base {
+        return theirs.to_string();
+    }
+
+    match (ours.strip_prefix(base), theirs.strip_prefix(base)) {
+        (Some(_), Some(theirs_added)) if !base.is_empty() => format!("{}{}", ours, theirs_added),
+        _ => format!("{}\n\n{}", ours, theirs),
+    }
+}
+
+/// Resolve the conflicted files of an interrupted merge and commit it.
+fn resolve_conflicts(data_dir: &Path) -> Result<Vec<String>, String> {
+    let conflicted = git(data_dir, &["diff", "--name-only", "--diff-filter=U"])?;
+    let paths: Vec<String> = conflicted.lines().map(str::to_string).collect();
+
+    for path in &paths {
+        if !path.ends_with(".json") {
+            git(data_dir, &["checkout", "--ours", "--", path])?;
+            git(data_dir, &["add", "--", path])?;
+            continue;
+        }
+
+        let stage = |n: u8| -> Result<Option<Value>, String> {
+            match git(data_dir, &["show", &format!(":{}:{}", n, path)]) {
+                Ok(content) => serde_json::from_str(&crate::encryption::open(data_dir, &content)?)
+                    .map(Some)
+                    .map_err(|e| format!("Failed to parse {}: {}", path, e)),
+                Err(_) => Ok(None),
+            }
+        };
+        let merged = merge_value(stage(1)?.as_ref(), stage(2)?.as_ref(), stage(3)?.as_ref());
+
+        match merged {
+            Some(value) => {
+                let json_content = serde_json::to_string_pretty(&value)
+                    .map_err(|e| format!("Failed to serialize {}: {}", path, e))?;
+                let json_content = crate::encryption::seal(data_dir, &json_content)?;
+                fs::write(data_dir.join(path), json_content)
+                    .map_err(|e| format!("Failed to write {}: {}", path, e))?;
+                git(data_dir, &["add", "--", path])?;
+            }
+            None => {
+                git(data_dir, &["rm", "-q", "--", path])?;
+            }
+        }
+    }
+
+    git(data_dir, &["commit", "-q", "--no-edit"])?;
+    Ok(paths)
+}
+
+/// Run blocking git work on a thread meant for it rather than the async runtime.
+async fn run_blocking<T: Send + 'static>(
+    data_dir: &Path,
+    work: impl FnOnce(&Path) -> Result<T, String> + Send + 'static,
+) -> Result<T, String> {
+    let data_dir = data_dir.to_path_buf();
+    tokio::task::spawn_blocking(move || work(&data_dir))
+        .await
+        .map_err(|e| format!("Git task failed: {}", e))?
+}
+
+/// Merge the fetched remote branch, resolving conflicts per todo ID.
+///
+/// # Returns
+/// The files whose conflicts were resolved.
+fn merge_fetched(data_dir: &Path) -> Result<Vec<String>, String> {
+    let merge = git(
+        data_dir,
+        &[
+            "merge",
+            "-q",
+            "--no-edit",
+            "--allow-unrelated-histories",
+            "FETCH_HEAD",
+        ],
+    );
+    match merge {
+        Ok(_) => Ok(Vec::new()),
+        Err(e) => match resolve_conflicts(data_dir) {
+            Ok(resolved) if !resolved.is_empty() => Ok(resolved),
+            result => {
+                let _ = git(data_dir, &["merge", "--abort"]);
+                Err(result.err().unwrap_or(e))
+            }
+        },
+    }
+}
+
+/// Commit local changes, merge the remote branch and push the result.
+///
+/// `DAY_FILES_LOCK` is only held while git works on the files in the data directory,
+/// not while it talks to the remote.
+async fn sync(data_dir: &Path, settings: &GitSyncSettings) -> Result<SyncReport, String> {
+    if settings.remote.is_none() {
+        return Err("No git remote configured".to_string());
+    }
+    let _sync_guard = SYNC_LOCK.lock().await;
+
+    let committed = {
+        let _guard = crate::DAY_FILES_LOCK.lock().await;
+        let settings = settings.clone();
+        run_blocking(data_dir, move |dir| {
+            init_repo(dir, &settings)?;
+            commit_pending(dir)
+        })
+        .await?
+    };
+    let mut report = SyncReport {
+        committed,
+        pulled: false,
+        resolved: Vec::new(),
+        pushed: false,
+    };
+
+    let branch = settings.branch.clone();
+    let fetched = run_blocking(data_dir, move |dir| {
+        let remote_head = git(dir, &["ls-remote", "--heads", "origin", &branch])?;
+        if remote_head.trim().is_empty() {
+            return Ok(false);
+        }
+        git(dir, &["fetch", "-q", "origin", &branch])?;
+        Ok(true)
+    })
+    .await?;
+
+    if fetched {
+        let _guard = crate::DAY_FILES_LOCK.lock().await;
+        let (committed, resolved) = run_blocking(data_dir, |dir| {
+            // Changes saved while fetching
+            let committed = commit_pending(dir)?;
+            Ok((committed, merge_fetched(dir)?))
+        })
+        .await?;
+        report.committed |= committed;
+        report.resolved = resolved;
+        report.pulled = true;
+    }
+
+    let refspec = format!("HEAD:refs/heads/{}", settings.branch);
+    report.pushed = run_blocking(data_dir, move |dir| {
+        if git(dir, &["rev-parse", "--verify", "-q", "HEAD"]).is_err() {
+            return Ok(false);
+        }
+        git(dir, &["push", "-q", "origin", &refspec])?;
+        Ok(true)
+    })
+    .await?;
+
+    Ok(report)
+}
+
+/// Background task committing saved changes in batches, started from `setup`.
+pub async fn run_git_commit_scheduler(app: tauri::AppHandle) {
+    loop {
+        tokio::time::sleep(COMMIT_INTERVAL).await;
+
+        let result = match crate::resolve_data_dir(&app) {
+            Ok(data_dir) => match load_settings(&data_dir) {
+                Ok(settings) if settings.enabled => {
+                    let _guard = crate::DAY_FILES_LOCK.lock().await;
+                    run_blocking(&data_dir, move |dir| {
+                        init_repo(dir, &settings)?;
+                        commit_pending(dir)
+                    })
+                    .await
+                }
+                Ok(_) => Ok(false),
+                Err(e) => Err(e),
+            },
+            Err(e) => Err(e),
+        };
+
+        if let Err(e) = result {
+            #[cfg(debug_assertions)]
+            eprintln!("Git commit failed: {}", e);
+            let _ = e;
+        }
+    }
+}
+
+/// Load the git sync settings.
+///
+/// # Arguments
+/// * `data_dir` - Path to the app data directory
+///
+/// # Errors
+/// Returns an error if the settings file cannot be read.
+#[tauri::command]
+pub async fn load_git_sync_settings(data_dir: String) -> Result<GitSyncSettings, String> {
+    load_settings(Path::new(&data_dir))
+}
+
+/// Save the git sync settings. Enabling it turns the data directory into a git
+/// repository and commits the current data.
+///
+/// # Arguments
+/// * `settings` - The settings to store
+/// * `data_dir` - Path to the app data directory
+///
+/// # Errors
+/// Returns an error if the branch name is empty, git fails, or saving fails.
+#[tauri::command]
+pub async fn save_git_sync_settings(
+    mut settings: GitSyncSettings,
+    data_dir: String,
+) -> Result<(), String> {
+    settings.branch = settings.branch.trim().to_string();
+    if settings.branch.is_empty() {
+        return Err("Branch name cannot be empty".to_string());
+    }
+    settings.remote = settings
+        .remote
+        .map(|remote| remote.trim().to_string())
+        .filter(|remote| !remote.is_empty());
+
+    let data_dir = Path::new(&data_dir);
+    let json_content = serde_json::to_string_pretty(&settings)
+        .map_err(|e| format!("Failed to serialize git sync settings: {}", e))?;
+    fs::write(data_dir.join(GIT_SYNC_SETTINGS_FILE), json_content)
+        .map_err(|e| format!("Failed to write git sync settings: {}", e))?;
+
+    if settings.enabled {
+        let _guard = crate::DAY_FILES_LOCK.lock().await;
+        run_blocking(data_dir, move |dir| {
+            init_repo(dir, &settings)?;
+            commit_pending(dir)
+        })
+        .await?;
+    }
+    Ok(())
+}
+
+/// Commit pending changes, pull from the remote and push.
+///
+/// # Arguments
+/// * `data_dir` - Path to the app data directory
+///
+/// # Returns
+/// What was done, including files whose conflicts were resolved.
+///
+/// # Errors
+/// Returns an error if git sync is disabled or has no remote, git fails, or a conflict
+/// cannot be resolved. A failed merge is aborted, leaving local data as it was.
+#[tauri::command]
+pub async fn sync_now(data_dir: String) -> Result<SyncReport, String> {
+    crate::app_lock::ensure_unlocked()?;
+    let data_dir = Path::new(&data_dir);
+    let settings = load_settings(data_dir)?;
+    if !settings.enabled {
+        return Err("Git sync is not enabled".to_string());
+    }
+
+    sync(data_dir, &settings).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{read_day_file, write_day_file, DayData, TodoItem};
+    use chrono::NaiveDate;
+    use serde_json::json;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_merge_by_todo_id() {
+        let base = json!({
+            "date": "2024-01-15",
+            "notes": "Standup",
+            "todos": [{"id": "a", "text": "Email"}, {"id": "b", "text": "Gym"}]
+        });
+        let ours = json!({
+            "date": "2024-01-15",
+            "notes": "Standup\nShip it",
+            "todos": [{"id": "a", "text": "Email Sam"}, {"id": "b", "text": "Gym"}]
+        });
+        let theirs = json!({
+            "date": "2024-01-15",
+            "notes": "Standup\nCall back",
+            "todos": [{"id": "c", "text": "Read"}, {"id": "a", "text": "Email"}]
+        });
+
+        let merged = merge_value(Some(&base), Some(&ours), Some(&theirs)).unwrap();
+        assert_eq!(
+            merged["todos"],
+            json!([{"id": "c", "text": "Read"}, {"id": "a", "text": "Email Sam"}])
+        );
+        assert_eq!(merged["notes"], "Standup\nShip it\nCall back");
+    }
+
+    #[test]
+    fn test_existing_gitignore_gets_new_entries() {
+        let temp_dir = TempDir::new().unwrap();
+        let dir = temp_dir.path();
+        let author = ["-c", "user.name=Test", "-c", "user.email=test@localhost"];
+
+        // A repository set up by an earlier version, with the WebDAV password committed
+        git(dir, &["init", "-q"]).unwrap();
+        fs::write(dir.join(".gitignore"), "git_sync.json\nnotes-draft.txt").unwrap();
+        fs::write(dir.join("webdav.json"), "{\"password\": \"hunter2\"}").unwrap();
+        fs::write(dir.join("2024-01-15.json"), "{}").unwrap();
+        git(dir, &["add", "-A"]).unwrap();
+        git(dir, &[&author[..], &["commit", "-q", "-m", "Old"]].concat()).unwrap();
+
+        let settings = GitSyncSettings {
+            enabled: true,
+            remote: None,
+            branch: "main".to_string(),
+        };
+        init_repo(dir, &settings).unwrap();
+
+        let gitignore = fs::read_to_string(dir.join(".gitignore")).unwrap();
+        let lines: Vec<&str> = gitignore.lines().collect();
+        assert_eq!(
+            &lines[..3],
+            &["git_sync.json", "notes-draft.txt", "webdav.json"]
+        );
+        assert_eq!(lines.iter().filter(|l| **l == "git_sync.json").count(), 1);
+        assert!(lines.contains(&"history/") && lines.contains(&"rollover_state.json"));
+
+        let tracked = git(dir, &["ls-files"]).unwrap();
+        assert!(tracked.contains("2024-01-15.json"));
+        assert!(!tracked.contains("webdav.json"));
+        assert!(dir.join("webdav.json").exists());
+
+        // Nothing left to add the second time
+        init_repo(dir, &settings).unwrap();
+        assert_eq!(
+            fs::read_to_string(dir.join(".gitignore")).unwrap(),
+            gitignore
+        );
+    }
+
+    #[tokio::test]
+    async fn test_sync_two_replicas_through_bare_repo() {
+        let temp_dir = TempDir::new().unwrap();
+        let remote = temp_dir.path().join("remote.git");
+        git(
+            temp_dir.path(),
+            &["init", "-q", "--bare", remote.to_str().unwrap()],
+        )
+        .unwrap();
+
+        let settings = GitSyncSettings {
+            enabled: true,
+            remote: Some(remote.to_string_lossy().to_string()),
+            branch: "main".to_string(),
+        };
+        let replicas = [
+            temp_dir.path().join("laptop"),
+            temp_dir.path().join("desktop"),
+        ];
+        for dir in &replicas {
+            fs::create_dir_all(dir).unwrap();
+            save_git_sync_settings(settings.clone(), dir.to_string_lossy().to_string())
+                .await
+                .unwrap();
+        }
+        let sync = |i: usize| sync_now(replicas[i].to_string_lossy().to_string());
+
+        let date = NaiveDate::from_ymd_opt(2024, 1, 15).unwrap();
+        let email = TodoItem::new("Email".to_string());
+        let gym = TodoItem::new("Gym".to_string());
+        let day_data = DayData {
+            date,
+            todos: vec![email.clone(), gym.clone()],
+            notes: String::new(),
+        };
+        write_day_file(&replicas[0], &day_data).unwrap();
+        let first = sync(0).await.unwrap();
+        assert!(first.committed && first.pushed && !first.pulled);
+        assert!(sync(1).await.unwrap().pulled);
+
+        // Both machines edit different todos of the same day
+        let mut laptop = read_day_file(&replicas[0], date).unwrap();
+        laptop.todos[0].text = "Email Sam".to_string();
+        write_day_file(&replicas[0], &laptop).unwrap();
+        let mut desktop = read_day_file(&replicas[1], date).unwrap();
+        desktop.todos.retain(|t| t.id != gym.id);
+        desktop.todos.push(TodoItem::new("Read".to_string()));
+        write_day_file(&replicas[1], &desktop).unwrap();
+
+        sync(0).await.unwrap();
+        let report = sync(1).await.unwrap();
+        assert_eq!(report.resolved, vec!["2024-01-15.json"]);
+        sync(0).await.unwrap();
+
+        for dir in &replicas {
+            let texts: Vec<String> = read_day_file(dir, date)
+                .unwrap()
+                .todos
+                .into_iter()
+                .map(|t| t.text)
+                .collect();
+            assert_eq!(texts, vec!["Email Sam", "Read"]);
+        }
+        assert!(sync(1).await.unwrap().pulled);
+    }
+}
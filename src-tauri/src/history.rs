@@ -0,0 +1,265 @@
+//! Append-only revision history for day files.
+//!
+//! Before a day file is overwritten, its previous contents are stored as a numbered
+//! revision under `history/YYYY-MM-DD/NNNNNN.json`. Revisions are never modified or
+//! removed, so reverting a day is itself recorded and can be undone again.
+
+use crate::{write_day_file, DayData};
+use chrono::{DateTime, Local, NaiveDate};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Name of the history folder inside the data directory
+pub(crate) const HISTORY_DIR: &str = "history";
+
+/// A previous version of a day's data
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct DayRevision {
+    /// Sequential revision number, starting at 1 for the oldest version
+    pub revision: u32,
+    /// When this version was replaced by a newer save
+    pub saved_at: DateTime<Local>,
+    pub day_data: DayData,
+}
+
+fn day_history_dir(data_dir: &Path, date: NaiveDate) -> PathBuf {
+    data_dir
+        .join(HISTORY_DIR)
+        .join(date.format("%Y-%m-%d").to_string())
+}
+
+fn revision_path(data_dir: &Path, date: NaiveDate, revision: u32) -> PathBuf {
+    day_history_dir(data_dir, date).join(format!("{:06}.json", revision))
+}
+
+/// List the revision numbers stored for a date, oldest first.
+fn revision_numbers(data_dir: &Path, date: NaiveDate) -> Result<Vec<u32>, String> {
+    let dir = day_history_dir(data_dir, date);
+    if !dir.exists() {
+        return Ok(Vec::new());
+    }
+
+    let entries = fs::read_dir(&dir).map_err(|e| format!("Failed to read history: {}", e))?;
+    let mut revisions: Vec<u32> = entries
+        .filter_map(|entry| entry.ok())
+        .filter_map(|entry| {
+            entry
+                .path()
+                .file_stem()
+                .and_then(|stem| stem.to_str())
+                .and_then(|stem| stem.parse().ok())
+        })
+        .collect();
+
+    revisions.sort_unstable();
+    Ok(revisions)
+}
+
+fn read_revision(data_dir: &Path, date: NaiveDate, revision: u32) -> Result<DayRevision, String> {
+    let path = revision_path(data_dir, date, revision);
+    if !path.exists() {
+        return Err(format!("Revision {} not found for {}", revision, date));
+    }
+
+    let content =
+        fs::read_to_string(&path).map_err(|e| format!("Failed to read revision: {}", e))?;
+    let content = crate::encryption::open(data_dir, &content)?;
+    serde_json::from_str(&content).map_err(|e| format!("Failed to parse revision: {}", e))
+}
+
+/// Store the current contents of a day file as a new revision before it is replaced.
+///
+/// Nothing is recorded if the day has no file yet, or if `new_content` is identical
+/// to what is already on disk.
+pub(crate) fn record_revision(
+    data_dir: &Path,
+    date: NaiveDate,
+    new_content: &str,
+) -> Result<(), String> {
+    let file_path = crate::day_file_path(data_dir, date);
+    if !file_path.exists() {
+        return Ok(());
+    }
+
+    let previous =
+        fs::read_to_string(&file_path).map_err(|e| format!("Failed to read file: {}", e))?;
+    let previous = crate::encryption::open(data_dir, &previous)?;
+    if previous == new_content {
+        return Ok(());
+    }
+
+    // A file that no longer parses can't be represented as a revision; don't let it
+    // block saving the replacement
+    let Ok(day_data) = serde_json::from_str::<DayData>(&previous) else {
+        return Ok(());
+    };
+
+    let revision = revision_numbers(data_dir, date)?
+        .last()
+        .copied()
+        .unwrap_or(0)
+        + 1;
+    let entry = DayRevision {
+        revision,
+        saved_at: Local::now(),
+        day_data,
+    };
+
+    fs::create_dir_all(day_history_dir(data_dir, date))
+        .map_err(|e| format!("Failed to create history folder: {}", e))?;
+
+    let json_content = serde_json::to_string_pretty(&entry)
+        .map_err(|e| format!("Failed to serialize revision: {}", e))?;
+    let json_content = crate::encryption::seal(data_dir, &json_content)?;
+    fs::write(revision_path(data_dir, date, revision), json_content)
+        .map_err(|e| format!("Failed to write revision: {}", e))?;
+
+    Ok(())
+}
+
+/// Get all stored revisions of a day, newest first.
+///
+/// # Arguments
+/// * `date` - Date string in YYYY-MM-DD format
+/// * `data_dir` - Path to the app data directory
+///
+/// # Errors
+/// Returns an error if the date is invalid or a revision cannot be read.
+#[tauri::command]
+pub async fn get_day_history(date: String, data_dir: String) -> Result<Vec<DayRevision>, String> {
+    crate::app_lock::ensure_unlocked()?;
+    let date = NaiveDate::parse_from_str(&date, "%Y-%m-%d")
+        .map_err(|e| format!("Invalid date format: {}", e))?;
+    let data_dir = PathBuf::from(data_dir);
+
+    revision_numbers(&data_dir, date)?
+        .into_iter()
+        .rev()
+        .map(|revision| read_revision(&data_dir, date, revision))
+        .collect()
+}
+
+/// Restore a day to a previous revision.
+///
+/// The state being replaced is recorded as a new revision, so a revert can be undone.
+///
+/// # Arguments
+/// * `date` - Date string in YYYY-MM-DD format
+/// * `revision` - Revision number from `get_day_history`
+/// * `data_dir` - Path to the app data directory
+///
+/// # Returns
+/// The restored DayData.
+///
+/// # Errors
+/// Returns an error if the date is invalid, the revision doesn't exist, or saving fails.
+#[tauri::command]
+pub async fn revert_day(date: String, revision: u32, data_dir: String) -> Result<DayData, String> {
+    crate::app_lock::ensure_unlocked()?;
+    let date = NaiveDate::parse_from_str(&date, "%Y-%m-%d")
+        .map_err(|e| format!("Invalid date format: {}", e))?;
+    let data_dir = PathBuf::from(data_dir);
+
+    let day_data = read_revision(&data_dir, date, revision)?.day_data;
+    let _guard = crate::DAY_FILES_LOCK.lock().await;
+    write_day_file(&data_dir, &day_data)?;
+
+    Ok(day_data)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{create_todo_item, load_day_data, save_day_data};
+    use tempfile::TempDir;
+
+    fn day(notes: &str) -> DayData {
+        DayData {
+            date: NaiveDate::from_ymd_opt(2024, 1, 15).unwrap(),
+            todos: Vec::new(),
+            notes: notes.to_string(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_save_records_previous_versions() {
+        let temp_dir = TempDir::new().unwrap();
+        let data_dir = temp_dir.path().to_string_lossy().to_string();
+
+        // First save has nothing to record
+        save_day_data(day("first"), data_dir.clone()).await.unwrap();
+        assert!(get_day_history("2024-01-15".to_string(), data_dir.clone())
+            .await
+            .unwrap()
+            .is_empty());
+
+        save_day_data(day("second"), data_dir.clone())
+            .await
+            .unwrap();
+        save_day_data(day("third"), data_dir.clone()).await.unwrap();
+
+        let history = get_day_history("2024-01-15".to_string(), data_dir)
+            .await
+            .unwrap();
+        assert_eq!(history.len(), 2);
+        assert_eq!(history[0].revision, 2);
+        assert_eq!(history[0].day_data.notes, "second");
+        assert_eq!(history[1].revision, 1);
+        assert_eq!(history[1].day_data.notes, "first");
+    }
+
+    #[tokio::test]
+    async fn test_identical_save_is_not_recorded() {
+        let temp_dir = TempDir::new().unwrap();
+        let data_dir = temp_dir.path().to_string_lossy().to_string();
+
+        save_day_data(day("same"), data_dir.clone()).await.unwrap();
+        save_day_data(day("same"), data_dir.clone()).await.unwrap();
+
+        let history = get_day_history("2024-01-15".to_string(), data_dir)
+            .await
+            .unwrap();
+        assert!(history.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_revert_day_restores_deleted_todo() {
+        let temp_dir = TempDir::new().unwrap();
+        let data_dir = temp_dir.path().to_string_lossy().to_string();
+
+        let todo = create_todo_item("Accidentally deleted".to_string())
+            .await
+            .unwrap();
+        let mut with_todo = day("notes");
+        with_todo.todos.push(todo.clone());
+        save_day_data(with_todo, data_dir.clone()).await.unwrap();
+        save_day_data(day("notes"), data_dir.clone()).await.unwrap();
+
+        let restored = revert_day("2024-01-15".to_string(), 1, data_dir.clone())
+            .await
+            .unwrap();
+        assert_eq!(restored.todos.len(), 1);
+
+        let loaded = load_day_data("2024-01-15".to_string(), data_dir.clone(), None)
+            .await
+            .unwrap();
+        assert_eq!(loaded.todos[0].id, todo.id);
+
+        // The emptied state was recorded too, so the revert can be undone
+        let history = get_day_history("2024-01-15".to_string(), data_dir)
+            .await
+            .unwrap();
+        assert_eq!(history.len(), 2);
+        assert!(history[0].day_data.todos.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_revert_day_unknown_revision() {
+        let temp_dir = TempDir::new().unwrap();
+        let data_dir = temp_dir.path().to_string_lossy().to_string();
+
+        let result = revert_day("2024-01-15".to_string(), 7, data_dir).await;
+        assert!(result.unwrap_err().contains("not found"));
+    }
+}
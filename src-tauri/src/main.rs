@@ -3,20 +3,56 @@
 // Clippy: Tauri command functions appear unused but are called by the frontend
 #![allow(dead_code)]
 
-use chrono::{DateTime, Local, NaiveDate};
+use chrono::{DateTime, Local, NaiveDate, NaiveDateTime, NaiveTime};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use tauri::{Emitter, Manager, Window};
 use tauri_plugin_notification::NotificationExt;
 use tauri_plugin_opener::OpenerExt;
 use uuid::Uuid;
 
+use collections::{Location, TodoList};
+
+mod app_lock;
+mod collections;
+mod crdt;
+mod encryption;
+mod export;
+mod git_sync;
+mod history;
+mod peer_sync;
+mod recurrence;
+mod reminders;
+mod rollover;
+mod snapshots;
+mod tags;
+mod templates;
+mod todos;
+mod trash;
+mod webdav;
+mod workspaces;
+
 // Zoom level constraints - shared across save/load to ensure consistency
 const MIN_ZOOM: f64 = 0.5;
 const MAX_ZOOM: f64 = 3.0;
 
+/// Top-level files of the data directory describing one device's setup or sync state,
+/// which no sync method copies to other devices
+const LOCAL_ONLY_FILES: &[&str] = &[
+    "git_sync.json",
+    "webdav.json",
+    "webdav_state.json",
+    "peer_sync.json",
+    "peer_sync_state.json",
+    "app_lock.json",
+    "crdt_settings.json",
+    "reminders_state.json",
+    "rollover_state.json",
+    "snapshot_settings.json",
+];
+
 /// Zoom limits structure for exposing to frontend
 #[derive(Debug, Serialize, Deserialize, Clone)]
 struct ZoomLimits {
@@ -24,17 +60,257 @@ struct ZoomLimits {
     max_zoom: f64,
 }
 
+/// Bullet journal state of a todo item
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "lowercase")]
+enum TodoStatus {
+    /// Still to do (•)
+    #[default]
+    Open,
+    /// Completed (×)
+    Done,
+    /// Carried forward to another day (>)
+    Migrated,
+    /// Scheduled out of a collection onto a day (<)
+    Scheduled,
+    /// No longer relevant (struck through)
+    Cancelled,
+}
+
+impl TodoStatus {
+    /// The bullet journal signifier for this status.
+    ///
+    /// Cancelled todos keep the task bullet; renderers strike the whole entry instead.
+    fn signifier(self) -> &'static str {
+        match self {
+            TodoStatus::Open | TodoStatus::Cancelled => "•",
+            TodoStatus::Done => "×",
+            TodoStatus::Migrated => ">",
+            TodoStatus::Scheduled => "<",
+        }
+    }
+}
+
+/// How important a todo is; ordered from least to most important
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Default)]
+#[serde(rename_all = "lowercase")]
+enum Priority {
+    #[default]
+    None,
+    Low,
+    Medium,
+    High,
+    Urgent,
+}
+
+impl Priority {
+    fn is_none(&self) -> bool {
+        *self == Priority::None
+    }
+
+    /// The inline `!` token for this priority, empty for none.
+    fn marker(self) -> &'static str {
+        match self {
+            Priority::None => "",
+            Priority::Low => "!",
+            Priority::Medium => "!!",
+            Priority::High => "!!!",
+            Priority::Urgent => "!!!!",
+        }
+    }
+}
+
+/// When a todo is due, independent of the day it is on
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+struct Deadline {
+    date: NaiveDate,
+    /// Without a time, the todo is due by the end of the day
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    time: Option<NaiveTime>,
+}
+
+impl Deadline {
+    /// The moment after which the todo is overdue.
+    fn due_at(&self) -> NaiveDateTime {
+        match self.time {
+            Some(time) => self.date.and_time(time),
+            None => (self.date + chrono::Duration::days(1)).and_time(NaiveTime::MIN),
+        }
+    }
+}
+
+/// Order in which todos are returned by load commands
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "lowercase")]
+enum TodoSort {
+    /// The order stored in the day file
+    #[default]
+    Manual,
+    /// Most important first, then oldest first
+    Priority,
+}
+
+impl TodoSort {
+    fn apply(self, todos: &mut [TodoItem]) {
+        if self == TodoSort::Priority {
+            todos.sort_by_key(|todo| (std::cmp::Reverse(todo.priority), todo.created_at));
+        }
+    }
+}
+
+/// Deserialize a status, accepting the `completed` boolean written by older versions.
+fn deserialize_status<'de, D>(deserializer: D) -> Result<TodoStatus, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum StatusOrCompletedFlag {
+        Status(TodoStatus),
+        Completed(bool),
+    }
+
+    Ok(match StatusOrCompletedFlag::deserialize(deserializer)? {
+        StatusOrCompletedFlag::Status(status) => status,
+        StatusOrCompletedFlag::Completed(true) => TodoStatus::Done,
+        StatusOrCompletedFlag::Completed(false) => TodoStatus::Open,
+    })
+}
+
+/// A checklist step inside a todo item
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+struct Subtask {
+    id: String,
+    text: String,
+    completed: bool,
+}
+
+impl Subtask {
+    fn new(text: String) -> Self {
+        Subtask {
+            id: Uuid::new_v4().to_string(),
+            text,
+            completed: false,
+        }
+    }
+}
+
 /// Represents a single todo item with bullet journal semantics
 #[derive(Debug, Serialize, Deserialize, Clone)]
 struct TodoItem {
     id: String,
     text: String,
-    completed: bool,
+    /// Files written before statuses existed store `completed: bool` instead
+    #[serde(default, alias = "completed", deserialize_with = "deserialize_status")]
+    status: TodoStatus,
     created_at: DateTime<Local>,
     move_to_next_day: bool,
     /// Notes attached to this specific todo item
     #[serde(default)]
     notes: String,
+    /// For a migrated or scheduled entry, the day the todo was moved to
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    migrated_to: Option<NaiveDate>,
+    /// The first day this todo was on, recorded the first time it moves
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    origin_date: Option<NaiveDate>,
+    /// Every day this todo was migrated away from, oldest first
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    migrated_through: Vec<NaiveDate>,
+    /// When the todo was marked done
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    completed_at: Option<DateTime<Local>>,
+    /// The recurrence rule this todo was generated from
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    recurrence_id: Option<String>,
+    /// Checklist steps, in order
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    subtasks: Vec<Subtask>,
+    /// Mark the todo done when every subtask is completed, and open again when one
+    /// is unchecked
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    auto_complete: bool,
+    /// Lowercase tags, sorted
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    tags: Vec<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    project: Option<String>,
+    #[serde(default, skip_serializing_if = "Priority::is_none")]
+    priority: Priority,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    deadline: Option<Deadline>,
+    /// Local time to show a reminder notification at
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    remind_at: Option<NaiveDateTime>,
+}
+
+impl TodoItem {
+    /// Create a new open todo item with a unique ID and the current timestamp.
+    fn new(text: String) -> Self {
+        TodoItem {
+            id: Uuid::new_v4().to_string(),
+            text,
+            status: TodoStatus::Open,
+            created_at: Local::now(),
+            move_to_next_day: false,
+            notes: String::new(),
+            migrated_to: None,
+            origin_date: None,
+            migrated_through: Vec::new(),
+            completed_at: None,
+            recurrence_id: None,
+            subtasks: Vec::new(),
+            auto_complete: false,
+            tags: Vec::new(),
+            project: None,
+            priority: Priority::None,
+            deadline: None,
+            remind_at: None,
+        }
+    }
+
+    /// Update the status from the subtasks if `auto_complete` is enabled.
+    ///
+    /// Called after a subtask changes, so a todo completed by hand isn't reopened just
+    /// because its checklist is unfinished.
+    fn apply_subtask_rules(&mut self) {
+        if !self.auto_complete || self.subtasks.is_empty() {
+            return;
+        }
+
+        let all_completed = self.subtasks.iter().all(|subtask| subtask.completed);
+        match self.status {
+            TodoStatus::Open if all_completed => self.status = TodoStatus::Done,
+            TodoStatus::Done if !all_completed => self.status = TodoStatus::Open,
+            _ => {}
+        }
+        self.sync_completed_at();
+    }
+
+    /// Keep `completed_at` in line with the status: stamped when the todo becomes done,
+    /// cleared when it is reopened.
+    fn sync_completed_at(&mut self) {
+        if self.status != TodoStatus::Done {
+            self.completed_at = None;
+        } else if self.completed_at.is_none() {
+            self.completed_at = Some(Local::now());
+        }
+    }
+
+    /// How many times this todo has been carried forward to another day.
+    fn times_postponed(&self) -> usize {
+        self.migrated_through.len()
+    }
+
+    /// The migrated or scheduled entry left behind when this todo moves elsewhere.
+    fn moved_ghost(&self, status: TodoStatus, to_date: Option<NaiveDate>) -> Self {
+        TodoItem {
+            status,
+            move_to_next_day: false,
+            migrated_to: to_date,
+            ..TodoItem::new(self.text.clone())
+        }
+    }
 }
 
 /// Represents all data for a single day
@@ -45,47 +321,81 @@ struct DayData {
     notes: String,
 }
 
-/// Get the application data directory, creating it if necessary.
+/// Resolve the directory holding day files and preferences, creating it if necessary.
+///
+/// This is the root of the active workspace. Shared by the `get_app_data_dir` command
+/// and background tasks that need the data directory without a round-trip through
+/// the frontend.
+fn resolve_data_dir(app: &tauri::AppHandle) -> Result<PathBuf, String> {
+    let data_dir = workspaces::active_root(app)?;
+
+    // Create the directory if it doesn't exist
+    fs::create_dir_all(&data_dir).map_err(|e| format!("Failed to create data directory: {}", e))?;
+
+    Ok(data_dir)
+}
+
+/// Get the data directory of the active workspace, creating it if necessary.
 ///
 /// # Returns
-/// The absolute path to the app data directory as a String.
+/// The absolute path to the data directory as a String.
 ///
 /// # Errors
 /// Returns an error if the directory cannot be accessed or created.
 #[tauri::command]
 async fn get_app_data_dir(app: tauri::AppHandle) -> Result<String, String> {
-    let data_dir = app
-        .path()
-        .app_data_dir()
-        .map_err(|e| format!("Failed to get app data directory: {}", e))?;
-
-    // Create the directory if it doesn't exist
-    fs::create_dir_all(&data_dir).map_err(|e| format!("Failed to create data directory: {}", e))?;
+    let data_dir = resolve_data_dir(&app)?;
 
     Ok(data_dir.to_string_lossy().to_string())
 }
 
-/// Load data for a specific date from persistent storage.
-///
-/// # Arguments
-/// * `date` - Date string in YYYY-MM-DD format
-/// * `data_dir` - Path to the app data directory
+/// Path of the JSON file holding a given day's data.
+fn day_file_path(data_dir: &Path, date: NaiveDate) -> PathBuf {
+    data_dir.join(format!("{}.json", date.format("%Y-%m-%d")))
+}
+
+/// Serializes read-modify-write cycles on day files.
 ///
-/// # Returns
-/// DayData for the requested date, or empty data if file doesn't exist.
+/// Commands that load a day, change it and write it back hold this lock for the whole
+/// cycle so concurrent callers (UI, tray, background jobs) can't lose each other's edits.
+static DAY_FILES_LOCK: tokio::sync::Mutex<()> = tokio::sync::Mutex::const_new(());
+
+/// Write a day's data to its file, recording the previous version in the day's history.
 ///
-/// # Errors
-/// Returns an error if date format is invalid or file cannot be read.
-#[tauri::command]
-async fn load_day_data(date: String, data_dir: String) -> Result<DayData, String> {
-    let date = NaiveDate::parse_from_str(&date, "%Y-%m-%d")
-        .map_err(|e| format!("Invalid date format: {}", e))?;
+/// All writes of day files go through here so that every overwrite can be undone. The
+/// file is written to a temporary path and renamed into place, so readers never see a
+/// partially written day. With CRDT sync enabled the change is also recorded in this
+/// device's day document.
+fn write_day_file(data_dir: &Path, day_data: &DayData) -> Result<(), String> {
+    crdt::record_local_change(data_dir, day_data)?;
+    store_day_file(data_dir, day_data)
+}
 
-    let file_path = PathBuf::from(data_dir).join(format!("{}.json", date.format("%Y-%m-%d")));
+/// Write a day file without recording a CRDT change, for days rebuilt from merged
+/// day documents.
+fn store_day_file(data_dir: &Path, day_data: &DayData) -> Result<(), String> {
+    let json_content = serde_json::to_string_pretty(day_data)
+        .map_err(|e| format!("Failed to serialize data: {}", e))?;
+
+    history::record_revision(data_dir, day_data.date, &json_content)?;
+
+    let json_content = encryption::seal(data_dir, &json_content)?;
+    let file_path = day_file_path(data_dir, day_data.date);
+    let temp_path = file_path.with_extension("json.tmp");
+    fs::write(&temp_path, json_content).map_err(|e| format!("Failed to write file: {}", e))?;
+    fs::rename(&temp_path, &file_path).map_err(|e| format!("Failed to write file: {}", e))?;
+
+    Ok(())
+}
+
+/// Read a day's data from its file, or empty data if the file doesn't exist.
+fn read_day_file(data_dir: &Path, date: NaiveDate) -> Result<DayData, String> {
+    let file_path = day_file_path(data_dir, date);
 
     if file_path.exists() {
         let content =
             fs::read_to_string(&file_path).map_err(|e| format!("Failed to read file: {}", e))?;
+        let content = encryption::open(data_dir, &content)?;
 
         let day_data: DayData =
             serde_json::from_str(&content).map_err(|e| format!("Failed to parse JSON: {}", e))?;
@@ -101,29 +411,159 @@ async fn load_day_data(date: String, data_dir: String) -> Result<DayData, String
     }
 }
 
-/// Save data for a specific day to persistent storage.
+/// Load data for a specific date from persistent storage.
+///
+/// This is a plain read: nothing is generated for the day. Use `open_day` when the
+/// user opens a day to work on it.
 ///
 /// # Arguments
-/// * `day_data` - The complete data for the day to save
+/// * `date` - Date string in YYYY-MM-DD format
 /// * `data_dir` - Path to the app data directory
+/// * `sort` - Order of the returned todos; defaults to the stored order
+///
+/// # Returns
+/// DayData for the requested date, or empty data if file doesn't exist.
 ///
 /// # Errors
-/// Returns an error if serialization fails or file cannot be written.
+/// Returns an error if date format is invalid or file cannot be read.
 #[tauri::command]
-async fn save_day_data(day_data: DayData, data_dir: String) -> Result<(), String> {
-    let file_path =
-        PathBuf::from(data_dir).join(format!("{}.json", day_data.date.format("%Y-%m-%d")));
+async fn load_day_data(
+    date: String,
+    data_dir: String,
+    sort: Option<TodoSort>,
+) -> Result<DayData, String> {
+    app_lock::ensure_unlocked()?;
+    let date = NaiveDate::parse_from_str(&date, "%Y-%m-%d")
+        .map_err(|e| format!("Invalid date format: {}", e))?;
+    let data_dir = Path::new(&data_dir);
 
-    let json_content = serde_json::to_string_pretty(&day_data)
-        .map_err(|e| format!("Failed to serialize data: {}", e))?;
+    let _guard = DAY_FILES_LOCK.lock().await;
+    crdt::merge_local_day(data_dir, date)?;
+    let mut day_data = read_day_file(data_dir, date)?;
 
-    fs::write(&file_path, json_content).map_err(|e| format!("Failed to write file: {}", e))?;
+    sort.unwrap_or_default().apply(&mut day_data.todos);
+    Ok(day_data)
+}
 
-    Ok(())
+/// Load a day the user opened, filling it in if it is today or later.
+///
+/// A day without a file starts from its weekday template, if one is defined, and
+/// recurring todos due on the date are added. Past days are returned as stored.
+/// Callers must hold `DAY_FILES_LOCK`.
+fn open_day_at(data_dir: &Path, date: NaiveDate, today: NaiveDate) -> Result<DayData, String> {
+    crdt::merge_local_day(data_dir, date)?;
+    let is_new = !day_file_path(data_dir, date).exists();
+    let mut day_data = read_day_file(data_dir, date)?;
+    if is_new && templates::apply_weekday_template(data_dir, &mut day_data, today)? {
+        write_day_file(data_dir, &day_data)?;
+    }
+    recurrence::materialize(data_dir, &mut day_data, today)?;
+    Ok(day_data)
+}
+
+/// Open a day for viewing and editing.
+///
+/// Unlike `load_day_data`, today and later days are filled in from their weekday
+/// template and due recurring todos the first time they are opened.
+///
+/// # Arguments
+/// * `date` - Date string in YYYY-MM-DD format
+/// * `data_dir` - Path to the app data directory
+/// * `sort` - Order of the returned todos; defaults to the stored order
+///
+/// # Returns
+/// DayData for the requested date.
+///
+/// # Errors
+/// Returns an error if date format is invalid or a file cannot be read or written.
+#[tauri::command]
+async fn open_day(
+    date: String,
+    data_dir: String,
+    sort: Option<TodoSort>,
+) -> Result<DayData, String> {
+    app_lock::ensure_unlocked()?;
+    let date = NaiveDate::parse_from_str(&date, "%Y-%m-%d")
+        .map_err(|e| format!("Invalid date format: {}", e))?;
+    let data_dir = Path::new(&data_dir);
+
+    let _guard = DAY_FILES_LOCK.lock().await;
+    let mut day_data = open_day_at(data_dir, date, Local::now().date_naive())?;
+
+    sort.unwrap_or_default().apply(&mut day_data.todos);
+    Ok(day_data)
+}
+
+/// Load every stored day in a date range.
+///
+/// Days without a file are skipped; nothing is generated or written.
+///
+/// # Arguments
+/// * `start_date` - First date in YYYY-MM-DD format
+/// * `end_date` - Last date (inclusive) in YYYY-MM-DD format
+/// * `data_dir` - Path to the app data directory
+/// * `sort` - Order of the todos within each day; defaults to the stored order
+///
+/// # Returns
+/// The days in the range, oldest first.
+///
+/// # Errors
+/// Returns an error if a date format is invalid or a file cannot be read.
+#[tauri::command]
+async fn load_day_range(
+    start_date: String,
+    end_date: String,
+    data_dir: String,
+    sort: Option<TodoSort>,
+) -> Result<Vec<DayData>, String> {
+    app_lock::ensure_unlocked()?;
+    let start_date = NaiveDate::parse_from_str(&start_date, "%Y-%m-%d")
+        .map_err(|e| format!("Invalid start_date format: {}", e))?;
+    let end_date = NaiveDate::parse_from_str(&end_date, "%Y-%m-%d")
+        .map_err(|e| format!("Invalid end_date format: {}", e))?;
+    let data_dir = Path::new(&data_dir);
+    let sort = sort.unwrap_or_default();
+
+    list_day_dates(data_dir)?
+        .into_iter()
+        .filter(|date| (start_date..=end_date).contains(date))
+        .map(|date| {
+            let mut day_data = read_day_file(data_dir, date)?;
+            sort.apply(&mut day_data.todos);
+            Ok(day_data)
+        })
+        .collect()
+}
+
+/// Save data for a specific day to persistent storage.
+///
+/// The previous contents of the day, if any, are kept in the day's revision history.
+/// Todos missing from `day_data` compared to the stored version are moved to the trash.
+///
+/// # Arguments
+/// * `day_data` - The complete data for the day to save
+/// * `data_dir` - Path to the app data directory
+///
+/// # Errors
+/// Returns an error if serialization fails or file cannot be written.
+#[tauri::command]
+async fn save_day_data(mut day_data: DayData, data_dir: String) -> Result<(), String> {
+    app_lock::ensure_unlocked()?;
+    let _guard = DAY_FILES_LOCK.lock().await;
+    let data_dir = Path::new(&data_dir);
+    day_data
+        .todos
+        .iter_mut()
+        .for_each(TodoItem::sync_completed_at);
+    trash::track_deletions(data_dir, &day_data)?;
+    write_day_file(data_dir, &day_data)
 }
 
 /// Create a new todo item with a unique ID and timestamp.
 ///
+/// `#tag` and `+project` tokens in the text are extracted into the item's tags and
+/// project.
+///
 /// # Arguments
 /// * `text` - The todo item text/description
 ///
@@ -131,21 +571,125 @@ async fn save_day_data(day_data: DayData, data_dir: String) -> Result<(), String
 /// A new TodoItem with generated ID and current timestamp.
 #[tauri::command]
 async fn create_todo_item(text: String) -> Result<TodoItem, String> {
-    let now = Local::now();
-    let todo = TodoItem {
-        id: Uuid::new_v4().to_string(),
-        text,
-        completed: false,
-        created_at: now,
-        move_to_next_day: false,
-        notes: String::new(),
+    Ok(tags::todo_from_input(text))
+}
+
+/// Move todos from one day to another, leaving migrated entries on the source day.
+///
+/// Callers must hold `DAY_FILES_LOCK`. See [`transfer_todos`].
+fn move_todos_between(
+    data_dir: &Path,
+    from_date: NaiveDate,
+    to_date: NaiveDate,
+    todo_ids: &[String],
+) -> Result<Vec<TodoItem>, String> {
+    transfer_todos(
+        data_dir,
+        &Location::Day(from_date),
+        &Location::Day(to_date),
+        todo_ids,
+    )
+}
+
+/// Move todos between days and collections, leaving an entry at the source.
+///
+/// The entry left behind is "scheduled" (<) when a todo goes from a collection onto a
+/// day and "migrated" (>) otherwise. Each file is read and written once however many
+/// todos move. Moved todos go to the top of the destination in the order given, with
+/// `move_to_next_day` cleared. Sources are written directly rather than through
+/// `save_day_data`, so moved todos aren't mistaken for deletions. The destination is
+/// opened first and written before the source, so a failure can leave a todo in both
+/// places but never in neither. Callers must hold `DAY_FILES_LOCK`.
+///
+/// # Returns
+/// The moved todos as they now appear at the destination.
+fn transfer_todos(
+    data_dir: &Path,
+    from: &Location,
+    to: &Location,
+    todo_ids: &[String],
+) -> Result<Vec<TodoItem>, String> {
+    let mut source = from.open(data_dir)?;
+    let mut destination = to.open(data_dir)?;
+    let ghost_status = match (from, to) {
+        (Location::Day(_), _) | (_, Location::Month(_) | Location::FutureLog) => {
+            TodoStatus::Migrated
+        }
+        _ => TodoStatus::Scheduled,
     };
 
-    Ok(todo)
+    let mut moved = Vec::with_capacity(todo_ids.len());
+    for (i, todo_id) in todo_ids.iter().enumerate() {
+        if todo_ids[..i].contains(todo_id) {
+            return Err(format!("Todo with ID {} is listed more than once", todo_id));
+        }
+
+        let todo = source
+            .todos_mut()
+            .iter_mut()
+            .find(|t| &t.id == todo_id)
+            .ok_or_else(|| format!("Todo with ID {} not found on {}", todo_id, from))?;
+
+        if matches!(todo.status, TodoStatus::Migrated | TodoStatus::Scheduled) {
+            return Err(format!("Todo with ID {} has already been moved", todo_id));
+        }
+
+        let ghost = todo.moved_ghost(ghost_status, to.date());
+        let mut todo_item = std::mem::replace(todo, ghost);
+        todo_item.move_to_next_day = false;
+        // Only moves between days count as postponing
+        if let Location::Day(from_date) = from {
+            todo_item.origin_date.get_or_insert(*from_date);
+            if to.date().is_some() {
+                todo_item.migrated_through.push(*from_date);
+            }
+        }
+        moved.push(todo_item);
+    }
+
+    if moved.is_empty() {
+        return Ok(moved);
+    }
+
+    // Add todos to destination (at the beginning to make them visible)
+    if let (TodoList::Day(day_data), Some(to_date)) = (&mut destination, to.date()) {
+        if !day_file_path(data_dir, to_date).exists() {
+            templates::apply_weekday_template(data_dir, day_data, Local::now().date_naive())?;
+        }
+    }
+    destination.todos_mut().splice(0..0, moved.iter().cloned());
+    destination.save(data_dir)?;
+    source.save(data_dir)?;
+
+    Ok(moved)
+}
+
+/// List the dates that have a day file in the data directory, oldest first.
+fn list_day_dates(data_dir: &Path) -> Result<Vec<NaiveDate>, String> {
+    let entries =
+        fs::read_dir(data_dir).map_err(|e| format!("Failed to read data directory: {}", e))?;
+
+    let mut dates: Vec<NaiveDate> = entries
+        .filter_map(|entry| entry.ok())
+        .filter_map(|entry| {
+            let path = entry.path();
+            if path.extension().and_then(|ext| ext.to_str()) != Some("json") {
+                return None;
+            }
+            let stem = path.file_stem()?.to_str()?.to_string();
+            NaiveDate::parse_from_str(&stem, "%Y-%m-%d").ok()
+        })
+        .collect();
+
+    dates.sort_unstable();
+    Ok(dates)
 }
 
 /// Move a todo item from one date to another.
 ///
+/// The source day keeps a "migrated" entry (>) pointing to the destination day, as in
+/// a paper bullet journal.
+///
 /// # Arguments
 /// * `todo_id` - The unique ID of the todo item to move
 /// * `from_date` - Source date in YYYY-MM-DD format
@@ -167,10 +711,11 @@ async fn move_todo_to_date(
     to_date: String,
     data_dir: String,
 ) -> Result<(), String> {
+    app_lock::ensure_unlocked()?;
     // Parse dates to validate format
-    let _from_date_parsed = NaiveDate::parse_from_str(&from_date, "%Y-%m-%d")
+    let from_date = NaiveDate::parse_from_str(&from_date, "%Y-%m-%d")
         .map_err(|e| format!("Invalid from_date format: {}", e))?;
-    let _to_date_parsed = NaiveDate::parse_from_str(&to_date, "%Y-%m-%d")
+    let to_date = NaiveDate::parse_from_str(&to_date, "%Y-%m-%d")
         .map_err(|e| format!("Invalid to_date format: {}", e))?;
 
     // If dates are the same, nothing to do
@@ -178,31 +723,190 @@ async fn move_todo_to_date(
         return Ok(());
     }
 
-    // Load source day data
-    let mut from_day_data = load_day_data(from_date.clone(), data_dir.clone()).await?;
+    let _guard = DAY_FILES_LOCK.lock().await;
+    move_todos_between(Path::new(&data_dir), from_date, to_date, &[todo_id])?;
+
+    Ok(())
+}
+
+/// Move several todos from one date to another in a single operation.
+///
+/// Each day file is written once, and nothing is written unless every todo can be
+/// moved. Moved todos keep the given order at the top of the destination day.
+///
+/// # Arguments
+/// * `ids` - The unique IDs of the todo items to move
+/// * `from_date` - Source date in YYYY-MM-DD format
+/// * `to_date` - Destination date in YYYY-MM-DD format
+/// * `data_dir` - Path to the app data directory
+///
+/// # Returns
+/// The moved todos as they now appear on the destination day.
+///
+/// # Errors
+/// Returns an error if:
+/// - Date format is invalid or both dates are the same
+/// - A todo is not found, listed twice, or has already been moved
+/// - File operations fail
+#[tauri::command]
+async fn move_todos(
+    ids: Vec<String>,
+    from_date: String,
+    to_date: String,
+    data_dir: String,
+) -> Result<Vec<TodoItem>, String> {
+    app_lock::ensure_unlocked()?;
+    let (from_date, to_date) = parse_move_dates(&from_date, &to_date)?;
+
+    let _guard = DAY_FILES_LOCK.lock().await;
+    move_todos_between(Path::new(&data_dir), from_date, to_date, &ids)
+}
+
+/// Migrate every open todo of a day to another date.
+///
+/// This is the bullet journal "migration" ritual: done, cancelled and already moved
+/// entries stay where they are, everything still open moves in one operation.
+///
+/// # Arguments
+/// * `from_date` - Source date in YYYY-MM-DD format
+/// * `to_date` - Destination date in YYYY-MM-DD format
+/// * `data_dir` - Path to the app data directory
+///
+/// # Returns
+/// The migrated todos as they now appear on the destination day.
+///
+/// # Errors
+/// Returns an error if a date format is invalid, both dates are the same, or file
+/// operations fail.
+#[tauri::command]
+async fn migrate_all_open(
+    from_date: String,
+    to_date: String,
+    data_dir: String,
+) -> Result<Vec<TodoItem>, String> {
+    app_lock::ensure_unlocked()?;
+    let (from_date, to_date) = parse_move_dates(&from_date, &to_date)?;
+    let data_dir = Path::new(&data_dir);
 
-    // Find and remove the todo from source
-    let todo_index = from_day_data
+    let _guard = DAY_FILES_LOCK.lock().await;
+    let open_ids: Vec<String> = read_day_file(data_dir, from_date)?
         .todos
-        .iter()
-        .position(|t| t.id == todo_id)
-        .ok_or_else(|| format!("Todo with ID {} not found on {}", todo_id, from_date))?;
+        .into_iter()
+        .filter(|todo| todo.status == TodoStatus::Open)
+        .map(|todo| todo.id)
+        .collect();
 
-    let todo_item = from_day_data.todos.remove(todo_index);
+    move_todos_between(data_dir, from_date, to_date, &open_ids)
+}
 
-    // Save updated source day
-    save_day_data(from_day_data, data_dir.clone()).await?;
+/// An open todo that keeps getting postponed
+#[derive(Debug, Serialize, Deserialize, Clone)]
+struct StaleTodo {
+    /// The day the todo is currently on
+    date: NaiveDate,
+    todo: TodoItem,
+    times_postponed: usize,
+}
 
-    // Load destination day data
-    let mut to_day_data = load_day_data(to_date.clone(), data_dir.clone()).await?;
+/// Find open todos that have been migrated at least `min_postponements` times.
+///
+/// # Arguments
+/// * `min_postponements` - Minimum number of times a todo must have been moved
+/// * `data_dir` - Path to the app data directory
+///
+/// # Returns
+/// The stale todos, most postponed first.
+///
+/// # Errors
+/// Returns an error if the data directory or a day file cannot be read.
+#[tauri::command]
+async fn get_stale_todos(
+    min_postponements: usize,
+    data_dir: String,
+) -> Result<Vec<StaleTodo>, String> {
+    app_lock::ensure_unlocked()?;
+    let data_dir = Path::new(&data_dir);
+
+    let mut stale = Vec::new();
+    for date in list_day_dates(data_dir)? {
+        for todo in read_day_file(data_dir, date)?.todos {
+            if todo.status == TodoStatus::Open && todo.times_postponed() >= min_postponements {
+                stale.push(StaleTodo {
+                    date,
+                    times_postponed: todo.times_postponed(),
+                    todo,
+                });
+            }
+        }
+    }
 
-    // Add todo to destination (at the beginning to make it visible)
-    to_day_data.todos.insert(0, todo_item);
+    stale.sort_by_key(|entry| std::cmp::Reverse(entry.times_postponed));
+    Ok(stale)
+}
 
-    // Save updated destination day
-    save_day_data(to_day_data, data_dir).await?;
+/// An open todo past its deadline
+#[derive(Debug, Serialize, Deserialize, Clone)]
+struct OverdueTodo {
+    /// The day the todo is currently on
+    date: NaiveDate,
+    todo: TodoItem,
+}
 
-    Ok(())
+/// Find open todos whose deadline has passed, on any day.
+///
+/// Deadlines with a time on `today` count as overdue once that time has passed, if
+/// `today` is the current date.
+///
+/// # Arguments
+/// * `today` - Date string in YYYY-MM-DD format
+/// * `data_dir` - Path to the app data directory
+///
+/// # Returns
+/// The overdue todos, earliest deadline first.
+///
+/// # Errors
+/// Returns an error if the date is invalid or a day file cannot be read.
+#[tauri::command]
+async fn get_overdue_todos(today: String, data_dir: String) -> Result<Vec<OverdueTodo>, String> {
+    app_lock::ensure_unlocked()?;
+    let today = NaiveDate::parse_from_str(&today, "%Y-%m-%d")
+        .map_err(|e| format!("Invalid date format: {}", e))?;
+    let local_now = Local::now().naive_local();
+    let now = if local_now.date() == today {
+        local_now
+    } else {
+        today.and_time(NaiveTime::MIN)
+    };
+    let data_dir = Path::new(&data_dir);
+
+    let mut overdue = Vec::new();
+    for date in list_day_dates(data_dir)? {
+        for todo in read_day_file(data_dir, date)?.todos {
+            let is_overdue = todo
+                .deadline
+                .is_some_and(|deadline| deadline.due_at() <= now);
+            if todo.status == TodoStatus::Open && is_overdue {
+                overdue.push(OverdueTodo { date, todo });
+            }
+        }
+    }
+
+    overdue.sort_by_key(|entry| entry.todo.deadline.map(|deadline| deadline.due_at()));
+    Ok(overdue)
+}
+
+/// Parse the source and destination dates of a bulk move, which must differ.
+fn parse_move_dates(from_date: &str, to_date: &str) -> Result<(NaiveDate, NaiveDate), String> {
+    let from_date = NaiveDate::parse_from_str(from_date, "%Y-%m-%d")
+        .map_err(|e| format!("Invalid from_date format: {}", e))?;
+    let to_date = NaiveDate::parse_from_str(to_date, "%Y-%m-%d")
+        .map_err(|e| format!("Invalid to_date format: {}", e))?;
+
+    if from_date == to_date {
+        return Err("Source and destination dates are the same".to_string());
+    }
+
+    Ok((from_date, to_date))
 }
 
 /// Start a pomodoro timer for a specific duration.
@@ -313,6 +1017,7 @@ async fn stop_pomodoro_timer() -> Result<(), String> {
 /// - JSON serialization/deserialization fails
 #[tauri::command]
 async fn migrate_calendar_events_to_todos(data_dir: String) -> Result<String, String> {
+    app_lock::ensure_unlocked()?;
     let events_file = PathBuf::from(&data_dir).join("calendar_events.json");
 
     // Check if calendar_events.json exists
@@ -349,11 +1054,12 @@ async fn migrate_calendar_events_to_todos(data_dir: String) -> Result<String, St
         })?;
 
         // Load existing day data
-        let file_path = PathBuf::from(&data_dir).join(format!("{}.json", date.format("%Y-%m-%d")));
+        let file_path = day_file_path(Path::new(&data_dir), date);
 
         let mut day_data = if file_path.exists() {
             let content = fs::read_to_string(&file_path)
                 .map_err(|e| format!("Failed to read day file: {}", e))?;
+            let content = encryption::open(Path::new(&data_dir), &content)?;
             serde_json::from_str(&content)
                 .map_err(|e| format!("Failed to parse day data: {}", e))?
         } else {
@@ -367,14 +1073,7 @@ async fn migrate_calendar_events_to_todos(data_dir: String) -> Result<String, St
         // Convert events to todos and prepend them (maintaining original order)
         let mut new_todos: Vec<TodoItem> = event_list
             .iter()
-            .map(|event_text| TodoItem {
-                id: Uuid::new_v4().to_string(),
-                text: event_text.clone(),
-                completed: false,
-                created_at: Local::now(),
-                move_to_next_day: false,
-                notes: String::new(),
-            })
+            .map(|event_text| TodoItem::new(event_text.clone()))
             .collect();
 
         migrated_count += new_todos.len();
@@ -384,11 +1083,7 @@ async fn migrate_calendar_events_to_todos(data_dir: String) -> Result<String, St
         day_data.todos = new_todos;
 
         // Save updated day data
-        let json_content = serde_json::to_string_pretty(&day_data)
-            .map_err(|e| format!("Failed to serialize day data: {}", e))?;
-
-        fs::write(&file_path, json_content)
-            .map_err(|e| format!("Failed to write day file: {}", e))?;
+        write_day_file(Path::new(&data_dir), &day_data)?;
 
         migrated_dates.push(date_str);
     }
@@ -415,16 +1110,14 @@ async fn migrate_calendar_events_to_todos(data_dir: String) -> Result<String, St
 /// Returns an error if preference cannot be saved.
 #[tauri::command]
 fn save_dark_mode_preference(dark_mode: bool, app: tauri::AppHandle) -> Result<(), String> {
-    let data_dir = app
-        .path()
-        .app_data_dir()
-        .map_err(|e| format!("Failed to get app data directory: {}", e))?;
+    let data_dir = resolve_data_dir(&app)?;
 
     let file_path = data_dir.join("dark_mode.json");
 
     let json_content = serde_json::json!({ "dark_mode": dark_mode });
     let json_str = serde_json::to_string_pretty(&json_content)
         .map_err(|e| format!("Failed to serialize dark mode preference: {}", e))?;
+    let json_str = encryption::seal(&data_dir, &json_str)?;
 
     fs::write(&file_path, json_str)
         .map_err(|e| format!("Failed to write dark mode preference file: {}", e))?;
@@ -444,16 +1137,14 @@ fn save_dark_mode_preference(dark_mode: bool, app: tauri::AppHandle) -> Result<(
 /// Returns an error if preference file cannot be read.
 #[tauri::command]
 fn load_dark_mode_preference(app: tauri::AppHandle) -> Result<bool, String> {
-    let data_dir = app
-        .path()
-        .app_data_dir()
-        .map_err(|e| format!("Failed to get app data directory: {}", e))?;
+    let data_dir = resolve_data_dir(&app)?;
 
     let file_path = data_dir.join("dark_mode.json");
 
     if file_path.exists() {
         let file_content = fs::read_to_string(&file_path)
             .map_err(|e| format!("Failed to read dark mode preference file: {}", e))?;
+        let file_content = encryption::open(&data_dir, &file_content)?;
 
         let json: serde_json::Value = serde_json::from_str(&file_content)
             .map_err(|e| format!("Failed to parse dark mode preference: {}", e))?;
@@ -655,6 +1346,7 @@ fn save_zoom_preference_to_path(zoom_level: f64, file_path: PathBuf) -> Result<(
     let json_content = serde_json::json!({ "zoom_level": validated_zoom });
     let json_str = serde_json::to_string_pretty(&json_content)
         .map_err(|e| format!("Failed to serialize zoom preference: {}", e))?;
+    let json_str = encryption::seal(preference_data_dir(&file_path), &json_str)?;
 
     fs::write(&file_path, json_str)
         .map_err(|e| format!("Failed to write zoom preference file: {}", e))?;
@@ -662,6 +1354,11 @@ fn save_zoom_preference_to_path(zoom_level: f64, file_path: PathBuf) -> Result<(
     Ok(())
 }
 
+/// The data directory a preference file lives in, at its top level.
+fn preference_data_dir(file_path: &Path) -> &Path {
+    file_path.parent().unwrap_or(Path::new(""))
+}
+
 /// Internal helper: Load zoom preference from a file path
 ///
 /// This function is extracted for testing purposes.
@@ -669,6 +1366,7 @@ fn load_zoom_preference_from_path(file_path: PathBuf) -> Result<f64, String> {
     if file_path.exists() {
         let file_content = fs::read_to_string(&file_path)
             .map_err(|e| format!("Failed to read zoom preference file: {}", e))?;
+        let file_content = encryption::open(preference_data_dir(&file_path), &file_content)?;
 
         let json: serde_json::Value = serde_json::from_str(&file_content)
             .map_err(|e| format!("Failed to parse zoom preference: {}", e))?;
@@ -707,10 +1405,7 @@ fn load_zoom_preference_from_path(file_path: PathBuf) -> Result<f64, String> {
 /// Returns an error if preference cannot be saved.
 #[tauri::command]
 fn save_zoom_preference(zoom_level: f64, app: tauri::AppHandle) -> Result<(), String> {
-    let data_dir = app
-        .path()
-        .app_data_dir()
-        .map_err(|e| format!("Failed to get app data directory: {}", e))?;
+    let data_dir = resolve_data_dir(&app)?;
 
     let file_path = data_dir.join("zoom_level.json");
     save_zoom_preference_to_path(zoom_level, file_path)
@@ -728,10 +1423,7 @@ fn save_zoom_preference(zoom_level: f64, app: tauri::AppHandle) -> Result<(), St
 /// Returns an error if preference file cannot be read.
 #[tauri::command]
 fn load_zoom_preference(app: tauri::AppHandle) -> Result<f64, String> {
-    let data_dir = app
-        .path()
-        .app_data_dir()
-        .map_err(|e| format!("Failed to get app data directory: {}", e))?;
+    let data_dir = resolve_data_dir(&app)?;
 
     let file_path = data_dir.join("zoom_level.json");
     load_zoom_preference_from_path(file_path)
@@ -744,12 +1436,54 @@ fn main() {
         tauri::Builder::default()
             .plugin(tauri_plugin_opener::init())
             .plugin(tauri_plugin_notification::init())
+            .manage(reminders::ReminderScheduler::new())
+            .manage(peer_sync::PeerDirectory::new())
+            .setup(|app| {
+                // Identify this device in CRDT day documents
+                if let Err(e) = crdt::init_replica_id(app.handle()) {
+                    #[cfg(debug_assertions)]
+                    eprintln!("Failed to set up replica ID: {}", e);
+                    let _ = e;
+                }
+                // Start locked if the app lock is on, and lock again when idle or asleep
+                if let Err(e) = app_lock::lock_on_startup(app.handle()) {
+                    #[cfg(debug_assertions)]
+                    eprintln!("Failed to check app lock: {}", e);
+                    let _ = e;
+                }
+                tauri::async_runtime::spawn(app_lock::run_app_lock_scheduler(app.handle().clone()));
+                // Keep a daily snapshot of the data directory in the background
+                tauri::async_runtime::spawn(snapshots::run_snapshot_scheduler(
+                    app.handle().clone(),
+                ));
+                // Carry todos forward on the first launch of a day and at midnight
+                tauri::async_runtime::spawn(rollover::run_rollover_scheduler(app.handle().clone()));
+                // Deliver todo reminders, including ones missed while the app was closed
+                tauri::async_runtime::spawn(reminders::run_reminder_scheduler(
+                    app.handle().clone(),
+                ));
+                // Discover and sync with other instances on the LAN once enabled
+                tauri::async_runtime::spawn(peer_sync::run_peer_sync_service(app.handle().clone()));
+                // Commit saved changes when the data directory is a git repository
+                tauri::async_runtime::spawn(git_sync::run_git_commit_scheduler(
+                    app.handle().clone(),
+                ));
+                // Mirror the data directory to a WebDAV server once configured
+                tauri::async_runtime::spawn(webdav::run_webdav_scheduler(app.handle().clone()));
+                Ok(())
+            })
             .invoke_handler(tauri::generate_handler![
                 get_app_data_dir,
                 load_day_data,
+                open_day,
+                load_day_range,
                 save_day_data,
                 create_todo_item,
                 move_todo_to_date,
+                move_todos,
+                migrate_all_open,
+                get_stale_todos,
+                get_overdue_todos,
                 start_pomodoro_timer,
                 stop_pomodoro_timer,
                 send_notification,
@@ -762,10 +1496,87 @@ fn main() {
                 get_app_version,
                 open_url_in_browser,
                 show_pomodoro_notification,
-                focus_app_window
+                focus_app_window,
+                snapshots::list_snapshots,
+                snapshots::restore_snapshot,
+                snapshots::load_snapshot_settings,
+                snapshots::save_snapshot_settings,
+                history::get_day_history,
+                history::revert_day,
+                trash::list_trash,
+                trash::restore_from_trash,
+                trash::empty_trash,
+                trash::get_trash_retention_days,
+                todos::add_todo,
+                todos::update_todo,
+                todos::set_deadline,
+                reminders::set_reminder,
+                reminders::snooze_reminder,
+                todos::toggle_todo,
+                todos::delete_todo,
+                todos::reorder_todos,
+                todos::add_subtask,
+                todos::update_subtask,
+                todos::toggle_subtask,
+                todos::delete_subtask,
+                tags::list_tags,
+                tags::rename_tag,
+                export::export_day_markdown,
+                recurrence::list_recurrences,
+                recurrence::add_recurrence,
+                recurrence::delete_recurrence,
+                templates::list_templates,
+                templates::save_template,
+                templates::delete_template,
+                templates::apply_template,
+                collections::list_month_logs,
+                collections::load_month_log,
+                collections::save_month_log,
+                collections::load_future_log,
+                collections::save_future_log,
+                collections::schedule_todo,
+                collections::list_collections,
+                collections::create_collection,
+                collections::load_collection,
+                collections::save_collection,
+                collections::rename_collection,
+                collections::delete_collection,
+                collections::move_todo,
+                workspaces::list_workspaces,
+                workspaces::create_workspace,
+                workspaces::switch_workspace,
+                workspaces::set_data_dir,
+                crdt::load_crdt_settings,
+                crdt::save_crdt_settings,
+                crdt::merge_day_documents,
+                encryption::load_encryption_status,
+                encryption::enable_encryption,
+                encryption::unlock,
+                encryption::change_passphrase,
+                app_lock::load_app_lock_settings,
+                app_lock::save_app_lock_settings,
+                app_lock::lock_app,
+                app_lock::record_activity,
+                git_sync::load_git_sync_settings,
+                git_sync::save_git_sync_settings,
+                git_sync::sync_now,
+                peer_sync::load_peer_sync_settings,
+                peer_sync::save_peer_sync_settings,
+                peer_sync::list_peers,
+                peer_sync::sync_with_peer,
+                webdav::load_webdav_settings,
+                webdav::save_webdav_settings,
+                webdav::sync_webdav,
+                rollover::load_rollover_settings,
+                rollover::save_rollover_settings
             ])
-            .run(tauri::generate_context!())
-            .expect("error while running tauri application");
+            .build(tauri::generate_context!())
+            .expect("error while building tauri application")
+            .run(|app, event| {
+                if let tauri::RunEvent::Exit = event {
+                    snapshots::snapshot_on_exit(app);
+                }
+            });
     }
 }
 
@@ -788,7 +1599,7 @@ mod tests {
         let todo = result.unwrap();
 
         assert_eq!(todo.text, text);
-        assert!(!todo.completed);
+        assert_eq!(todo.status, TodoStatus::Open);
         assert!(!todo.move_to_next_day);
         assert!(!todo.id.is_empty());
         assert!(uuid::Uuid::parse_str(&todo.id).is_ok());
@@ -816,7 +1627,7 @@ mod tests {
         assert!(save_result.is_ok());
 
         // Load the data back
-        let load_result = load_day_data("2024-01-15".to_string(), data_dir).await;
+        let load_result = load_day_data("2024-01-15".to_string(), data_dir, None).await;
         assert!(load_result.is_ok());
 
         let loaded_data = load_result.unwrap();
@@ -832,7 +1643,7 @@ mod tests {
         let temp_dir = setup_test_dir();
         let data_dir = temp_dir.path().to_string_lossy().to_string();
 
-        let result = load_day_data("2024-01-15".to_string(), data_dir).await;
+        let result = load_day_data("2024-01-15".to_string(), data_dir, None).await;
         assert!(result.is_ok());
 
         let day_data = result.unwrap();
@@ -846,7 +1657,7 @@ mod tests {
         let temp_dir = setup_test_dir();
         let data_dir = temp_dir.path().to_string_lossy().to_string();
 
-        let result = load_day_data("invalid-date".to_string(), data_dir).await;
+        let result = load_day_data("invalid-date".to_string(), data_dir, None).await;
         assert!(result.is_err());
         assert!(result.unwrap_err().contains("Invalid date format"));
     }
@@ -886,7 +1697,7 @@ mod tests {
         let deserialized_todo = deserialized.unwrap();
         assert_eq!(deserialized_todo.text, todo.text);
         assert_eq!(deserialized_todo.id, todo.id);
-        assert_eq!(deserialized_todo.completed, todo.completed);
+        assert_eq!(deserialized_todo.status, todo.status);
     }
 
     #[tokio::test]
@@ -919,7 +1730,7 @@ mod tests {
         let todo = rt.block_on(create_todo_item(text.clone())).unwrap();
 
         assert_eq!(todo.text, text);
-        assert!(!todo.completed);
+        assert_eq!(todo.status, TodoStatus::Open);
         assert!(!todo.move_to_next_day);
         assert!(!todo.id.is_empty());
 
@@ -943,7 +1754,7 @@ mod tests {
         let todo1 = create_todo_item("First todo".to_string()).await.unwrap();
         let todo2 = create_todo_item("Second todo".to_string()).await.unwrap();
         let mut todo3 = create_todo_item("Third todo".to_string()).await.unwrap();
-        todo3.completed = true; // Mark one as completed
+        todo3.status = TodoStatus::Done; // Mark one as completed
 
         let day_data = DayData {
             date,
@@ -953,7 +1764,7 @@ mod tests {
 
         // Save and reload
         save_day_data(day_data, data_dir.clone()).await.unwrap();
-        let loaded = load_day_data("2024-01-15".to_string(), data_dir)
+        let loaded = load_day_data("2024-01-15".to_string(), data_dir, None)
             .await
             .unwrap();
 
@@ -961,9 +1772,9 @@ mod tests {
         assert_eq!(loaded.todos[0].text, todo1.text);
         assert_eq!(loaded.todos[1].text, todo2.text);
         assert_eq!(loaded.todos[2].text, todo3.text);
-        assert!(!loaded.todos[0].completed);
-        assert!(!loaded.todos[1].completed);
-        assert!(loaded.todos[2].completed);
+        assert_eq!(loaded.todos[0].status, TodoStatus::Open);
+        assert_eq!(loaded.todos[1].status, TodoStatus::Open);
+        assert_eq!(loaded.todos[2].status, TodoStatus::Done);
     }
 
     #[tokio::test]
@@ -1066,17 +1877,17 @@ mod tests {
         assert!(message.contains("2 days")); // Number of days
 
         // Verify todos were created for 2024-01-15
-        let day_data = load_day_data("2024-01-15".to_string(), data_dir.clone())
+        let day_data = load_day_data("2024-01-15".to_string(), data_dir.clone(), None)
             .await
             .unwrap();
         assert_eq!(day_data.todos.len(), 2);
         assert_eq!(day_data.todos[0].text, "Meeting at 2pm");
         assert_eq!(day_data.todos[1].text, "Call dentist");
-        assert!(!day_data.todos[0].completed);
-        assert!(!day_data.todos[1].completed);
+        assert_eq!(day_data.todos[0].status, TodoStatus::Open);
+        assert_eq!(day_data.todos[1].status, TodoStatus::Open);
 
         // Verify todos were created for 2024-01-16
-        let day_data2 = load_day_data("2024-01-16".to_string(), data_dir.clone())
+        let day_data2 = load_day_data("2024-01-16".to_string(), data_dir.clone(), None)
             .await
             .unwrap();
         assert_eq!(day_data2.todos.len(), 1);
@@ -1100,12 +1911,8 @@ mod tests {
         let existing_day = DayData {
             date: NaiveDate::from_ymd_opt(2024, 1, 15).unwrap(),
             todos: vec![TodoItem {
-                id: Uuid::new_v4().to_string(),
-                text: "Existing todo".to_string(),
-                completed: true,
-                created_at: Local::now(),
-                move_to_next_day: false,
-                notes: String::new(),
+                status: TodoStatus::Done,
+                ..TodoItem::new("Existing todo".to_string())
             }],
             notes: "Existing notes".to_string(),
         };
@@ -1132,7 +1939,7 @@ mod tests {
         assert!(result.is_ok());
 
         // Verify todos were merged (calendar events prepended)
-        let day_data = load_day_data("2024-01-15".to_string(), data_dir.clone())
+        let day_data = load_day_data("2024-01-15".to_string(), data_dir.clone(), None)
             .await
             .unwrap();
         assert_eq!(day_data.todos.len(), 3);
@@ -1143,7 +1950,7 @@ mod tests {
 
         // Existing todo should be last
         assert_eq!(day_data.todos[2].text, "Existing todo");
-        assert!(day_data.todos[2].completed); // Preserved completion status
+        assert_eq!(day_data.todos[2].status, TodoStatus::Done); // Preserved completion status
 
         // Existing notes should be preserved
         assert_eq!(day_data.notes, "Existing notes");
@@ -1284,15 +2091,20 @@ mod tests {
 
         assert!(result.is_ok());
 
-        // Verify source day has only one todo left
-        let source_loaded = load_day_data("2024-01-15".to_string(), data_dir.clone())
+        // Verify source day keeps a migrated entry in place of the moved todo
+        let source_loaded = load_day_data("2024-01-15".to_string(), data_dir.clone(), None)
             .await
             .unwrap();
-        assert_eq!(source_loaded.todos.len(), 1);
-        assert_eq!(source_loaded.todos[0].id, todo2.id);
+        assert_eq!(source_loaded.todos.len(), 2);
+        let ghost = &source_loaded.todos[0];
+        assert_ne!(ghost.id, todo_id);
+        assert_eq!(ghost.text, "Todo to move");
+        assert_eq!(ghost.status, TodoStatus::Migrated);
+        assert_eq!(ghost.migrated_to, Some(dest_date));
+        assert_eq!(source_loaded.todos[1].id, todo2.id);
 
         // Verify destination day has both todos (moved one at beginning)
-        let dest_loaded = load_day_data("2024-01-20".to_string(), data_dir.clone())
+        let dest_loaded = load_day_data("2024-01-20".to_string(), data_dir.clone(), None)
             .await
             .unwrap();
         assert_eq!(dest_loaded.todos.len(), 2);
@@ -1300,6 +2112,29 @@ mod tests {
         assert_eq!(dest_loaded.todos[1].id, existing_todo.id); // Existing todo is second
     }
 
+    #[test]
+    fn test_legacy_completed_flag_is_migrated() {
+        let legacy = r#"{
+            "date": "2024-01-15",
+            "todos": [
+                {"id": "a", "text": "Done", "completed": true,
+                 "created_at": "2024-01-15T09:00:00+00:00", "move_to_next_day": false},
+                {"id": "b", "text": "Open", "completed": false,
+                 "created_at": "2024-01-15T09:00:00+00:00", "move_to_next_day": false}
+            ],
+            "notes": ""
+        }"#;
+
+        let day_data: DayData = serde_json::from_str(legacy).unwrap();
+        assert_eq!(day_data.todos[0].status, TodoStatus::Done);
+        assert_eq!(day_data.todos[1].status, TodoStatus::Open);
+
+        // Re-saved files use the new field only
+        let json = serde_json::to_string(&day_data).unwrap();
+        assert!(json.contains(r#""status":"done""#));
+        assert!(!json.contains("completed"));
+    }
+
     #[tokio::test]
     async fn test_move_todo_to_date_same_date() {
         let temp_dir = setup_test_dir();
@@ -1329,7 +2164,7 @@ mod tests {
         assert!(result.is_ok());
 
         // Verify todo is still there
-        let loaded = load_day_data("2024-01-15".to_string(), data_dir)
+        let loaded = load_day_data("2024-01-15".to_string(), data_dir, None)
             .await
             .unwrap();
         assert_eq!(loaded.todos.len(), 1);
@@ -1379,4 +2214,251 @@ mod tests {
         assert!(result.is_err());
         assert!(result.unwrap_err().contains("Invalid from_date format"));
     }
+
+    #[tokio::test]
+    async fn test_move_todos_is_all_or_nothing() {
+        let temp_dir = setup_test_dir();
+        let data_dir = temp_dir.path().to_string_lossy().to_string();
+
+        let first = create_todo_item("First".to_string()).await.unwrap();
+        let second = create_todo_item("Second".to_string()).await.unwrap();
+        let day_data = DayData {
+            date: NaiveDate::from_ymd_opt(2024, 1, 31).unwrap(),
+            todos: vec![first.clone(), second.clone()],
+            notes: String::new(),
+        };
+        save_day_data(day_data, data_dir.clone()).await.unwrap();
+
+        // One unknown ID rejects the whole batch
+        let result = move_todos(
+            vec![first.id.clone(), "nonexistent-id".to_string()],
+            "2024-01-31".to_string(),
+            "2024-02-01".to_string(),
+            data_dir.clone(),
+        )
+        .await;
+        assert!(result.unwrap_err().contains("not found"));
+        let source = load_day_data("2024-01-31".to_string(), data_dir.clone(), None)
+            .await
+            .unwrap();
+        assert_eq!(source.todos[0].status, TodoStatus::Open);
+
+        let moved = move_todos(
+            vec![second.id.clone(), first.id.clone()],
+            "2024-01-31".to_string(),
+            "2024-02-01".to_string(),
+            data_dir.clone(),
+        )
+        .await
+        .unwrap();
+        assert_eq!(moved.len(), 2);
+
+        let dest = load_day_data("2024-02-01".to_string(), data_dir, None)
+            .await
+            .unwrap();
+        let ids: Vec<&str> = dest.todos.iter().map(|t| t.id.as_str()).collect();
+        assert_eq!(ids, vec![second.id.as_str(), first.id.as_str()]);
+    }
+
+    #[tokio::test]
+    async fn test_priority_sort() {
+        let temp_dir = setup_test_dir();
+        let data_dir = temp_dir.path().to_string_lossy().to_string();
+
+        let texts = ["Someday", "Fix outage !!!!", "Review PR !!", "Water plants"];
+        let mut todos = Vec::new();
+        for text in texts {
+            todos.push(create_todo_item(text.to_string()).await.unwrap());
+        }
+        let day_data = DayData {
+            date: NaiveDate::from_ymd_opt(2024, 1, 15).unwrap(),
+            todos,
+            notes: String::new(),
+        };
+        save_day_data(day_data, data_dir.clone()).await.unwrap();
+
+        let sorted = load_day_data(
+            "2024-01-15".to_string(),
+            data_dir.clone(),
+            Some(TodoSort::Priority),
+        )
+        .await
+        .unwrap();
+        let texts: Vec<&str> = sorted.todos.iter().map(|t| t.text.as_str()).collect();
+        assert_eq!(
+            texts,
+            vec!["Fix outage", "Review PR", "Someday", "Water plants"]
+        );
+
+        // The stored order is unchanged
+        let manual = load_day_data("2024-01-15".to_string(), data_dir.clone(), None)
+            .await
+            .unwrap();
+        assert_eq!(manual.todos[0].text, "Someday");
+
+        let range = load_day_range(
+            "2024-01-01".to_string(),
+            "2024-01-31".to_string(),
+            data_dir,
+            Some(TodoSort::Priority),
+        )
+        .await
+        .unwrap();
+        assert_eq!(range.len(), 1);
+        assert_eq!(range[0].todos[0].priority, Priority::Urgent);
+    }
+
+    #[tokio::test]
+    async fn test_get_overdue_todos() {
+        let temp_dir = setup_test_dir();
+        let data_dir = temp_dir.path().to_string_lossy().to_string();
+
+        let deadline = |date: &str, time: Option<&str>| {
+            Some(Deadline {
+                date: NaiveDate::parse_from_str(date, "%Y-%m-%d").unwrap(),
+                time: time.map(|t| NaiveTime::parse_from_str(t, "%H:%M").unwrap()),
+            })
+        };
+        let mut late = create_todo_item("Late report".to_string()).await.unwrap();
+        late.deadline = deadline("2024-01-18", Some("17:00"));
+        let mut later = create_todo_item("Later".to_string()).await.unwrap();
+        later.deadline = deadline("2024-01-19", None);
+        let mut done = create_todo_item("Done on time".to_string()).await.unwrap();
+        done.deadline = deadline("2024-01-10", None);
+        done.status = TodoStatus::Done;
+        let mut upcoming = create_todo_item("Upcoming".to_string()).await.unwrap();
+        upcoming.deadline = deadline("2024-01-20", None);
+
+        let day_data = DayData {
+            date: NaiveDate::from_ymd_opt(2024, 1, 15).unwrap(),
+            todos: vec![later, done, upcoming, late.clone()],
+            notes: String::new(),
+        };
+        save_day_data(day_data, data_dir.clone()).await.unwrap();
+
+        // A deadline without a time is overdue from the next day on
+        let overdue = get_overdue_todos("2024-01-20".to_string(), data_dir.clone())
+            .await
+            .unwrap();
+        let texts: Vec<&str> = overdue.iter().map(|o| o.todo.text.as_str()).collect();
+        assert_eq!(texts, vec!["Late report", "Later"]);
+        assert_eq!(
+            overdue[0].date,
+            NaiveDate::from_ymd_opt(2024, 1, 15).unwrap()
+        );
+
+        // The deadline survives a move
+        move_todo_to_date(
+            late.id.clone(),
+            "2024-01-15".to_string(),
+            "2024-01-16".to_string(),
+            data_dir.clone(),
+        )
+        .await
+        .unwrap();
+        let overdue = get_overdue_todos("2024-01-19".to_string(), data_dir)
+            .await
+            .unwrap();
+        assert_eq!(overdue.len(), 1);
+        assert_eq!(overdue[0].todo.id, late.id);
+        assert_eq!(
+            overdue[0].date,
+            NaiveDate::from_ymd_opt(2024, 1, 16).unwrap()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_lineage_and_stale_todos() {
+        let temp_dir = setup_test_dir();
+        let data_dir = temp_dir.path().to_string_lossy().to_string();
+
+        let todo = create_todo_item("File taxes".to_string()).await.unwrap();
+        let day_data = DayData {
+            date: NaiveDate::from_ymd_opt(2024, 3, 1).unwrap(),
+            todos: vec![todo.clone()],
+            notes: String::new(),
+        };
+        save_day_data(day_data, data_dir.clone()).await.unwrap();
+
+        for (from, to) in [("2024-03-01", "2024-03-02"), ("2024-03-02", "2024-03-05")] {
+            move_todo_to_date(
+                todo.id.clone(),
+                from.to_string(),
+                to.to_string(),
+                data_dir.clone(),
+            )
+            .await
+            .unwrap();
+        }
+
+        let current = load_day_data("2024-03-05".to_string(), data_dir.clone(), None)
+            .await
+            .unwrap();
+        let moved = &current.todos[0];
+        assert_eq!(moved.origin_date, NaiveDate::from_ymd_opt(2024, 3, 1));
+        assert_eq!(
+            moved.migrated_through,
+            vec![
+                NaiveDate::from_ymd_opt(2024, 3, 1).unwrap(),
+                NaiveDate::from_ymd_opt(2024, 3, 2).unwrap()
+            ]
+        );
+        assert_eq!(moved.times_postponed(), 2);
+
+        // Migrated entries left behind are not reported
+        let stale = get_stale_todos(2, data_dir.clone()).await.unwrap();
+        assert_eq!(stale.len(), 1);
+        assert_eq!(stale[0].todo.id, todo.id);
+        assert_eq!(stale[0].date, NaiveDate::from_ymd_opt(2024, 3, 5).unwrap());
+        assert_eq!(stale[0].times_postponed, 2);
+
+        assert!(get_stale_todos(3, data_dir).await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_migrate_all_open() {
+        let temp_dir = setup_test_dir();
+        let data_dir = temp_dir.path().to_string_lossy().to_string();
+
+        let open = create_todo_item("Still open".to_string()).await.unwrap();
+        let mut done = create_todo_item("Finished".to_string()).await.unwrap();
+        done.status = TodoStatus::Done;
+        let mut cancelled = create_todo_item("Dropped".to_string()).await.unwrap();
+        cancelled.status = TodoStatus::Cancelled;
+        let day_data = DayData {
+            date: NaiveDate::from_ymd_opt(2024, 1, 31).unwrap(),
+            todos: vec![done, open.clone(), cancelled],
+            notes: String::new(),
+        };
+        save_day_data(day_data, data_dir.clone()).await.unwrap();
+
+        let moved = migrate_all_open(
+            "2024-01-31".to_string(),
+            "2024-02-01".to_string(),
+            data_dir.clone(),
+        )
+        .await
+        .unwrap();
+        assert_eq!(moved.len(), 1);
+        assert_eq!(moved[0].id, open.id);
+
+        let source = load_day_data("2024-01-31".to_string(), data_dir.clone(), None)
+            .await
+            .unwrap();
+        let statuses: Vec<TodoStatus> = source.todos.iter().map(|t| t.status).collect();
+        assert_eq!(
+            statuses,
+            vec![
+                TodoStatus::Done,
+                TodoStatus::Migrated,
+                TodoStatus::Cancelled
+            ]
+        );
+
+        // Running it again finds nothing left to migrate
+        let again = migrate_all_open("2024-01-31".to_string(), "2024-02-01".to_string(), data_dir)
+            .await
+            .unwrap();
+        assert!(again.is_empty());
+    }
 }
@@ -0,0 +1,799 @@
+//! Local network sync between running instances.
+//!
+//! When enabled, each instance announces itself with a UDP broadcast on
+//! [`DISCOVERY_PORT`] and serves its day files over TCP. Every [`SYNC_INTERVAL`] it
+//! pulls from the peers it has heard from.
+//!
+//! Each change to a day file gets a version: the ID of the device that made it and
+//! that device's change counter. A device's vector clock holds the highest counter it
+//! has seen from every device, so a pull only transfers day files changed since the
+//! puller's clock. A received day replaces the local one if the sender had already
+//! seen the local version; otherwise both changed it concurrently and they are merged
+//! by `TodoItem::id`, the same way on both sides so the devices converge.
+//!
+//! Peers must share the same sync secret. Traffic is not encrypted, so only enable this
+//! on trusted networks.
+
+use crate::{list_day_dates, read_day_file, write_day_file, DayData, TodoItem};
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::net::SocketAddr;
+use std::path::{Path, PathBuf};
+use tauri::{Emitter, Manager};
+use tokio::io::{AsyncBufReadExt, AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt, BufReader};
+use tokio::net::{TcpListener, TcpStream, UdpSocket};
+use tokio::sync::Mutex;
+
+/// UDP port instances announce themselves on
+pub const DISCOVERY_PORT: u16 = 47474;
+
+/// How often peers are announced to and pulled from
+const SYNC_INTERVAL: std::time::Duration = std::time::Duration::from_secs(30);
+
+/// Identifies discovery broadcasts from this app
+const SERVICE_NAME: &str = "todo-notes-tracker-sync";
+
+/// Largest pull request accepted from a peer
+const MAX_REQUEST_BYTES: u64 = 64 * 1024;
+
+/// Largest pull response accepted from a peer
+const MAX_RESPONSE_BYTES: u64 = 256 * 1024 * 1024;
+
+/// How long connecting to a peer, or sending or receiving one message, may take
+const IO_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(30);
+
+/// Preference file holding the peer sync settings
+const PEER_SYNC_SETTINGS_FILE: &str = "peer_sync.json";
+
+/// File holding this device's vector clock and day versions
+const PEER_SYNC_STATE_FILE: &str = "peer_sync_state.json";
+
+/// Highest change counter seen from each device
+type VectorClock = BTreeMap<String, u64>;
+
+fn default_port() -> u16 {
+    47475
+}
+
+/// Peer sync settings
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct PeerSyncSettings {
+    #[serde(default)]
+    pub enabled: bool,
+    /// TCP port serving day files to peers; takes effect after a restart
+    #[serde(default = "default_port")]
+    pub port: u16,
+    /// Shared secret all peers must use
+    #[serde(default)]
+    pub secret: String,
+}
+
+impl Default for PeerSyncSettings {
+    fn default() -> Self {
+        PeerSyncSettings {
+            enabled: false,
+            port: default_port(),
+            secret: String::new(),
+        }
+    }
+}
+
+/// The change that produced a day file's current contents
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+struct DayVersion {
+    /// Device that made the change
+    origin: String,
+    counter: u64,
+    /// Hash of the file as last written or scanned, to notice local changes
+    hash: u64,
+}
+
+/// This device's view of the synced day files
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq)]
+struct PeerSyncState {
+    clock: VectorClock,
+    days: BTreeMap<NaiveDate, DayVersion>,
+}
+
+/// A request for the day files changed since a clock
+#[derive(Debug, Serialize, Deserialize)]
+struct PullRequest {
+    secret: String,
+    since: VectorClock,
+}
+
+/// A day file sent to a peer
+#[derive(Debug, Serialize, Deserialize)]
+struct DayChange {
+    origin: String,
+    counter: u64,
+    day_data: DayData,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+enum PullResponse {
+    Changes {
+        clock: VectorClock,
+        days: Vec<DayChange>,
+    },
+    Refused {
+        reason: String,
+    },
+}
+
+/// A discovery broadcast
+#[derive(Debug, Serialize, Deserialize)]
+struct Announcement {
+    service: String,
+    peer_id: String,
+    port: u16,
+}
+
+/// Another instance heard on the network
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct Peer {
+    pub peer_id: String,
+    pub address: SocketAddr,
+}
+
+/// What a pull from a peer changed
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct PullReport {
+    /// Days replaced by the peer's version
+    pub updated: Vec<NaiveDate>,
+    /// Days changed on both devices and merged
+    pub merged: Vec<NaiveDate>,
+}
+
+/// Managed state holding the peers heard from
+#[derive(Default)]
+pub struct PeerDirectory {
+    peers: Mutex<HashMap<String, SocketAddr>>,
+}
+
+impl PeerDirectory {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+fn load_settings(data_dir: &Path) -> Result<PeerSyncSettings, String> {
+    let file_path = data_dir.join(PEER_SYNC_SETTINGS_FILE);
+    if !file_path.exists() {
+        return Ok(PeerSyncSettings::default());
+    }
+
+    let content = fs::read_to_string(&file_path)
+        .map_err(|e| format!("Failed to read peer sync settings: {}", e))?;
+    serde_json::from_str(&content).map_err(|e| format!("Failed to parse peer sync settings: {}", e))
+}
+
+fn load_state(data_dir: &Path) -> Result<PeerSyncState, String> {
+    let file_path = data_dir.join(PEER_SYNC_STATE_FILE);
+    if !file_path.exists() {
+        return Ok(PeerSyncState::default());
+    }
+
+    let content = fs::read_to_string(&file_path)
+        .map_err(|e| format!("Failed to read peer sync state: {}", e))?;
+    serde_json::from_str(&content).map_err(|e| format!("Failed to parse peer sync state: {}", e))
+}
+
+fn save_state(data_dir: &Path, state: &PeerSyncState) -> Result<(), String> {
+    let json_content = serde_json::to_string_pretty(state)
+        .map_err(|e| format!("Failed to serialize peer sync state: {}", e))?;
+    fs::write(data_dir.join(PEER_SYNC_STATE_FILE), json_content)
+        .map_err(|e| format!("Failed to write peer sync state: {}", e))
+}
+
+/// FNV-1a hash of file contents. Unlike `DefaultHasher`, the result doesn't change
+/// between Rust releases, so it can be stored.
+pub(crate) fn stable_hash(content: &[u8]) -> u64 {
+    content.iter().fold(0xcbf2_9ce4_8422_2325, |hash, byte| {
+        (hash ^ u64::from(*byte)).wrapping_mul(0x0100_0000_01b3)
+    })
+}
+
+fn file_hash(data_dir: &Path, date: NaiveDate) -> Result<u64, String> {
+    let content = fs::read(crate::day_file_path(data_dir, date))
+        .map_err(|e| format!("Failed to read file: {}", e))?;
+    Ok(stable_hash(&content))
+}
+
+impl PeerSyncState {
+    /// Give a new version to every day file changed since the last scan.
+    fn scan(&mut self, data_dir: &Path, peer_id: &str) -> Result<(), String> {
+        for date in list_day_dates(data_dir)? {
+            let hash = file_hash(data_dir, date)?;
+            if self.days.get(&date).map(|v| v.hash) != Some(hash) {
+                self.bump(peer_id, date, hash);
+            }
+        }
+        Ok(())
+    }
+
+    fn bump(&mut self, peer_id: &str, date: NaiveDate, hash: u64) {
+        let counter = self.clock.entry(peer_id.to_string()).or_default();
+        *counter += 1;
+        self.days.insert(
+            date,
+            DayVersion {
+                origin: peer_id.to_string(),
+                counter: *counter,
+                hash,
+            },
+        );
+    }
+
+    /// Day files with versions the `since` clock hasn't seen.
+    fn changes_since(
+        &self,
+        data_dir: &Path,
+        since: &VectorClock,
+    ) -> Result<Vec<DayChange>, String> {
+        self.days
+            .iter()
+            .filter(|(_, v)| since.get(&v.origin).copied().unwrap_or(0) < v.counter)
+            .map(|(date, v)| {
+                Ok(DayChange {
+                    origin: v.origin.clone(),
+                    counter: v.counter,
+                    day_data: read_day_file(data_dir, *date)?,
+                })
+            })
+            .collect()
+    }
+}
+
+/// Merge two versions of a day changed concurrently.
+///
+/// Todos are merged by ID, taking the winner's version of todos both sides have and
+/// keeping todos only one side has. Both devices pick the same winner, so they end up
+/// with the same day.
+fn merge_days(winner: &DayData, loser: &DayData) -> DayData {
+    let mut todos: Vec<TodoItem> = winner.todos.clone();
+    for (i, todo) in loser.todos.iter().enumerate() {
+        if todos.iter().any(|t| t.id == todo.id) {
+            continue;
+        }
+        let position = loser.todos[..i]
+            .iter()
+            .rev()
+            .find_map(|prev| todos.iter().position(|t| t.id == prev.id))
+            .map_or(0, |p| p + 1);
+        todos.insert(position, todo.clone());
+    }
+
+    let notes = if loser.notes.is_empty() || winner.notes.starts_with(&loser.notes) {
+        winner.notes.clone()
+    } else if loser.notes.starts_with(&winner.notes) {
+        loser.notes.clone()
+    } else {
+        format!("{}\n\n{}", winner.notes, loser.notes)
+    };
+
+    DayData {
+        date: winner.date,
+        todos,
+        notes,
+    }
+}
+
+/// Apply day files pulled from a peer whose clock is `remote_clock`.
+fn integrate(
+    data_dir: &Path,
+    peer_id: &str,
+    state: &mut PeerSyncState,
+    remote_clock: &VectorClock,
+    changes: Vec<DayChange>,
+) -> Result<PullReport, String> {
+    let mut report = PullReport {
+        updated: Vec::new(),
+        merged: Vec::new(),
+    };
+
+    for change in changes {
+        let date = change.day_data.date;
+        if state.clock.get(&change.origin).copied().unwrap_or(0) >= change.counter {
+            continue;
+        }
+
+        let concurrent = state
+            .days
+            .get(&date)
+            .filter(|local| remote_clock.get(&local.origin).copied().unwrap_or(0) < local.counter);
+        match concurrent {
+            Some(local) => {
+                let local_day = read_day_file(data_dir, date)?;
+                let merged = if (&local.origin, local.counter) > (&change.origin, change.counter) {
+                    merge_days(&local_day, &change.day_data)
+                } else {
+                    merge_days(&change.day_data, &local_day)
+                };
+                write_day_file(data_dir, &merged)?;
+                state.bump(peer_id, date, file_hash(data_dir, date)?);
+                report.merged.push(date);
+            }
+            None => {
+                write_day_file(data_dir, &change.day_data)?;
+                state.days.insert(
+                    date,
+                    DayVersion {
+                        origin: change.origin,
+                        counter: change.counter,
+                        hash: file_hash(data_dir, date)?,
+                    },
+                );
+                report.updated.push(date);
+            }
+        }
+    }
+
+    for (device, counter) in remote_clock {
+        let seen = state.clock.entry(device.clone()).or_default();
+        *seen = (*seen).max(*counter);
+    }
+    Ok(report)
+}
+
+/// Read one newline-terminated message of at most `limit` bytes.
+async fn read_message(
+    reader: impl AsyncRead + Unpin,
+    limit: u64,
+    what: &str,
+) -> Result<String, String> {
+    let mut line = String::new();
+    let mut reader = BufReader::new(reader.take(limit));
+    tokio::time::timeout(IO_TIMEOUT, reader.read_line(&mut line))
+        .await
+        .map_err(|_| format!("Timed out reading {}", what))?
+        .map_err(|e| format!("Failed to read {}: {}", what, e))?;
+
+    if !line.ends_with('\n') {
+        return Err(format!("Incomplete or oversized {}", what));
+    }
+    Ok(line)
+}
+
+/// Send one message, followed by a newline.
+async fn write_message(
+    mut writer: impl AsyncWrite + Unpin,
+    message: &impl Serialize,
+    what: &str,
+) -> Result<(), String> {
+    let mut json_content = serde_json::to_string(message)
+        .map_err(|e| format!("Failed to serialize {}: {}", what, e))?;
+    json_content.push('\n');
+    tokio::time::timeout(IO_TIMEOUT, writer.write_all(json_content.as_bytes()))
+        .await
+        .map_err(|_| format!("Timed out sending {}", what))?
+        .map_err(|e| format!("Failed to send {}: {}", what, e))
+}
+
+/// Answer one pull request from a peer.
+pub(crate) async fn serve_connection(
+    stream: TcpStream,
+    data_dir: &Path,
+    peer_id: &str,
+    secret: &str,
+) -> Result<(), String> {
+    let (reader, writer) = stream.into_split();
+    let line = read_message(reader, MAX_REQUEST_BYTES, "request").await?;
+    let request: PullRequest =
+        serde_json::from_str(&line).map_err(|e| format!("Failed to parse request: {}", e))?;
+
+    let response = if secret.is_empty() || request.secret != secret {
+        PullResponse::Refused {
+            reason: "Sync secret does not match".to_string(),
+        }
+    } else {
+        let _guard = crate::DAY_FILES_LOCK.lock().await;
+        let mut state = load_state(data_dir)?;
+        state.scan(data_dir, peer_id)?;
+        save_state(data_dir, &state)?;
+        PullResponse::Changes {
+            days: state.changes_since(data_dir, &request.since)?,
+            clock: state.clock,
+        }
+    };
+
+    write_message(writer, &response, "response").await
+}
+
+/// Pull the day files a peer changed since we last heard from it.
+pub(crate) async fn pull_from(
+    address: SocketAddr,
+    data_dir: &Path,
+    peer_id: &str,
+    secret: &str,
+) -> Result<PullReport, String> {
+    let since = {
+        let _guard = crate::DAY_FILES_LOCK.lock().await;
+        let mut state = load_state(data_dir)?;
+        state.scan(data_dir, peer_id)?;
+        save_state(data_dir, &state)?;
+        state.clock
+    };
+
+    let stream = tokio::time::timeout(IO_TIMEOUT, TcpStream::connect(address))
+        .await
+        .map_err(|_| format!("Timed out connecting to {}", address))?
+        .map_err(|e| format!("Failed to connect to {}: {}", address, e))?;
+    let (reader, writer) = stream.into_split();
+    let request = PullRequest {
+        secret: secret.to_string(),
+        since,
+    };
+    write_message(writer, &request, "request").await?;
+
+    let line = read_message(reader, MAX_RESPONSE_BYTES, "response").await?;
+    let (clock, days) = match serde_json::from_str(&line)
+        .map_err(|e| format!("Failed to parse response: {}", e))?
+    {
+        PullResponse::Changes { clock, days } => (clock, days),
+        PullResponse::Refused { reason } => return Err(format!("Peer refused sync: {}", reason)),
+    };
+
+    let _guard = crate::DAY_FILES_LOCK.lock().await;
+    let mut state = load_state(data_dir)?;
+    // Days saved while the request was in flight need a version before they are
+    // compared with the peer's
+    state.scan(data_dir, peer_id)?;
+    let report = integrate(data_dir, peer_id, &mut state, &clock, days)?;
+    save_state(data_dir, &state)?;
+    Ok(report)
+}
+
+/// Record peers announcing themselves on the network.
+async fn listen_for_peers(app: tauri::AppHandle, socket: UdpSocket, own_id: String) {
+    let directory = app.state::<PeerDirectory>();
+    let mut buffer = [0u8; 1024];
+
+    loop {
+        let Ok((len, from)) = socket.recv_from(&mut buffer).await else {
+            continue;
+        };
+        let Ok(announcement) = serde_json::from_slice::<Announcement>(&buffer[..len]) else {
+            continue;
+        };
+        if announcement.service == SERVICE_NAME && announcement.peer_id != own_id {
+            let address = SocketAddr::new(from.ip(), announcement.port);
+            directory
+                .peers
+                .lock()
+                .await
+                .insert(announcement.peer_id, address);
+        }
+    }
+}
+
+/// Serve pull requests while peer sync is enabled, each connection in its own task.
+async fn accept_peers(app: tauri::AppHandle, listener: TcpListener, own_id: String) {
+    loop {
+        let Ok((stream, _)) = listener.accept().await else {
+            continue;
+        };
+        let (app, own_id) = (app.clone(), own_id.clone());
+
+        tauri::async_runtime::spawn(async move {
+            let result = match crate::resolve_data_dir(&app) {
+                Ok(data_dir) => match load_settings(&data_dir) {
+                    Ok(settings) if settings.enabled => {
+                        serve_connection(stream, &data_dir, &own_id, &settings.secret).await
+                    }
+                    Ok(_) => Ok(()),
+                    Err(e) => Err(e),
+                },
+                Err(e) => Err(e),
+            };
+
+            if let Err(e) = result {
+                #[cfg(debug_assertions)]
+                eprintln!("Peer sync request failed: {}", e);
+                let _ = e;
+            }
+        });
+    }
+}
+
+/// Announce this instance and pull from every known peer.
+async fn sync_round(
+    app: &tauri::AppHandle,
+    socket: &UdpSocket,
+    own_id: &str,
+    data_dir: &Path,
+    settings: &PeerSyncSettings,
+) -> Result<(), String> {
+    let announcement = serde_json::to_vec(&Announcement {
+        service: SERVICE_NAME.to_string(),
+        peer_id: own_id.to_string(),
+        port: settings.port,
+    })
+    .map_err(|e| format!("Failed to serialize announcement: {}", e))?;
+    socket
+        .send_to(&announcement, ("255.255.255.255", DISCOVERY_PORT))
+        .await
+        .map_err(|e| format!("Failed to announce: {}", e))?;
+
+    let directory = app.state::<PeerDirectory>();
+    let peers: Vec<(String, SocketAddr)> = directory
+        .peers
+        .lock()
+        .await
+        .iter()
+        .map(|(peer_id, address)| (peer_id.clone(), *address))
+        .collect();
+
+    let mut changed = false;
+    for (peer_id, address) in peers {
+        match pull_from(address, data_dir, own_id, &settings.secret).await {
+            Ok(report) => changed |= !report.updated.is_empty() || !report.merged.is_empty(),
+            Err(e) => {
+                // Forget peers that went away; they are re-added when heard again
+                directory.peers.lock().await.remove(&peer_id);
+                #[cfg(debug_assertions)]
+                eprintln!("Failed to sync with {}: {}", peer_id, e);
+                let _ = e;
+            }
+        }
+    }
+    if changed {
+        app.emit("peer-sync-complete", ())
+            .map_err(|e| format!("Failed to emit peer sync event: {}", e))?;
+    }
+    Ok(())
+}
+
+/// Background task running peer sync once it is enabled, started from `setup`.
+pub async fn run_peer_sync_service(app: tauri::AppHandle) {
+    let mut socket: Option<UdpSocket> = None;
+
+    loop {
+        tokio::time::sleep(SYNC_INTERVAL).await;
+
+        let result: Result<(), String> = async {
+            let own_id = crate::crdt::device_id().ok_or("Device ID is not set up")?;
+            let data_dir: PathBuf = crate::resolve_data_dir(&app)?;
+            let settings = load_settings(&data_dir)?;
+            if !settings.enabled {
+                return Ok(());
+            }
+
+            if socket.is_none() {
+                let listener = TcpListener::bind(("0.0.0.0", settings.port))
+                    .await
+                    .map_err(|e| format!("Failed to listen on port {}: {}", settings.port, e))?;
+                let discovery = UdpSocket::bind(("0.0.0.0", DISCOVERY_PORT))
+                    .await
+                    .map_err(|e| format!("Failed to bind discovery port: {}", e))?;
+                let sender = UdpSocket::bind(("0.0.0.0", 0))
+                    .await
+                    .map_err(|e| format!("Failed to bind discovery port: {}", e))?;
+                sender
+                    .set_broadcast(true)
+                    .map_err(|e| format!("Failed to enable broadcast: {}", e))?;
+
+                tauri::async_runtime::spawn(accept_peers(
+                    app.clone(),
+                    listener,
+                    own_id.to_string(),
+                ));
+                tauri::async_runtime::spawn(listen_for_peers(
+                    app.clone(),
+                    discovery,
+                    own_id.to_string(),
+                ));
+                socket = Some(sender);
+            }
+
+            match &socket {
+                Some(sender) => sync_round(&app, sender, own_id, &data_dir, &settings).await,
+                None => Ok(()),
+            }
+        }
+        .await;
+
+        if let Err(e) = result {
+            #[cfg(debug_assertions)]
+            eprintln!("Peer sync failed: {}", e);
+            let _ = e;
+        }
+    }
+}
+
+/// Load the peer sync settings.
+///
+/// # Arguments
+/// * `data_dir` - Path to the app data directory
+///
+/// # Errors
+/// Returns an error if the settings file cannot be read.
+#[tauri::command]
+pub async fn load_peer_sync_settings(data_dir: String) -> Result<PeerSyncSettings, String> {
+    load_settings(Path::new(&data_dir))
+}
+
+/// Save the peer sync settings.
+///
+/// # Arguments
+/// * `settings` - The settings to store
+/// * `data_dir` - Path to the app data directory
+///
+/// # Errors
+/// Returns an error if sync is enabled without a secret or saving fails.
+#[tauri::command]
+pub async fn save_peer_sync_settings(
+    settings: PeerSyncSettings,
+    data_dir: String,
+) -> Result<(), String> {
+    if settings.enabled && settings.secret.trim().is_empty() {
+        return Err("Peer sync needs a shared secret".to_string());
+    }
+
+    let json_content = serde_json::to_string_pretty(&settings)
+        .map_err(|e| format!("Failed to serialize peer sync settings: {}", e))?;
+    fs::write(
+        Path::new(&data_dir).join(PEER_SYNC_SETTINGS_FILE),
+        json_content,
+    )
+    .map_err(|e| format!("Failed to write peer sync settings: {}", e))
+}
+
+/// List the peers heard on the local network.
+///
+/// # Arguments
+/// * `directory` - Managed state holding discovered peers
+#[tauri::command]
+pub async fn list_peers(directory: tauri::State<'_, PeerDirectory>) -> Result<Vec<Peer>, String> {
+    let mut peers: Vec<Peer> = directory
+        .peers
+        .lock()
+        .await
+        .iter()
+        .map(|(peer_id, address)| Peer {
+            peer_id: peer_id.clone(),
+            address: *address,
+        })
+        .collect();
+    peers.sort_by(|a, b| a.peer_id.cmp(&b.peer_id));
+    Ok(peers)
+}
+
+/// Pull changes from a peer right away, e.g. one that discovery can't reach.
+///
+/// # Arguments
+/// * `address` - The peer's address, like "192.168.1.20:47475"
+/// * `data_dir` - Path to the app data directory
+///
+/// # Returns
+/// The days that were updated or merged.
+///
+/// # Errors
+/// Returns an error if peer sync is not enabled, the address is invalid, the peer
+/// refuses or can't be reached, or file operations fail.
+#[tauri::command]
+pub async fn sync_with_peer(address: String, data_dir: String) -> Result<PullReport, String> {
+    crate::app_lock::ensure_unlocked()?;
+    let data_dir = Path::new(&data_dir);
+    let settings = load_settings(data_dir)?;
+    if !settings.enabled {
+        return Err("Peer sync is not enabled".to_string());
+    }
+    let address: SocketAddr = address
+        .parse()
+        .map_err(|e| format!("Invalid peer address: {}", e))?;
+    let own_id = crate::crdt::device_id().ok_or("Device ID is not set up")?;
+
+    pull_from(address, data_dir, own_id, &settings.secret).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::TodoStatus;
+    use tempfile::TempDir;
+
+    /// Serve `data_dir` as `peer_id` on a localhost port.
+    async fn spawn_peer(data_dir: PathBuf, peer_id: &'static str) -> SocketAddr {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let address = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            while let Ok((stream, _)) = listener.accept().await {
+                serve_connection(stream, &data_dir, peer_id, "s3cret")
+                    .await
+                    .unwrap();
+            }
+        });
+        address
+    }
+
+    #[tokio::test]
+    async fn test_two_instances_sync_over_localhost() {
+        let laptop_dir = TempDir::new().unwrap();
+        let desktop_dir = TempDir::new().unwrap();
+        let (laptop, desktop) = (laptop_dir.path(), desktop_dir.path());
+        let laptop_address = spawn_peer(laptop.to_path_buf(), "laptop").await;
+        let desktop_address = spawn_peer(desktop.to_path_buf(), "desktop").await;
+
+        let date = NaiveDate::from_ymd_opt(2024, 1, 15).unwrap();
+        let email = TodoItem::new("Email".to_string());
+        let day_data = DayData {
+            date,
+            todos: vec![email.clone()],
+            notes: "Standup".to_string(),
+        };
+        write_day_file(laptop, &day_data).unwrap();
+
+        let report = pull_from(laptop_address, desktop, "desktop", "s3cret")
+            .await
+            .unwrap();
+        assert_eq!(report.updated, vec![date]);
+
+        // Nothing new the second time
+        let report = pull_from(laptop_address, desktop, "desktop", "s3cret")
+            .await
+            .unwrap();
+        assert!(report.updated.is_empty() && report.merged.is_empty());
+
+        // Concurrent edits to the same day
+        let mut on_laptop = read_day_file(laptop, date).unwrap();
+        on_laptop.todos[0].status = TodoStatus::Done;
+        write_day_file(laptop, &on_laptop).unwrap();
+        let mut on_desktop = read_day_file(desktop, date).unwrap();
+        on_desktop.todos.push(TodoItem::new("Gym".to_string()));
+        on_desktop.notes = "Standup\nLunch".to_string();
+        write_day_file(desktop, &on_desktop).unwrap();
+
+        let report = pull_from(desktop_address, laptop, "laptop", "s3cret")
+            .await
+            .unwrap();
+        assert_eq!(report.merged, vec![date]);
+        let report = pull_from(laptop_address, desktop, "desktop", "s3cret")
+            .await
+            .unwrap();
+        assert_eq!(report.updated, vec![date]);
+
+        let (a, b) = (
+            read_day_file(laptop, date).unwrap(),
+            read_day_file(desktop, date).unwrap(),
+        );
+        assert_eq!(
+            serde_json::to_value(&a).unwrap(),
+            serde_json::to_value(&b).unwrap()
+        );
+        assert_eq!(a.todos.len(), 2);
+        assert_eq!(a.todos[0].status, TodoStatus::Done);
+        assert_eq!(a.notes, "Standup\nLunch");
+
+        let refused = pull_from(laptop_address, desktop, "desktop", "wrong").await;
+        assert!(refused.unwrap_err().contains("refused"));
+    }
+
+    #[tokio::test]
+    async fn test_oversized_request_is_rejected() {
+        let temp_dir = TempDir::new().unwrap();
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let address = listener.local_addr().unwrap();
+
+        let client = tokio::spawn(async move {
+            let mut stream = TcpStream::connect(address).await.unwrap();
+            let flood = vec![b'x'; MAX_REQUEST_BYTES as usize + 1];
+            let _ = stream.write_all(&flood).await;
+            stream
+        });
+        let (stream, _) = listener.accept().await.unwrap();
+        let served = serve_connection(stream, temp_dir.path(), "laptop", "s3cret").await;
+        assert!(served.unwrap_err().contains("oversized"));
+        drop(client.await.unwrap());
+    }
+
+    #[test]
+    fn test_stable_hash() {
+        // Reference values of 64-bit FNV-1a
+        assert_eq!(stable_hash(b""), 0xcbf2_9ce4_8422_2325);
+        assert_eq!(stable_hash(b"a"), 0xaf63_dc4c_8601_ec8c);
+    }
+}
@@ -0,0 +1,513 @@
+//! Recurring todos.
+//!
+//! Recurrence rules live in `recurrences.json` in the data directory, separate from
+//! the day files. Instances are materialized into today or a later day the first time
+//! it is opened through `open_day`; past days are never filled in. Each rule
+//! remembers the dates from today on it has generated for, so an instance that was
+//! moved, deleted or completed is never generated again.
+
+use crate::{list_day_dates, read_day_file, write_day_file, DayData, TodoItem, TodoStatus};
+use chrono::{Datelike, Duration, NaiveDate, Weekday};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+use uuid::Uuid;
+
+/// File in the data directory holding the recurrence registry
+const RECURRENCES_FILE: &str = "recurrences.json";
+
+/// When a recurring todo comes due
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum RecurrenceRule {
+    /// Every day
+    Daily,
+    /// Monday to Friday
+    Weekdays,
+    /// Every week on the given days
+    Weekly { days: Vec<Weekday> },
+    /// Every month on a day of the month; short months use their last day
+    MonthlyOnDate { day: u32 },
+    /// Every month on the nth (1-5) occurrence of a weekday, e.g. the 2nd Tuesday
+    MonthlyOnWeekday { nth: u32, weekday: Weekday },
+    /// `days` days after the previous instance was completed
+    AfterCompletion { days: u32 },
+}
+
+impl RecurrenceRule {
+    fn validate(&self) -> Result<(), String> {
+        match self {
+            RecurrenceRule::Weekly { days } if days.is_empty() => {
+                Err("Weekly recurrence needs at least one day".to_string())
+            }
+            RecurrenceRule::MonthlyOnDate { day } if !(1..=31).contains(day) => {
+                Err(format!("Invalid day of month: {}", day))
+            }
+            RecurrenceRule::MonthlyOnWeekday { nth, .. } if !(1..=5).contains(nth) => {
+                Err(format!("Invalid week of month: {}", nth))
+            }
+            RecurrenceRule::AfterCompletion { days: 0 } => {
+                Err("Recurrence interval must be at least one day".to_string())
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Whether a calendar-based rule falls on `date`. Always false for
+    /// `AfterCompletion`, which depends on the previous instance instead.
+    fn falls_on(&self, date: NaiveDate) -> bool {
+        match self {
+            RecurrenceRule::Daily => true,
+            RecurrenceRule::Weekdays => !matches!(date.weekday(), Weekday::Sat | Weekday::Sun),
+            RecurrenceRule::Weekly { days } => days.contains(&date.weekday()),
+            RecurrenceRule::MonthlyOnDate { day } => date.day() == (*day).min(days_in_month(date)),
+            RecurrenceRule::MonthlyOnWeekday { nth, weekday } => {
+                date.weekday() == *weekday && (date.day() - 1) / 7 + 1 == *nth
+            }
+            RecurrenceRule::AfterCompletion { .. } => false,
+        }
+    }
+}
+
+fn days_in_month(date: NaiveDate) -> u32 {
+    let (year, month) = if date.month() == 12 {
+        (date.year() + 1, 1)
+    } else {
+        (date.year(), date.month() + 1)
+    };
+    NaiveDate::from_ymd_opt(year, month, 1)
+        .and_then(|first| first.pred_opt())
+        .map_or(31, |last| last.day())
+}
+
+/// The most recently generated todo of a rule
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct RecurrenceInstance {
+    pub date: NaiveDate,
+    pub todo_id: String,
+}
+
+/// A recurring todo and its generation state
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct Recurrence {
+    pub id: String,
+    pub text: String,
+    pub rule: RecurrenceRule,
+    /// First day instances may be generated for
+    pub start_date: NaiveDate,
+    /// Last day instances may be generated for, if the series ends
+    #[serde(default)]
+    pub end_date: Option<NaiveDate>,
+    /// Days from today on an instance has already been generated for
+    #[serde(default)]
+    pub generated: BTreeSet<NaiveDate>,
+    #[serde(default)]
+    pub last_instance: Option<RecurrenceInstance>,
+}
+
+impl Recurrence {
+    /// Decide whether an instance should be generated on `date`.
+    ///
+    /// `AfterCompletion` rules only generate up to `today`, once the previous instance
+    /// is done, cancelled or deleted and the interval has passed.
+    fn is_due(&self, data_dir: &Path, date: NaiveDate, today: NaiveDate) -> Result<bool, String> {
+        if date < self.start_date
+            || self.end_date.is_some_and(|end| date > end)
+            || self.generated.contains(&date)
+        {
+            return Ok(false);
+        }
+
+        let RecurrenceRule::AfterCompletion { days } = self.rule else {
+            return Ok(self.rule.falls_on(date));
+        };
+        if date > today {
+            return Ok(false);
+        }
+        let Some(last) = &self.last_instance else {
+            return Ok(true);
+        };
+
+        // The instance keeps its ID when migrated, so look for it from where it was
+        // generated onwards
+        let mut finished_on = last.date;
+        for day in list_day_dates(data_dir)?
+            .into_iter()
+            .filter(|day| *day >= last.date)
+        {
+            if let Some(todo) = read_day_file(data_dir, day)?
+                .todos
+                .into_iter()
+                .find(|t| t.id == last.todo_id)
+            {
+                finished_on = match todo.status {
+                    TodoStatus::Done => todo.completed_at.map_or(day, |at| at.date_naive()),
+                    TodoStatus::Cancelled => day,
+                    _ => return Ok(false),
+                };
+                break;
+            }
+        }
+
+        Ok(date >= finished_on + Duration::days(i64::from(days)))
+    }
+}
+
+fn recurrences_path(data_dir: &Path) -> PathBuf {
+    data_dir.join(RECURRENCES_FILE)
+}
+
+fn load_recurrences(data_dir: &Path) -> Result<Vec<Recurrence>, String> {
+    let file_path = recurrences_path(data_dir);
+    if !file_path.exists() {
+        return Ok(Vec::new());
+    }
+
+    let content =
+        fs::read_to_string(&file_path).map_err(|e| format!("Failed to read recurrences: {}", e))?;
+    let content = crate::encryption::open(data_dir, &content)?;
+    serde_json::from_str(&content).map_err(|e| format!("Failed to parse recurrences: {}", e))
+}
+
+fn save_recurrences(data_dir: &Path, recurrences: &[Recurrence]) -> Result<(), String> {
+    let json_content = serde_json::to_string_pretty(recurrences)
+        .map_err(|e| format!("Failed to serialize recurrences: {}", e))?;
+    let json_content = crate::encryption::seal(data_dir, &json_content)?;
+
+    fs::write(recurrences_path(data_dir), json_content)
+        .map_err(|e| format!("Failed to write recurrences: {}", e))
+}
+
+/// Add instances of every recurrence due on `day_data.date` and save the day.
+///
+/// Days before `today` are left alone. A rule is skipped if the day already holds one
+/// of its instances (e.g. one carried over from the previous day). Generated dates
+/// before `today` are dropped from the registry, since those days are never
+/// materialized again. Callers must hold `DAY_FILES_LOCK`.
+pub(crate) fn materialize(
+    data_dir: &Path,
+    day_data: &mut DayData,
+    today: NaiveDate,
+) -> Result<(), String> {
+    let date = day_data.date;
+    if date < today {
+        return Ok(());
+    }
+    let mut recurrences = load_recurrences(data_dir)?;
+
+    let mut day_changed = false;
+    let mut registry_changed = false;
+    for recurrence in recurrences.iter_mut() {
+        let before = recurrence.generated.len();
+        recurrence.generated.retain(|generated| *generated >= today);
+        registry_changed |= recurrence.generated.len() != before;
+
+        if !recurrence.is_due(data_dir, date, today)? {
+            continue;
+        }
+
+        let already_present = day_data
+            .todos
+            .iter()
+            .any(|t| t.recurrence_id.as_deref() == Some(recurrence.id.as_str()));
+        if !already_present {
+            let todo = TodoItem {
+                recurrence_id: Some(recurrence.id.clone()),
+                ..crate::tags::todo_from_input(recurrence.text.clone())
+            };
+            recurrence.last_instance = Some(RecurrenceInstance {
+                date,
+                todo_id: todo.id.clone(),
+            });
+            day_data.todos.push(todo);
+            day_changed = true;
+        }
+
+        recurrence.generated.insert(date);
+        registry_changed = true;
+    }
+
+    if day_changed {
+        write_day_file(data_dir, day_data)?;
+    }
+    if registry_changed {
+        save_recurrences(data_dir, &recurrences)?;
+    }
+
+    Ok(())
+}
+
+/// List all recurring todos.
+///
+/// # Arguments
+/// * `data_dir` - Path to the app data directory
+///
+/// # Errors
+/// Returns an error if the recurrence registry cannot be read.
+#[tauri::command]
+pub async fn list_recurrences(data_dir: String) -> Result<Vec<Recurrence>, String> {
+    crate::app_lock::ensure_unlocked()?;
+    load_recurrences(Path::new(&data_dir))
+}
+
+/// Create a recurring todo.
+///
+/// # Arguments
+/// * `text` - The text of each generated todo
+/// * `rule` - When instances are generated
+/// * `start_date` - First date in YYYY-MM-DD format instances may appear on
+/// * `end_date` - Optional last date in YYYY-MM-DD format
+/// * `data_dir` - Path to the app data directory
+///
+/// # Returns
+/// The new Recurrence.
+///
+/// # Errors
+/// Returns an error if the text is empty, a date or the rule is invalid, or saving
+/// fails.
+#[tauri::command]
+pub async fn add_recurrence(
+    text: String,
+    rule: RecurrenceRule,
+    start_date: String,
+    end_date: Option<String>,
+    data_dir: String,
+) -> Result<Recurrence, String> {
+    crate::app_lock::ensure_unlocked()?;
+    let text = text.trim().to_string();
+    if text.is_empty() {
+        return Err("Todo text cannot be empty".to_string());
+    }
+    rule.validate()?;
+
+    let parse = |date: &str| {
+        NaiveDate::parse_from_str(date, "%Y-%m-%d")
+            .map_err(|e| format!("Invalid date format: {}", e))
+    };
+    let start_date = parse(&start_date)?;
+    let end_date = end_date.as_deref().map(parse).transpose()?;
+
+    let recurrence = Recurrence {
+        id: Uuid::new_v4().to_string(),
+        text,
+        rule,
+        start_date,
+        end_date,
+        generated: BTreeSet::new(),
+        last_instance: None,
+    };
+
+    let data_dir = PathBuf::from(data_dir);
+    let _guard = crate::DAY_FILES_LOCK.lock().await;
+    let mut recurrences = load_recurrences(&data_dir)?;
+    recurrences.push(recurrence.clone());
+    save_recurrences(&data_dir, &recurrences)?;
+
+    Ok(recurrence)
+}
+
+/// Delete a recurring todo. Instances generated so far are kept.
+///
+/// # Arguments
+/// * `recurrence_id` - The unique ID of the recurrence
+/// * `data_dir` - Path to the app data directory
+///
+/// # Errors
+/// Returns an error if the recurrence doesn't exist or saving fails.
+#[tauri::command]
+pub async fn delete_recurrence(recurrence_id: String, data_dir: String) -> Result<(), String> {
+    crate::app_lock::ensure_unlocked()?;
+    let data_dir = PathBuf::from(data_dir);
+    let _guard = crate::DAY_FILES_LOCK.lock().await;
+    let mut recurrences = load_recurrences(&data_dir)?;
+
+    let before = recurrences.len();
+    recurrences.retain(|r| r.id != recurrence_id);
+    if recurrences.len() == before {
+        return Err(format!("Recurrence with ID {} not found", recurrence_id));
+    }
+
+    save_recurrences(&data_dir, &recurrences)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{move_todo_to_date, todos};
+    use chrono::{Local, TimeZone};
+    use tempfile::TempDir;
+
+    fn date(s: &str) -> NaiveDate {
+        NaiveDate::parse_from_str(s, "%Y-%m-%d").unwrap()
+    }
+
+    #[test]
+    fn test_calendar_rules() {
+        // 2024-02-13 is the second Tuesday of a leap-year February
+        let tuesday = date("2024-02-13");
+        assert!(RecurrenceRule::Weekdays.falls_on(tuesday));
+        assert!(!RecurrenceRule::Weekdays.falls_on(date("2024-02-17")));
+        assert!(RecurrenceRule::Weekly {
+            days: vec![Weekday::Tue, Weekday::Thu]
+        }
+        .falls_on(tuesday));
+
+        let second_tuesday = RecurrenceRule::MonthlyOnWeekday {
+            nth: 2,
+            weekday: Weekday::Tue,
+        };
+        assert!(second_tuesday.falls_on(tuesday));
+        assert!(!second_tuesday.falls_on(date("2024-02-20")));
+
+        // The 31st falls back to the last day of shorter months
+        let end_of_month = RecurrenceRule::MonthlyOnDate { day: 31 };
+        assert!(end_of_month.falls_on(date("2024-02-29")));
+        assert!(end_of_month.falls_on(date("2024-01-31")));
+        assert!(!end_of_month.falls_on(date("2024-01-30")));
+    }
+
+    /// Load a day and materialize its recurrences as if it were `today`
+    fn open(dir: &Path, day: &str, today: &str) -> DayData {
+        let mut day_data = read_day_file(dir, date(day)).unwrap();
+        materialize(dir, &mut day_data, date(today)).unwrap();
+        day_data
+    }
+
+    #[tokio::test]
+    async fn test_instances_are_generated_once() {
+        let temp_dir = TempDir::new().unwrap();
+        let dir = temp_dir.path();
+        let data_dir = dir.to_string_lossy().to_string();
+
+        let recurrence = add_recurrence(
+            "Stand-up".to_string(),
+            RecurrenceRule::Weekdays,
+            "2024-01-15".to_string(),
+            None,
+            data_dir.clone(),
+        )
+        .await
+        .unwrap();
+
+        let monday = open(dir, "2024-01-15", "2024-01-15");
+        assert_eq!(monday.todos.len(), 1);
+        assert_eq!(monday.todos[0].recurrence_id, Some(recurrence.id.clone()));
+
+        // Reloading doesn't duplicate, and neither does reloading after a move
+        move_todo_to_date(
+            monday.todos[0].id.clone(),
+            "2024-01-15".to_string(),
+            "2024-01-16".to_string(),
+            data_dir.clone(),
+        )
+        .await
+        .unwrap();
+        let monday = open(dir, "2024-01-15", "2024-01-15");
+        assert_eq!(monday.todos.len(), 1);
+        assert_eq!(monday.todos[0].status, TodoStatus::Migrated);
+
+        // Tuesday already has the carried-over instance
+        let tuesday = open(dir, "2024-01-16", "2024-01-15");
+        assert_eq!(tuesday.todos.len(), 1);
+
+        // Nothing on the weekend
+        assert!(open(dir, "2024-01-20", "2024-01-15").todos.is_empty());
+        assert!(!crate::day_file_path(dir, date("2024-01-20")).exists());
+
+        // Past days are never filled in, and their dates are pruned from the registry
+        assert!(open(dir, "2024-01-17", "2024-01-18").todos.is_empty());
+        assert!(!crate::day_file_path(dir, date("2024-01-17")).exists());
+        assert_eq!(open(dir, "2024-01-19", "2024-01-18").todos.len(), 1);
+        let recurrences = list_recurrences(data_dir).await.unwrap();
+        assert_eq!(
+            recurrences[0].generated,
+            BTreeSet::from([date("2024-01-19")])
+        );
+    }
+
+    #[tokio::test]
+    async fn test_after_completion_waits_for_previous_instance() {
+        let temp_dir = TempDir::new().unwrap();
+        let dir = temp_dir.path();
+        let data_dir = dir.to_string_lossy().to_string();
+
+        add_recurrence(
+            "Water plants".to_string(),
+            RecurrenceRule::AfterCompletion { days: 3 },
+            "2024-01-01".to_string(),
+            None,
+            data_dir.clone(),
+        )
+        .await
+        .unwrap();
+
+        // Each day is opened on the day itself
+        let load = |day: &str| open(dir, day, day);
+
+        let mut first = load("2024-01-01");
+        assert_eq!(first.todos.len(), 1);
+
+        // Still open, so nothing new
+        assert!(load("2024-01-04").todos.is_empty());
+
+        first.todos[0].status = TodoStatus::Done;
+        first.todos[0].completed_at = Local.with_ymd_and_hms(2024, 1, 5, 18, 0, 0).single();
+        write_day_file(dir, &first).unwrap();
+
+        // Due three days after completion
+        assert!(load("2024-01-07").todos.is_empty());
+        let second = load("2024-01-08");
+        assert_eq!(second.todos.len(), 1);
+
+        // A deleted instance counts from the day it was generated for
+        todos::delete_todo(
+            "2024-01-08".to_string(),
+            second.todos[0].id.clone(),
+            data_dir,
+        )
+        .await
+        .unwrap();
+        assert!(load("2024-01-10").todos.is_empty());
+        assert_eq!(load("2024-01-11").todos.len(), 1);
+
+        // Days after `today` can't know about future completions
+        assert!(open(dir, "2024-02-20", "2024-01-31").todos.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_delete_recurrence_and_validation() {
+        let temp_dir = TempDir::new().unwrap();
+        let data_dir = temp_dir.path().to_string_lossy().to_string();
+
+        let invalid = add_recurrence(
+            "Never".to_string(),
+            RecurrenceRule::Weekly { days: Vec::new() },
+            "2024-01-01".to_string(),
+            None,
+            data_dir.clone(),
+        )
+        .await;
+        assert!(invalid.is_err());
+
+        let recurrence = add_recurrence(
+            "Pay rent".to_string(),
+            RecurrenceRule::MonthlyOnDate { day: 1 },
+            "2024-01-01".to_string(),
+            Some("2024-06-30".to_string()),
+            data_dir.clone(),
+        )
+        .await
+        .unwrap();
+        assert_eq!(list_recurrences(data_dir.clone()).await.unwrap().len(), 1);
+
+        delete_recurrence(recurrence.id, data_dir.clone())
+            .await
+            .unwrap();
+        assert!(list_recurrences(data_dir.clone()).await.unwrap().is_empty());
+
+        assert!(open(temp_dir.path(), "2024-02-01", "2024-02-01")
+            .todos
+            .is_empty());
+    }
+}
@@ -0,0 +1,432 @@
+//! Time-of-day reminders for todos.
+//!
+//! The [`ReminderScheduler`] is kept in Tauri managed state. A background task indexes
+//! the `remind_at` times of open todos, rescanning day files whose modification time
+//! changed, and shows a native notification when a reminder comes due. Delivered
+//! reminders are recorded in `reminders_state.json`, so reminders missed while the app
+//! was closed fire once on the next start and none fire twice.
+
+use crate::{list_day_dates, read_day_file, write_day_file, TodoItem, TodoStatus};
+use chrono::{Duration, Local, NaiveDate, NaiveDateTime};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+use tauri::Manager;
+use tauri_plugin_notification::NotificationExt;
+use tokio::sync::{Mutex, Notify};
+
+/// File in the data directory recording delivered reminders
+const REMINDERS_STATE_FILE: &str = "reminders_state.json";
+
+/// How often day files are checked for changed reminders
+const RESCAN_INTERVAL_SECS: u64 = 30;
+
+/// A reminder waiting to be delivered
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct PendingReminder {
+    pub todo_id: String,
+    /// The day the todo is on
+    pub date: NaiveDate,
+    pub text: String,
+    pub remind_at: NaiveDateTime,
+}
+
+/// Delivered reminders: todo ID to the reminder time that was delivered
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+struct DeliveredReminders {
+    delivered: HashMap<String, NaiveDateTime>,
+}
+
+fn load_delivered(data_dir: &Path) -> Result<DeliveredReminders, String> {
+    let file_path = data_dir.join(REMINDERS_STATE_FILE);
+    if !file_path.exists() {
+        return Ok(DeliveredReminders::default());
+    }
+
+    let content = fs::read_to_string(&file_path)
+        .map_err(|e| format!("Failed to read reminder state: {}", e))?;
+    serde_json::from_str(&content).map_err(|e| format!("Failed to parse reminder state: {}", e))
+}
+
+fn save_delivered(data_dir: &Path, delivered: &DeliveredReminders) -> Result<(), String> {
+    let json_content = serde_json::to_string_pretty(delivered)
+        .map_err(|e| format!("Failed to serialize reminder state: {}", e))?;
+
+    fs::write(data_dir.join(REMINDERS_STATE_FILE), json_content)
+        .map_err(|e| format!("Failed to write reminder state: {}", e))
+}
+
+/// In-memory index of pending reminders
+#[derive(Debug, Default)]
+struct ReminderIndex {
+    /// Data directory the index was built from
+    data_dir: Option<PathBuf>,
+    pending: HashMap<String, PendingReminder>,
+    /// Modification time of each day file when it was last indexed
+    scanned: HashMap<NaiveDate, SystemTime>,
+}
+
+impl ReminderIndex {
+    /// Re-index day files that changed since the last scan.
+    fn refresh(&mut self, data_dir: &Path) -> Result<(), String> {
+        // Start over when the data directory changes, e.g. on a workspace switch
+        if self.data_dir.as_deref() != Some(data_dir) {
+            *self = ReminderIndex {
+                data_dir: Some(data_dir.to_path_buf()),
+                ..ReminderIndex::default()
+            };
+        }
+
+        let delivered = load_delivered(data_dir)?;
+        let dates = list_day_dates(data_dir)?;
+
+        // Forget days whose files are gone
+        self.scanned.retain(|date, _| dates.contains(date));
+        self.pending
+            .retain(|_, reminder| dates.contains(&reminder.date));
+
+        for date in dates {
+            let modified = fs::metadata(crate::day_file_path(data_dir, date))
+                .and_then(|metadata| metadata.modified())
+                .map_err(|e| format!("Failed to read file metadata: {}", e))?;
+            if self.scanned.get(&date) == Some(&modified) {
+                continue;
+            }
+
+            self.pending.retain(|_, reminder| reminder.date != date);
+            for todo in read_day_file(data_dir, date)?.todos {
+                if let Some(reminder) = pending_reminder(&todo, date, &delivered) {
+                    self.pending.insert(todo.id.clone(), reminder);
+                }
+            }
+            self.scanned.insert(date, modified);
+        }
+
+        Ok(())
+    }
+
+    /// Remove and return reminders due at `now`, earliest first.
+    fn take_due(&mut self, now: NaiveDateTime) -> Vec<PendingReminder> {
+        let due_ids: Vec<String> = self
+            .pending
+            .values()
+            .filter(|reminder| reminder.remind_at <= now)
+            .map(|reminder| reminder.todo_id.clone())
+            .collect();
+
+        let mut due: Vec<PendingReminder> = due_ids
+            .iter()
+            .filter_map(|id| self.pending.remove(id))
+            .collect();
+        due.sort_by_key(|reminder| reminder.remind_at);
+        due
+    }
+
+    fn next_due(&self) -> Option<NaiveDateTime> {
+        self.pending
+            .values()
+            .map(|reminder| reminder.remind_at)
+            .min()
+    }
+}
+
+/// The reminder of an open todo, unless it has already been delivered.
+fn pending_reminder(
+    todo: &TodoItem,
+    date: NaiveDate,
+    delivered: &DeliveredReminders,
+) -> Option<PendingReminder> {
+    let remind_at = todo.remind_at?;
+    if todo.status != TodoStatus::Open || delivered.delivered.get(&todo.id) == Some(&remind_at) {
+        return None;
+    }
+
+    Some(PendingReminder {
+        todo_id: todo.id.clone(),
+        date,
+        text: todo.text.clone(),
+        remind_at,
+    })
+}
+
+/// Managed state driving reminder notifications
+#[derive(Default)]
+pub struct ReminderScheduler {
+    index: Mutex<ReminderIndex>,
+    /// Wakes the scheduler early when a reminder is set or snoozed
+    changed: Notify,
+}
+
+impl ReminderScheduler {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+/// Notification body for a reminder; the todo text stays hidden while the app is locked.
+fn reminder_body(reminder: &PendingReminder, locked: bool) -> String {
+    if locked {
+        "Unlock the app to see this reminder".to_string()
+    } else {
+        reminder.text.clone()
+    }
+}
+
+fn show_reminder(app: &tauri::AppHandle, reminder: &PendingReminder) -> Result<(), String> {
+    app.notification()
+        .builder()
+        .title("⏰ Reminder")
+        .body(reminder_body(reminder, crate::app_lock::is_locked()))
+        .sound("default")
+        .show()
+        .map_err(|e| format!("Failed to show notification: {}", e))
+}
+
+/// Deliver due reminders and return how long to wait before checking again.
+async fn deliver_due(
+    app: &tauri::AppHandle,
+    scheduler: &ReminderScheduler,
+) -> Result<std::time::Duration, String> {
+    let data_dir = crate::resolve_data_dir(app)?;
+    let now = Local::now().naive_local();
+
+    let mut index = scheduler.index.lock().await;
+    index.refresh(&data_dir)?;
+
+    let due = index.take_due(now);
+    if !due.is_empty() {
+        let mut delivered = load_delivered(&data_dir)?;
+        for reminder in due {
+            // Recorded as delivered even if the notification fails, so a broken
+            // notification daemon doesn't cause a retry every few seconds
+            if let Err(e) = show_reminder(app, &reminder) {
+                #[cfg(debug_assertions)]
+                eprintln!("Failed to show reminder: {}", e);
+                let _ = e;
+            }
+            delivered
+                .delivered
+                .insert(reminder.todo_id, reminder.remind_at);
+        }
+        save_delivered(&data_dir, &delivered)?;
+    }
+
+    let rescan = std::time::Duration::from_secs(RESCAN_INTERVAL_SECS);
+    Ok(index
+        .next_due()
+        .and_then(|next| (next - now).to_std().ok())
+        .map_or(rescan, |until_next| until_next.min(rescan)))
+}
+
+/// Background task delivering reminders, started from `setup`.
+///
+/// The first pass indexes every day file, which restores pending reminders after a
+/// restart and delivers those missed while the app was closed.
+pub async fn run_reminder_scheduler(app: tauri::AppHandle) {
+    let scheduler = app.state::<ReminderScheduler>();
+
+    loop {
+        let wait = match deliver_due(&app, &scheduler).await {
+            Ok(wait) => wait,
+            Err(e) => {
+                #[cfg(debug_assertions)]
+                eprintln!("Reminder check failed: {}", e);
+                let _ = e;
+                std::time::Duration::from_secs(RESCAN_INTERVAL_SECS)
+            }
+        };
+
+        tokio::select! {
+            _ = tokio::time::sleep(wait) => {}
+            _ = scheduler.changed.notified() => {}
+        }
+    }
+}
+
+/// Set `remind_at` on the todo with the given ID, wherever it is.
+///
+/// # Returns
+/// The updated todo.
+async fn update_reminder(
+    data_dir: &Path,
+    todo_id: &str,
+    date: Option<NaiveDate>,
+    remind_at: Option<NaiveDateTime>,
+) -> Result<TodoItem, String> {
+    let _guard = crate::DAY_FILES_LOCK.lock().await;
+
+    let dates = match date {
+        Some(date) => vec![date],
+        None => list_day_dates(data_dir)?,
+    };
+    for date in dates {
+        let mut day_data = read_day_file(data_dir, date)?;
+        if let Some(todo) = day_data.todos.iter_mut().find(|t| t.id == todo_id) {
+            todo.remind_at = remind_at;
+            let todo = todo.clone();
+            write_day_file(data_dir, &day_data)?;
+            return Ok(todo);
+        }
+    }
+
+    Err(format!("Todo with ID {} not found", todo_id))
+}
+
+/// Set or clear the reminder time of a todo.
+///
+/// # Arguments
+/// * `date` - Date string in YYYY-MM-DD format of the day the todo is on
+/// * `todo_id` - The unique ID of the todo item
+/// * `remind_at` - Local date and time to be reminded at, or `None` to remove it
+/// * `data_dir` - Path to the app data directory
+/// * `scheduler` - Reminder scheduler (automatically injected by Tauri)
+///
+/// # Returns
+/// The updated TodoItem.
+///
+/// # Errors
+/// Returns an error if the date is invalid, the todo doesn't exist, or saving fails.
+#[tauri::command]
+pub async fn set_reminder(
+    date: String,
+    todo_id: String,
+    remind_at: Option<NaiveDateTime>,
+    data_dir: String,
+    scheduler: tauri::State<'_, ReminderScheduler>,
+) -> Result<TodoItem, String> {
+    crate::app_lock::ensure_unlocked()?;
+    let date = NaiveDate::parse_from_str(&date, "%Y-%m-%d")
+        .map_err(|e| format!("Invalid date format: {}", e))?;
+
+    let todo = update_reminder(Path::new(&data_dir), &todo_id, Some(date), remind_at).await?;
+    scheduler.changed.notify_one();
+    Ok(todo)
+}
+
+/// Remind about a todo again after a number of minutes.
+///
+/// # Arguments
+/// * `id` - The unique ID of the todo item
+/// * `minutes` - Minutes from now to deliver the reminder again
+/// * `data_dir` - Path to the app data directory
+/// * `scheduler` - Reminder scheduler (automatically injected by Tauri)
+///
+/// # Returns
+/// The updated TodoItem.
+///
+/// # Errors
+/// Returns an error if `minutes` is zero, the todo doesn't exist, or saving fails.
+#[tauri::command]
+pub async fn snooze_reminder(
+    id: String,
+    minutes: u32,
+    data_dir: String,
+    scheduler: tauri::State<'_, ReminderScheduler>,
+) -> Result<TodoItem, String> {
+    crate::app_lock::ensure_unlocked()?;
+    if minutes == 0 {
+        return Err("Snooze duration must be at least one minute".to_string());
+    }
+
+    let remind_at = Local::now().naive_local() + Duration::minutes(i64::from(minutes));
+    let todo = update_reminder(Path::new(&data_dir), &id, None, Some(remind_at)).await?;
+    scheduler.changed.notify_one();
+    Ok(todo)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::DayData;
+    use tempfile::TempDir;
+
+    fn at(s: &str) -> NaiveDateTime {
+        NaiveDateTime::parse_from_str(s, "%Y-%m-%d %H:%M").unwrap()
+    }
+
+    fn write_day(data_dir: &Path, todos: Vec<TodoItem>) {
+        let day_data = DayData {
+            date: NaiveDate::from_ymd_opt(2024, 1, 15).unwrap(),
+            todos,
+            notes: String::new(),
+        };
+        write_day_file(data_dir, &day_data).unwrap();
+    }
+
+    fn with_reminder(text: &str, remind_at: &str) -> TodoItem {
+        TodoItem {
+            remind_at: Some(at(remind_at)),
+            ..TodoItem::new(text.to_string())
+        }
+    }
+
+    #[test]
+    fn test_index_tracks_open_todos_and_delivery() {
+        let temp_dir = TempDir::new().unwrap();
+        let call = with_reminder("Call plumber", "2024-01-15 09:00");
+        let mut done = with_reminder("Already done", "2024-01-15 08:00");
+        done.status = TodoStatus::Done;
+        let lunch = with_reminder("Lunch", "2024-01-15 12:00");
+        write_day(temp_dir.path(), vec![call.clone(), done, lunch.clone()]);
+
+        let mut index = ReminderIndex::default();
+        index.refresh(temp_dir.path()).unwrap();
+        assert_eq!(index.pending.len(), 2);
+        assert_eq!(index.next_due(), Some(at("2024-01-15 09:00")));
+
+        let due = index.take_due(at("2024-01-15 10:00"));
+        assert_eq!(due.len(), 1);
+        assert_eq!(due[0].todo_id, call.id);
+        assert_eq!(reminder_body(&due[0], false), "Call plumber");
+        assert!(!reminder_body(&due[0], true).contains("plumber"));
+        assert_eq!(index.next_due(), Some(at("2024-01-15 12:00")));
+
+        // A delivered reminder stays delivered after a restart
+        let delivered = DeliveredReminders {
+            delivered: HashMap::from([(call.id.clone(), at("2024-01-15 09:00"))]),
+        };
+        save_delivered(temp_dir.path(), &delivered).unwrap();
+        let mut restarted = ReminderIndex::default();
+        restarted.refresh(temp_dir.path()).unwrap();
+        assert_eq!(restarted.pending.len(), 1);
+        assert!(restarted.pending.contains_key(&lunch.id));
+    }
+
+    #[tokio::test]
+    async fn test_changed_files_are_reindexed() {
+        let temp_dir = TempDir::new().unwrap();
+        let call = with_reminder("Call plumber", "2024-01-15 09:00");
+        write_day(temp_dir.path(), vec![call.clone()]);
+
+        let mut index = ReminderIndex::default();
+        index.refresh(temp_dir.path()).unwrap();
+        index.take_due(at("2024-01-15 10:00"));
+        save_delivered(
+            temp_dir.path(),
+            &DeliveredReminders {
+                delivered: HashMap::from([(call.id.clone(), at("2024-01-15 09:00"))]),
+            },
+        )
+        .unwrap();
+
+        // Snoozing gives the todo a new time, which is pending again
+        std::thread::sleep(std::time::Duration::from_millis(20));
+        let snoozed = update_reminder(
+            temp_dir.path(),
+            &call.id,
+            None,
+            Some(at("2024-01-15 10:15")),
+        )
+        .await
+        .unwrap();
+        assert_eq!(snoozed.remind_at, Some(at("2024-01-15 10:15")));
+
+        index.refresh(temp_dir.path()).unwrap();
+        assert_eq!(index.next_due(), Some(at("2024-01-15 10:15")));
+
+        let missing = update_reminder(temp_dir.path(), "missing", None, None).await;
+        assert!(missing.unwrap_err().contains("not found"));
+    }
+}
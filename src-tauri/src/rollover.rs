@@ -0,0 +1,353 @@
+//! Automatic end-of-day rollover.
+//!
+//! On the first launch of a new day (or, for encrypted data, once it is unlocked), and
+//! at local midnight while the app keeps running, open todos from the most recent
+//! earlier day are carried forward to today using the same semantics as
+//! `move_todo_to_date`: the source day keeps a migrated entry. Which todos move is
+//! controlled by [`RolloverPolicy`].
+
+use crate::{list_day_dates, move_todos_between, read_day_file, TodoItem, TodoStatus};
+use chrono::{DateTime, Local, NaiveDate, TimeZone};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::Path;
+use tauri::Emitter;
+
+/// Preference file holding the rollover policy
+const ROLLOVER_SETTINGS_FILE: &str = "rollover_settings.json";
+
+/// File remembering the last day a rollover ran for
+const ROLLOVER_STATE_FILE: &str = "rollover_state.json";
+
+/// Wakes the scheduler before midnight, e.g. once encrypted data has been unlocked
+static RETRY: tokio::sync::Notify = tokio::sync::Notify::const_new();
+
+/// Which todos are carried forward at the end of a day
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum RolloverPolicy {
+    /// Never move todos automatically
+    Off,
+    /// Move open todos flagged with `move_to_next_day`
+    #[default]
+    Flagged,
+    /// Move every open todo
+    AllIncomplete,
+}
+
+impl RolloverPolicy {
+    fn carries(self, todo: &TodoItem) -> bool {
+        todo.status == TodoStatus::Open
+            && match self {
+                RolloverPolicy::Off => false,
+                RolloverPolicy::Flagged => todo.move_to_next_day,
+                RolloverPolicy::AllIncomplete => true,
+            }
+    }
+}
+
+/// User-configurable rollover settings
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq)]
+pub struct RolloverSettings {
+    #[serde(default)]
+    pub policy: RolloverPolicy,
+}
+
+/// Bookkeeping so each day is rolled over at most once
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+struct RolloverState {
+    last_rollover: Option<NaiveDate>,
+}
+
+/// What a rollover moved, sent to the frontend as a "rollover-complete" event
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct RolloverReport {
+    pub from_date: NaiveDate,
+    pub to_date: NaiveDate,
+    pub moved: Vec<TodoItem>,
+}
+
+/// Internal helper: Load a JSON file, or the type's default if it doesn't exist
+fn load_or_default<T: for<'de> Deserialize<'de> + Default>(file_path: &Path) -> Result<T, String> {
+    if !file_path.exists() {
+        return Ok(T::default());
+    }
+
+    let content = fs::read_to_string(file_path)
+        .map_err(|e| format!("Failed to read {}: {}", file_path.display(), e))?;
+    serde_json::from_str(&content)
+        .map_err(|e| format!("Failed to parse {}: {}", file_path.display(), e))
+}
+
+/// Internal helper: Save a value as pretty JSON
+fn save_json<T: Serialize>(value: &T, file_path: &Path) -> Result<(), String> {
+    let json_str = serde_json::to_string_pretty(value)
+        .map_err(|e| format!("Failed to serialize {}: {}", file_path.display(), e))?;
+    fs::write(file_path, json_str)
+        .map_err(|e| format!("Failed to write {}: {}", file_path.display(), e))
+}
+
+/// Carry todos forward from the most recent day before `today`.
+///
+/// Does nothing if a rollover already ran for `today`. The most recent earlier day
+/// with a file is used as the source, so a gap of several days (e.g. a weekend with
+/// the app closed) still rolls over the last day that was actually used.
+///
+/// # Returns
+/// A report of the moved todos, or `None` if nothing was moved.
+pub(crate) async fn run_rollover(
+    data_dir: &Path,
+    today: NaiveDate,
+) -> Result<Option<RolloverReport>, String> {
+    let _guard = crate::DAY_FILES_LOCK.lock().await;
+
+    let state_path = data_dir.join(ROLLOVER_STATE_FILE);
+    let state: RolloverState = load_or_default(&state_path)?;
+    if state.last_rollover.is_some_and(|last| last >= today) {
+        return Ok(None);
+    }
+
+    let settings: RolloverSettings = load_or_default(&data_dir.join(ROLLOVER_SETTINGS_FILE))?;
+
+    let previous_day = list_day_dates(data_dir)?
+        .into_iter()
+        .rfind(|date| *date < today);
+
+    let mut report = None;
+    if let Some(from_date) = previous_day {
+        let ids: Vec<String> = read_day_file(data_dir, from_date)?
+            .todos
+            .iter()
+            .filter(|todo| settings.policy.carries(todo))
+            .map(|todo| todo.id.clone())
+            .collect();
+
+        let moved = move_todos_between(data_dir, from_date, today, &ids)?;
+        if !moved.is_empty() {
+            report = Some(RolloverReport {
+                from_date,
+                to_date: today,
+                moved,
+            });
+        }
+    }
+
+    save_json(
+        &RolloverState {
+            last_rollover: Some(today),
+        },
+        &state_path,
+    )?;
+
+    Ok(report)
+}
+
+/// Time left until the next local midnight.
+fn until_next_midnight(now: DateTime<Local>) -> std::time::Duration {
+    let next_midnight = now
+        .date_naive()
+        .succ_opt()
+        .and_then(|tomorrow| tomorrow.and_hms_opt(0, 0, 0))
+        .and_then(|midnight| Local.from_local_datetime(&midnight).earliest());
+
+    match next_midnight {
+        Some(midnight) => (midnight - now)
+            .to_std()
+            .unwrap_or(std::time::Duration::from_secs(60)),
+        // Midnight skipped by a DST change; check again in an hour
+        None => std::time::Duration::from_secs(60 * 60),
+    }
+}
+
+/// Run the rollover again now, e.g. because it failed while the data was locked.
+///
+/// Runs for today are recorded, so this is a no-op if the rollover already happened.
+pub(crate) fn retry() {
+    RETRY.notify_one();
+}
+
+/// Background task running the rollover at startup, after every local midnight and
+/// whenever [`retry`] is called.
+///
+/// Emits a "rollover-complete" event with a [`RolloverReport`] whenever todos move.
+pub async fn run_rollover_scheduler(app: tauri::AppHandle) {
+    loop {
+        let today = Local::now().date_naive();
+        let result = match crate::resolve_data_dir(&app) {
+            Ok(data_dir) => run_rollover(&data_dir, today).await,
+            Err(e) => Err(e),
+        };
+
+        match result {
+            Ok(Some(report)) => {
+                if let Err(e) = app.emit("rollover-complete", &report) {
+                    #[cfg(debug_assertions)]
+                    eprintln!("Failed to emit rollover-complete event: {}", e);
+                    let _ = e;
+                }
+            }
+            Ok(None) => {}
+            Err(e) => {
+                #[cfg(debug_assertions)]
+                eprintln!("Rollover failed: {}", e);
+                let _ = e;
+            }
+        }
+
+        // Sleep past midnight so the next iteration sees the new date
+        let wait = until_next_midnight(Local::now()) + std::time::Duration::from_secs(1);
+        tokio::select! {
+            _ = tokio::time::sleep(wait) => {}
+            _ = RETRY.notified() => {}
+        }
+    }
+}
+
+/// Load the rollover settings.
+///
+/// # Arguments
+/// * `app` - Tauri app handle for accessing app data directory
+///
+/// # Errors
+/// Returns an error if the settings file cannot be read.
+#[tauri::command]
+pub fn load_rollover_settings(app: tauri::AppHandle) -> Result<RolloverSettings, String> {
+    let data_dir = crate::resolve_data_dir(&app)?;
+    load_or_default(&data_dir.join(ROLLOVER_SETTINGS_FILE))
+}
+
+/// Save the rollover settings.
+///
+/// # Arguments
+/// * `settings` - The rollover policy to apply from the next rollover on
+/// * `app` - Tauri app handle for accessing app data directory
+///
+/// # Errors
+/// Returns an error if the settings cannot be saved.
+#[tauri::command]
+pub fn save_rollover_settings(
+    settings: RolloverSettings,
+    app: tauri::AppHandle,
+) -> Result<(), String> {
+    let data_dir = crate::resolve_data_dir(&app)?;
+    save_json(&settings, &data_dir.join(ROLLOVER_SETTINGS_FILE))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{write_day_file, DayData};
+    use tempfile::TempDir;
+
+    fn date(s: &str) -> NaiveDate {
+        NaiveDate::parse_from_str(s, "%Y-%m-%d").unwrap()
+    }
+
+    fn todo(text: &str, status: TodoStatus, flagged: bool) -> TodoItem {
+        TodoItem {
+            status,
+            move_to_next_day: flagged,
+            ..TodoItem::new(text.to_string())
+        }
+    }
+
+    /// Friday with a flagged todo, an unflagged open todo and a done todo
+    fn write_friday(data_dir: &Path) {
+        let day_data = DayData {
+            date: date("2024-01-12"),
+            todos: vec![
+                TodoItem {
+                    deadline: Some(crate::Deadline {
+                        date: date("2024-01-19"),
+                        time: None,
+                    }),
+                    ..todo("Flagged", TodoStatus::Open, true)
+                },
+                todo("Unflagged", TodoStatus::Open, false),
+                todo("Finished", TodoStatus::Done, true),
+            ],
+            notes: String::new(),
+        };
+        write_day_file(data_dir, &day_data).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_rollover_moves_flagged_todos_from_last_used_day() {
+        let temp_dir = TempDir::new().unwrap();
+        write_friday(temp_dir.path());
+
+        // Monday: the weekend had no files, so Friday is the previous day
+        let report = run_rollover(temp_dir.path(), date("2024-01-15"))
+            .await
+            .unwrap()
+            .unwrap();
+
+        assert_eq!(report.from_date, date("2024-01-12"));
+        assert_eq!(report.moved.len(), 1);
+        assert_eq!(report.moved[0].text, "Flagged");
+        assert!(!report.moved[0].move_to_next_day);
+        assert_eq!(report.moved[0].deadline.unwrap().date, date("2024-01-19"));
+
+        let monday = read_day_file(temp_dir.path(), date("2024-01-15")).unwrap();
+        assert_eq!(monday.todos.len(), 1);
+        let friday = read_day_file(temp_dir.path(), date("2024-01-12")).unwrap();
+        assert_eq!(friday.todos[0].status, TodoStatus::Migrated);
+        assert_eq!(friday.todos[0].migrated_to, Some(date("2024-01-15")));
+
+        // A second run on the same day does nothing
+        assert!(run_rollover(temp_dir.path(), date("2024-01-15"))
+            .await
+            .unwrap()
+            .is_none());
+    }
+
+    #[tokio::test]
+    async fn test_rollover_all_incomplete_policy() {
+        let temp_dir = TempDir::new().unwrap();
+        write_friday(temp_dir.path());
+        save_json(
+            &RolloverSettings {
+                policy: RolloverPolicy::AllIncomplete,
+            },
+            &temp_dir.path().join(ROLLOVER_SETTINGS_FILE),
+        )
+        .unwrap();
+
+        let report = run_rollover(temp_dir.path(), date("2024-01-15"))
+            .await
+            .unwrap()
+            .unwrap();
+
+        let texts: Vec<&str> = report.moved.iter().map(|t| t.text.as_str()).collect();
+        assert_eq!(texts, vec!["Flagged", "Unflagged"]);
+    }
+
+    #[tokio::test]
+    async fn test_rollover_off_policy_moves_nothing() {
+        let temp_dir = TempDir::new().unwrap();
+        write_friday(temp_dir.path());
+        save_json(
+            &RolloverSettings {
+                policy: RolloverPolicy::Off,
+            },
+            &temp_dir.path().join(ROLLOVER_SETTINGS_FILE),
+        )
+        .unwrap();
+
+        assert!(run_rollover(temp_dir.path(), date("2024-01-15"))
+            .await
+            .unwrap()
+            .is_none());
+        let friday = read_day_file(temp_dir.path(), date("2024-01-12")).unwrap();
+        assert_eq!(friday.todos[0].status, TodoStatus::Open);
+    }
+
+    #[test]
+    fn test_until_next_midnight() {
+        let now = Local.with_ymd_and_hms(2024, 1, 15, 23, 30, 0).unwrap();
+        assert_eq!(
+            until_next_midnight(now),
+            std::time::Duration::from_secs(30 * 60)
+        );
+    }
+}
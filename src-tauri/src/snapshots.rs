@@ -0,0 +1,507 @@
+//! Scheduled snapshots of the data directory.
+//!
+//! A snapshot is a copy of the data directory (day files, collections, preferences,
+//! day history and CRDT day documents) stored under `snapshots/YYYY-MM-DD/`. Earlier
+//! snapshots and git's own files are left out. The scheduler takes one snapshot per
+//! day while the app is running and refreshes the current day's snapshot on exit. Old
+//! snapshots are pruned according to [`SnapshotSettings`].
+
+use chrono::{Datelike, Local, NaiveDate};
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Name of the snapshot folder inside the data directory
+pub(crate) const SNAPSHOTS_DIR: &str = "snapshots";
+
+/// Preference file holding the retention policy
+const SNAPSHOT_SETTINGS_FILE: &str = "snapshot_settings.json";
+
+/// Directories of the data directory that never go into a snapshot
+const EXCLUDED_DIRS: &[&str] = &[SNAPSHOTS_DIR, ".git"];
+
+/// How often the background task checks whether today's snapshot exists
+const SNAPSHOT_CHECK_INTERVAL: std::time::Duration = std::time::Duration::from_secs(60 * 60);
+
+/// Retention policy for automatic snapshots
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct SnapshotSettings {
+    /// Number of most recent daily snapshots to keep
+    pub keep_daily: usize,
+    /// Number of additional weekly snapshots (newest per ISO week) to keep
+    pub keep_weekly: usize,
+}
+
+impl Default for SnapshotSettings {
+    fn default() -> Self {
+        SnapshotSettings {
+            keep_daily: 7,
+            keep_weekly: 4,
+        }
+    }
+}
+
+/// Summary of a stored snapshot, as shown to the frontend
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct SnapshotInfo {
+    pub date: NaiveDate,
+    pub file_count: usize,
+}
+
+fn snapshots_root(data_dir: &Path) -> PathBuf {
+    data_dir.join(SNAPSHOTS_DIR)
+}
+
+fn snapshot_path(data_dir: &Path, date: NaiveDate) -> PathBuf {
+    snapshots_root(data_dir).join(date.format("%Y-%m-%d").to_string())
+}
+
+/// Internal helper: Load snapshot settings from a file path
+///
+/// Falls back to the default policy if the file doesn't exist.
+fn load_snapshot_settings_from_path(file_path: &Path) -> Result<SnapshotSettings, String> {
+    if !file_path.exists() {
+        return Ok(SnapshotSettings::default());
+    }
+
+    let content = fs::read_to_string(file_path)
+        .map_err(|e| format!("Failed to read snapshot settings: {}", e))?;
+
+    serde_json::from_str(&content).map_err(|e| format!("Failed to parse snapshot settings: {}", e))
+}
+
+/// Internal helper: Save snapshot settings to a file path
+fn save_snapshot_settings_to_path(
+    settings: &SnapshotSettings,
+    file_path: &Path,
+) -> Result<(), String> {
+    let json_str = serde_json::to_string_pretty(settings)
+        .map_err(|e| format!("Failed to serialize snapshot settings: {}", e))?;
+
+    fs::write(file_path, json_str).map_err(|e| format!("Failed to write snapshot settings: {}", e))
+}
+
+/// Copy the data directory, except [`EXCLUDED_DIRS`], into the snapshot for `date`.
+///
+/// The copy is written to a temporary folder first and renamed into place, so an
+/// interrupted snapshot never replaces a complete one.
+///
+/// # Returns
+/// `false` if a snapshot for `date` already exists and `overwrite` is not set.
+fn create_snapshot(data_dir: &Path, date: NaiveDate, overwrite: bool) -> Result<bool, String> {
+    let target = snapshot_path(data_dir, date);
+    if target.exists() && !overwrite {
+        return Ok(false);
+    }
+
+    let staging = snapshots_root(data_dir).join(format!(".tmp-{}", date.format("%Y-%m-%d")));
+    if staging.exists() {
+        fs::remove_dir_all(&staging)
+            .map_err(|e| format!("Failed to clear snapshot staging folder: {}", e))?;
+    }
+    fs::create_dir_all(&staging).map_err(|e| format!("Failed to create snapshot folder: {}", e))?;
+
+    let entries =
+        fs::read_dir(data_dir).map_err(|e| format!("Failed to read data directory: {}", e))?;
+    for entry in entries {
+        let entry = entry.map_err(|e| format!("Failed to read data directory: {}", e))?;
+        let path = entry.path();
+        let excluded = EXCLUDED_DIRS.iter().any(|dir| entry.file_name() == *dir);
+        if path.is_file() || (path.is_dir() && !excluded) {
+            crate::workspaces::copy_recursively(&path, &staging.join(entry.file_name()))?;
+        }
+    }
+
+    if target.exists() {
+        fs::remove_dir_all(&target)
+            .map_err(|e| format!("Failed to replace existing snapshot: {}", e))?;
+    }
+    fs::rename(&staging, &target).map_err(|e| format!("Failed to finalize snapshot: {}", e))?;
+
+    Ok(true)
+}
+
+/// List the dates of all stored snapshots, newest first.
+fn snapshot_dates(data_dir: &Path) -> Result<Vec<NaiveDate>, String> {
+    let root = snapshots_root(data_dir);
+    if !root.exists() {
+        return Ok(Vec::new());
+    }
+
+    let entries = fs::read_dir(&root).map_err(|e| format!("Failed to read snapshots: {}", e))?;
+    let mut dates: Vec<NaiveDate> = entries
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| entry.path().is_dir())
+        .filter_map(|entry| {
+            NaiveDate::parse_from_str(&entry.file_name().to_string_lossy(), "%Y-%m-%d").ok()
+        })
+        .collect();
+
+    dates.sort_unstable_by(|a, b| b.cmp(a));
+    Ok(dates)
+}
+
+/// Decide which snapshots survive the retention policy.
+///
+/// The newest `keep_daily` snapshots are always kept. Beyond those, the newest
+/// snapshot of each of the `keep_weekly` most recent ISO weeks not already covered
+/// by a daily snapshot is kept as well.
+fn snapshots_to_keep(
+    dates_newest_first: &[NaiveDate],
+    settings: &SnapshotSettings,
+) -> HashSet<NaiveDate> {
+    let mut keep: HashSet<NaiveDate> = dates_newest_first
+        .iter()
+        .take(settings.keep_daily)
+        .copied()
+        .collect();
+
+    let mut covered_weeks: HashSet<(i32, u32)> = keep
+        .iter()
+        .map(|d| (d.iso_week().year(), d.iso_week().week()))
+        .collect();
+
+    let mut weekly_kept = 0;
+    for date in dates_newest_first.iter().skip(settings.keep_daily) {
+        if weekly_kept >= settings.keep_weekly {
+            break;
+        }
+        let week = (date.iso_week().year(), date.iso_week().week());
+        if covered_weeks.insert(week) {
+            keep.insert(*date);
+            weekly_kept += 1;
+        }
+    }
+
+    keep
+}
+
+/// Delete snapshots that fall outside the retention policy.
+///
+/// # Returns
+/// The dates of the snapshots that were removed.
+fn apply_retention(data_dir: &Path, settings: &SnapshotSettings) -> Result<Vec<NaiveDate>, String> {
+    let dates = snapshot_dates(data_dir)?;
+    let keep = snapshots_to_keep(&dates, settings);
+
+    let mut removed = Vec::new();
+    for date in dates.into_iter().filter(|d| !keep.contains(d)) {
+        fs::remove_dir_all(snapshot_path(data_dir, date))
+            .map_err(|e| format!("Failed to remove snapshot {}: {}", date, e))?;
+        removed.push(date);
+    }
+
+    Ok(removed)
+}
+
+/// Take today's snapshot (if missing or `overwrite` is set) and prune old ones.
+fn run_snapshot(data_dir: &Path, today: NaiveDate, overwrite: bool) -> Result<(), String> {
+    let settings = load_snapshot_settings_from_path(&data_dir.join(SNAPSHOT_SETTINGS_FILE))?;
+    create_snapshot(data_dir, today, overwrite)?;
+    apply_retention(data_dir, &settings)?;
+    Ok(())
+}
+
+/// Background task that keeps a daily snapshot of the data directory.
+///
+/// Spawned from `main()` during setup; checks once an hour so a day boundary
+/// crossed while the app stays open still produces a snapshot.
+pub async fn run_snapshot_scheduler(app: tauri::AppHandle) {
+    loop {
+        let result = crate::resolve_data_dir(&app)
+            .and_then(|data_dir| run_snapshot(&data_dir, Local::now().date_naive(), false));
+
+        if let Err(e) = result {
+            #[cfg(debug_assertions)]
+            eprintln!("Scheduled snapshot failed: {}", e);
+            let _ = e;
+        }
+
+        tokio::time::sleep(SNAPSHOT_CHECK_INTERVAL).await;
+    }
+}
+
+/// Refresh today's snapshot when the app exits so the last session is captured.
+pub fn snapshot_on_exit(app: &tauri::AppHandle) {
+    let result = crate::resolve_data_dir(app)
+        .and_then(|data_dir| run_snapshot(&data_dir, Local::now().date_naive(), true));
+
+    if let Err(e) = result {
+        #[cfg(debug_assertions)]
+        eprintln!("Exit snapshot failed: {}", e);
+        let _ = e;
+    }
+}
+
+/// List all stored snapshots, newest first.
+///
+/// # Arguments
+/// * `data_dir` - Path to the app data directory
+///
+/// # Errors
+/// Returns an error if the snapshot folder cannot be read.
+#[tauri::command]
+pub async fn list_snapshots(data_dir: String) -> Result<Vec<SnapshotInfo>, String> {
+    let data_dir = PathBuf::from(data_dir);
+
+    snapshot_dates(&data_dir)?
+        .into_iter()
+        .map(|date| {
+            let file_count = fs::read_dir(snapshot_path(&data_dir, date))
+                .map_err(|e| format!("Failed to read snapshot {}: {}", date, e))?
+                .filter_map(|entry| entry.ok())
+                .filter(|entry| entry.path().is_file())
+                .count();
+            Ok(SnapshotInfo { date, file_count })
+        })
+        .collect()
+}
+
+/// Restore the data directory from the snapshot taken on `date`.
+///
+/// Every top-level file in the snapshot is put back. Day files are saved like any other
+/// edit, so the current version stays in the day's history and todos missing from the
+/// snapshot go to the trash; other files except the trash are copied over the current
+/// ones. Files created after the snapshot was taken are left untouched, and the
+/// snapshot's history and CRDT documents are only kept for reference.
+///
+/// # Arguments
+/// * `date` - Snapshot date in YYYY-MM-DD format
+/// * `data_dir` - Path to the app data directory
+///
+/// # Returns
+/// The number of files restored.
+///
+/// # Errors
+/// Returns an error if the date is invalid, no snapshot exists for it, or copying fails.
+#[tauri::command]
+pub async fn restore_snapshot(date: String, data_dir: String) -> Result<usize, String> {
+    crate::app_lock::ensure_unlocked()?;
+    let date = NaiveDate::parse_from_str(&date, "%Y-%m-%d")
+        .map_err(|e| format!("Invalid date format: {}", e))?;
+    let data_dir = PathBuf::from(data_dir);
+
+    let source = snapshot_path(&data_dir, date);
+    if !source.is_dir() {
+        return Err(format!("No snapshot found for {}", date));
+    }
+
+    let _guard = crate::DAY_FILES_LOCK.lock().await;
+    let entries = fs::read_dir(&source).map_err(|e| format!("Failed to read snapshot: {}", e))?;
+    let mut days = Vec::new();
+    let mut restored = 0;
+    for entry in entries {
+        let entry = entry.map_err(|e| format!("Failed to read snapshot: {}", e))?;
+        let path = entry.path();
+        let name = entry.file_name().to_string_lossy().to_string();
+        // The current trash holds todos deleted since; restored days add to it below
+        if !path.is_file() || name == crate::trash::TRASH_FILE {
+            continue;
+        }
+
+        let is_day = name
+            .strip_suffix(".json")
+            .is_some_and(|stem| NaiveDate::parse_from_str(stem, "%Y-%m-%d").is_ok());
+        if is_day {
+            days.push((name, path));
+        } else {
+            fs::copy(&path, data_dir.join(&name))
+                .map_err(|e| format!("Failed to restore {}: {}", path.display(), e))?;
+            restored += 1;
+        }
+    }
+
+    for (name, path) in days {
+        let content =
+            fs::read_to_string(&path).map_err(|e| format!("Failed to restore {}: {}", name, e))?;
+        let content = crate::encryption::open(&data_dir, &content)?;
+        let day_data: crate::DayData = serde_json::from_str(&content)
+            .map_err(|e| format!("Failed to parse {}: {}", name, e))?;
+        crate::trash::track_deletions(&data_dir, &day_data)?;
+        crate::write_day_file(&data_dir, &day_data)?;
+        restored += 1;
+    }
+
+    Ok(restored)
+}
+
+/// Load the snapshot retention policy.
+///
+/// # Arguments
+/// * `app` - Tauri app handle for accessing app data directory
+///
+/// # Errors
+/// Returns an error if the settings file cannot be read.
+#[tauri::command]
+pub fn load_snapshot_settings(app: tauri::AppHandle) -> Result<SnapshotSettings, String> {
+    let data_dir = crate::resolve_data_dir(&app)?;
+    load_snapshot_settings_from_path(&data_dir.join(SNAPSHOT_SETTINGS_FILE))
+}
+
+/// Save the snapshot retention policy and prune snapshots it no longer covers.
+///
+/// # Arguments
+/// * `settings` - Number of daily and weekly snapshots to keep
+/// * `app` - Tauri app handle for accessing app data directory
+///
+/// # Errors
+/// Returns an error if the settings cannot be saved or pruning fails.
+#[tauri::command]
+pub fn save_snapshot_settings(
+    settings: SnapshotSettings,
+    app: tauri::AppHandle,
+) -> Result<(), String> {
+    let data_dir = crate::resolve_data_dir(&app)?;
+    save_snapshot_settings_to_path(&settings, &data_dir.join(SNAPSHOT_SETTINGS_FILE))?;
+    apply_retention(&data_dir, &settings)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{read_day_file, write_day_file, DayData, TodoItem};
+    use tempfile::TempDir;
+
+    fn date(s: &str) -> NaiveDate {
+        NaiveDate::parse_from_str(s, "%Y-%m-%d").unwrap()
+    }
+
+    #[test]
+    fn test_create_snapshot_copies_data_directory() {
+        let temp_dir = TempDir::new().unwrap();
+        fs::write(temp_dir.path().join("2024-01-15.json"), "{}").unwrap();
+        fs::write(temp_dir.path().join("dark_mode.json"), "{}").unwrap();
+
+        assert!(create_snapshot(temp_dir.path(), date("2024-01-15"), false).unwrap());
+        // Second call is a no-op without overwrite
+        assert!(!create_snapshot(temp_dir.path(), date("2024-01-15"), false).unwrap());
+
+        let snapshot = snapshot_path(temp_dir.path(), date("2024-01-15"));
+        assert!(snapshot.join("2024-01-15.json").exists());
+        assert!(snapshot.join("dark_mode.json").exists());
+
+        // Subdirectories come along, but not earlier snapshots
+        fs::create_dir_all(temp_dir.path().join("history/2024-01-15")).unwrap();
+        fs::write(temp_dir.path().join("history/2024-01-15/000001.json"), "{}").unwrap();
+        assert!(create_snapshot(temp_dir.path(), date("2024-01-16"), false).unwrap());
+        let snapshot = snapshot_path(temp_dir.path(), date("2024-01-16"));
+        assert!(snapshot.join("history/2024-01-15/000001.json").exists());
+        assert!(!snapshot.join(SNAPSHOTS_DIR).exists());
+    }
+
+    #[test]
+    fn test_retention_keeps_daily_and_weekly() {
+        let settings = SnapshotSettings {
+            keep_daily: 3,
+            keep_weekly: 2,
+        };
+        // 21 consecutive days, newest first: 2024-01-21 (Sun) back to 2024-01-01 (Mon)
+        let dates: Vec<NaiveDate> = (1..=21)
+            .rev()
+            .map(|d| NaiveDate::from_ymd_opt(2024, 1, d).unwrap())
+            .collect();
+
+        let keep = snapshots_to_keep(&dates, &settings);
+
+        assert_eq!(keep.len(), 5);
+        // Daily: the three newest days (all in ISO week 3)
+        assert!(keep.contains(&date("2024-01-21")));
+        assert!(keep.contains(&date("2024-01-20")));
+        assert!(keep.contains(&date("2024-01-19")));
+        // Weekly: newest snapshot of weeks 2 and 1
+        assert!(keep.contains(&date("2024-01-14")));
+        assert!(keep.contains(&date("2024-01-07")));
+    }
+
+    #[test]
+    fn test_apply_retention_removes_old_snapshots() {
+        let temp_dir = TempDir::new().unwrap();
+        fs::write(temp_dir.path().join("2024-01-15.json"), "{}").unwrap();
+        for day in 1..=5 {
+            let d = NaiveDate::from_ymd_opt(2024, 1, day).unwrap();
+            create_snapshot(temp_dir.path(), d, false).unwrap();
+        }
+
+        let settings = SnapshotSettings {
+            keep_daily: 2,
+            keep_weekly: 0,
+        };
+        let removed = apply_retention(temp_dir.path(), &settings).unwrap();
+
+        assert_eq!(removed.len(), 3);
+        assert_eq!(
+            snapshot_dates(temp_dir.path()).unwrap(),
+            vec![date("2024-01-05"), date("2024-01-04")]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_restore_snapshot() {
+        let temp_dir = TempDir::new().unwrap();
+        let dir = temp_dir.path();
+        let data_dir = dir.to_string_lossy().to_string();
+        let prefs_file = dir.join("dark_mode.json");
+
+        let mut day = DayData {
+            date: date("2024-01-15"),
+            todos: vec![TodoItem::new("Email".to_string())],
+            notes: "original".to_string(),
+        };
+        write_day_file(dir, &day).unwrap();
+        fs::write(&prefs_file, "original").unwrap();
+        create_snapshot(dir, date("2024-01-15"), false).unwrap();
+
+        let added = TodoItem::new("Added later".to_string());
+        day.todos.push(added.clone());
+        day.notes = "overwritten".to_string();
+        write_day_file(dir, &day).unwrap();
+        fs::write(&prefs_file, "overwritten").unwrap();
+
+        let restored = restore_snapshot("2024-01-15".to_string(), data_dir.clone())
+            .await
+            .unwrap();
+        assert_eq!(restored, 2);
+        let restored_day = read_day_file(dir, day.date).unwrap();
+        assert_eq!(restored_day.notes, "original");
+        assert_eq!(restored_day.todos.len(), 1);
+        assert_eq!(fs::read_to_string(&prefs_file).unwrap(), "original");
+
+        // The overwritten version can still be recovered
+        let trash = crate::trash::list_trash(data_dir.clone()).await.unwrap();
+        assert_eq!(trash[0].todo.id, added.id);
+        let revisions = crate::history::get_day_history("2024-01-15".to_string(), data_dir.clone())
+            .await
+            .unwrap();
+        assert!(revisions.iter().any(|r| r.day_data.notes == "overwritten"));
+
+        let snapshots = list_snapshots(data_dir.clone()).await.unwrap();
+        assert_eq!(snapshots.len(), 1);
+        assert_eq!(snapshots[0].file_count, 2);
+
+        let missing = restore_snapshot("2024-02-01".to_string(), data_dir).await;
+        assert!(missing.unwrap_err().contains("No snapshot found"));
+    }
+
+    #[test]
+    fn test_snapshot_settings_default_and_roundtrip() {
+        let temp_dir = TempDir::new().unwrap();
+        let file_path = temp_dir.path().join(SNAPSHOT_SETTINGS_FILE);
+
+        assert_eq!(
+            load_snapshot_settings_from_path(&file_path).unwrap(),
+            SnapshotSettings::default()
+        );
+
+        let settings = SnapshotSettings {
+            keep_daily: 14,
+            keep_weekly: 8,
+        };
+        save_snapshot_settings_to_path(&settings, &file_path).unwrap();
+        assert_eq!(
+            load_snapshot_settings_from_path(&file_path).unwrap(),
+            settings
+        );
+    }
+}
@@ -0,0 +1,276 @@
+//! Tags and projects on todos.
+//!
+//! `#tag` and `+project` tokens typed into a todo's text, new or edited, are moved
+//! into its `tags` and `project` fields. Tags are case-insensitive and stored in
+//! lowercase; a todo has at most one project. A token of one to four `!` sets the
+//! priority.
+
+use crate::collections::all_locations;
+use crate::{Priority, TodoItem};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::Path;
+
+/// A tag and the number of todos carrying it
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct TagCount {
+    pub tag: String,
+    pub count: usize,
+}
+
+/// Return the name of a `#tag` or `+project` token with the given sigil.
+///
+/// Names start with a letter and may contain letters, digits, `-`, `_` and `/`, so
+/// "#1" or a lone "+" stay part of the text.
+fn token_name(word: &str, sigil: char) -> Option<&str> {
+    let name = word.strip_prefix(sigil)?;
+    let valid = name.chars().next().is_some_and(char::is_alphabetic)
+        && name
+            .chars()
+            .all(|c| c.is_alphanumeric() || matches!(c, '-' | '_' | '/'));
+    valid.then_some(name)
+}
+
+/// Priority for a token made only of `!`: `!` low up to `!!!!` urgent.
+fn priority_token(word: &str) -> Option<Priority> {
+    if word.is_empty() || !word.chars().all(|c| c == '!') {
+        return None;
+    }
+    Some(match word.len() {
+        1 => Priority::Low,
+        2 => Priority::Medium,
+        3 => Priority::High,
+        _ => Priority::Urgent,
+    })
+}
+
+/// Normalize a tag name, accepting it with or without the leading `#`.
+pub(crate) fn normalize_tag(tag: &str) -> Result<String, String> {
+    let tag = tag.trim();
+    let tag = tag.strip_prefix('#').unwrap_or(tag);
+    token_name(&format!("#{}", tag), '#')
+        .map(str::to_lowercase)
+        .ok_or_else(|| format!("Invalid tag name: {}", tag))
+}
+
+/// Normalize a project name, accepting it with or without the leading `+`. An empty
+/// name means no project.
+pub(crate) fn normalize_project(project: &str) -> Result<Option<String>, String> {
+    let project = project.trim();
+    let project = project.strip_prefix('+').unwrap_or(project);
+    if project.is_empty() {
+        return Ok(None);
+    }
+    token_name(&format!("+{}", project), '+')
+        .map(|name| Some(name.to_string()))
+        .ok_or_else(|| format!("Invalid project name: {}", project))
+}
+
+/// Build a todo from user input, extracting `#tag`, `+project` and `!!` priority
+/// tokens from the text.
+///
+/// If the text consists of nothing but tokens, it is kept as typed.
+pub(crate) fn todo_from_input(text: String) -> TodoItem {
+    let mut tags = Vec::new();
+    let mut project = None;
+    let mut priority = None;
+    let mut words = Vec::new();
+
+    for word in text.split_whitespace() {
+        if let Some(tag) = token_name(word, '#') {
+            let tag = tag.to_lowercase();
+            if !tags.contains(&tag) {
+                tags.push(tag);
+            }
+        } else if let Some(name) = token_name(word, '+') {
+            project = Some(name.to_string());
+        } else if let Some(level) = priority_token(word) {
+            priority = Some(level);
+        } else {
+            words.push(word);
+        }
+    }
+
+    let text = if (tags.is_empty() && project.is_none() && priority.is_none()) || words.is_empty() {
+        text
+    } else {
+        words.join(" ")
+    };
+
+    tags.sort();
+    TodoItem {
+        tags,
+        project,
+        priority: priority.unwrap_or_default(),
+        ..TodoItem::new(text)
+    }
+}
+
+/// List every tag in use in days and collections with the number of todos carrying it,
+/// most used first.
+///
+/// # Arguments
+/// * `data_dir` - Path to the app data directory
+///
+/// # Errors
+/// Returns an error if the data directory, a day file or a collection cannot be read.
+#[tauri::command]
+pub async fn list_tags(data_dir: String) -> Result<Vec<TagCount>, String> {
+    crate::app_lock::ensure_unlocked()?;
+    let data_dir = Path::new(&data_dir);
+
+    let mut counts: BTreeMap<String, usize> = BTreeMap::new();
+    for location in all_locations(data_dir)? {
+        for todo in location.open(data_dir)?.todos() {
+            for tag in &todo.tags {
+                *counts.entry(tag.clone()).or_default() += 1;
+            }
+        }
+    }
+
+    let mut tags: Vec<TagCount> = counts
+        .into_iter()
+        .map(|(tag, count)| TagCount { tag, count })
+        .collect();
+    tags.sort_by_key(|entry| std::cmp::Reverse(entry.count));
+    Ok(tags)
+}
+
+/// Rename a tag on every todo in every day file and collection.
+///
+/// If a todo already has the new tag, the two are merged.
+///
+/// # Arguments
+/// * `old_tag` - The tag to rename, with or without `#`
+/// * `new_tag` - The new tag name, with or without `#`
+/// * `data_dir` - Path to the app data directory
+///
+/// # Returns
+/// The number of todos that were changed.
+///
+/// # Errors
+/// Returns an error if a tag name is invalid or file operations fail.
+#[tauri::command]
+pub async fn rename_tag(
+    old_tag: String,
+    new_tag: String,
+    data_dir: String,
+) -> Result<usize, String> {
+    crate::app_lock::ensure_unlocked()?;
+    let old_tag = normalize_tag(&old_tag)?;
+    let new_tag = normalize_tag(&new_tag)?;
+    let data_dir = Path::new(&data_dir);
+    if old_tag == new_tag {
+        return Ok(0);
+    }
+
+    let _guard = crate::DAY_FILES_LOCK.lock().await;
+
+    let mut renamed = 0;
+    for location in all_locations(data_dir)? {
+        let mut list = location.open(data_dir)?;
+        let mut changed = false;
+
+        for todo in list.todos_mut() {
+            if !todo.tags.contains(&old_tag) {
+                continue;
+            }
+            todo.tags.retain(|tag| *tag != old_tag && *tag != new_tag);
+            todo.tags.push(new_tag.clone());
+            todo.tags.sort();
+            renamed += 1;
+            changed = true;
+        }
+
+        if changed {
+            list.save(data_dir)?;
+        }
+    }
+
+    Ok(renamed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::collections::Location;
+    use crate::{write_day_file, DayData};
+    use chrono::NaiveDate;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_todo_from_input_extracts_tokens() {
+        let todo = todo_from_input("Fix login #Bug +website #urgent #bug".to_string());
+        assert_eq!(todo.text, "Fix login");
+        assert_eq!(todo.tags, vec!["bug", "urgent"]);
+        assert_eq!(todo.project.as_deref(), Some("website"));
+
+        // Numbers, sums and plain text are left alone
+        let todo = todo_from_input("Pay invoice #42 for 1 + 1".to_string());
+        assert_eq!(todo.text, "Pay invoice #42 for 1 + 1");
+        assert!(todo.tags.is_empty());
+        assert!(todo.project.is_none());
+
+        let todo = todo_from_input("Renew passport !!! #admin".to_string());
+        assert_eq!(todo.text, "Renew passport");
+        assert_eq!(todo.priority, Priority::High);
+        assert_eq!(
+            todo_from_input("Call now!!".to_string()).priority,
+            Priority::None
+        );
+
+        // A todo that is only a tag keeps its text
+        let todo = todo_from_input("#someday".to_string());
+        assert_eq!(todo.text, "#someday");
+        assert_eq!(todo.tags, vec!["someday"]);
+    }
+
+    #[tokio::test]
+    async fn test_list_and_rename_tags() {
+        let temp_dir = TempDir::new().unwrap();
+        let data_dir = temp_dir.path().to_string_lossy().to_string();
+
+        for (day, texts) in [
+            (15, vec!["Email Sam #work", "Gym #health"]),
+            (16, vec!["Review PR #work #code", "Write docs #job"]),
+        ] {
+            let day_data = DayData {
+                date: NaiveDate::from_ymd_opt(2024, 1, day).unwrap(),
+                todos: texts
+                    .into_iter()
+                    .map(|t| todo_from_input(t.to_string()))
+                    .collect(),
+                notes: String::new(),
+            };
+            write_day_file(temp_dir.path(), &day_data).unwrap();
+        }
+        let mut future_log = Location::FutureLog.open(temp_dir.path()).unwrap();
+        future_log
+            .todos_mut()
+            .push(todo_from_input("Write a novel #job #work".to_string()));
+        future_log.save(temp_dir.path()).unwrap();
+
+        let tags = list_tags(data_dir.clone()).await.unwrap();
+        assert_eq!(
+            tags[0],
+            TagCount {
+                tag: "work".to_string(),
+                count: 3
+            }
+        );
+        assert_eq!(tags.len(), 4);
+
+        // Renaming onto an existing tag merges them
+        let renamed = rename_tag("#job".to_string(), "Work".to_string(), data_dir.clone())
+            .await
+            .unwrap();
+        assert_eq!(renamed, 2);
+
+        let tags = list_tags(data_dir.clone()).await.unwrap();
+        assert_eq!(tags[0].count, 4);
+        assert!(!tags.iter().any(|t| t.tag == "job"));
+
+        let invalid = rename_tag("work".to_string(), "#".to_string(), data_dir).await;
+        assert!(invalid.is_err());
+    }
+}
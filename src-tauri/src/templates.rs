@@ -0,0 +1,336 @@
+//! Day templates.
+//!
+//! A template is a list of todos and a notes skeleton stored in `templates.json`.
+//! Templates assigned to weekdays are applied automatically when a new day (today or
+//! later, without a day file yet) is opened; any template can be applied on demand
+//! with `apply_template`.
+
+use crate::tags::todo_from_input;
+use crate::{read_day_file, write_day_file, DayData};
+use chrono::{Datelike, NaiveDate, Weekday};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// File in the data directory holding the day templates
+const TEMPLATES_FILE: &str = "templates.json";
+
+/// A reusable starting point for a day
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct DayTemplate {
+    /// Unique name used to apply the template
+    pub name: String,
+    /// Texts of the todos the template adds
+    #[serde(default)]
+    pub todos: Vec<String>,
+    /// Notes skeleton, used when the day has no notes yet
+    #[serde(default)]
+    pub notes: String,
+    /// Weekdays this template is applied to automatically
+    #[serde(default)]
+    pub weekdays: Vec<Weekday>,
+}
+
+impl DayTemplate {
+    /// Add the template's todos to the end of a day and fill in empty notes.
+    ///
+    /// Todo texts are parsed like typed input, so `#tag`, `+project` and `!` tokens work.
+    fn apply_to(&self, day_data: &mut DayData) {
+        day_data
+            .todos
+            .extend(self.todos.iter().map(|text| todo_from_input(text.clone())));
+
+        if day_data.notes.trim().is_empty() {
+            day_data.notes = self.notes.clone();
+        }
+    }
+}
+
+fn templates_path(data_dir: &Path) -> PathBuf {
+    data_dir.join(TEMPLATES_FILE)
+}
+
+fn load_templates(data_dir: &Path) -> Result<Vec<DayTemplate>, String> {
+    let file_path = templates_path(data_dir);
+    if !file_path.exists() {
+        return Ok(Vec::new());
+    }
+
+    let content =
+        fs::read_to_string(&file_path).map_err(|e| format!("Failed to read templates: {}", e))?;
+    let content = crate::encryption::open(data_dir, &content)?;
+    serde_json::from_str(&content).map_err(|e| format!("Failed to parse templates: {}", e))
+}
+
+fn save_templates(data_dir: &Path, templates: &[DayTemplate]) -> Result<(), String> {
+    let json_content = serde_json::to_string_pretty(templates)
+        .map_err(|e| format!("Failed to serialize templates: {}", e))?;
+    let json_content = crate::encryption::seal(data_dir, &json_content)?;
+
+    fs::write(templates_path(data_dir), json_content)
+        .map_err(|e| format!("Failed to write templates: {}", e))
+}
+
+/// Apply the weekday template for a day that has no file yet.
+///
+/// Days before `today` are left blank, so browsing old empty days doesn't fill them in.
+///
+/// # Returns
+/// Whether a template was applied.
+pub(crate) fn apply_weekday_template(
+    data_dir: &Path,
+    day_data: &mut DayData,
+    today: NaiveDate,
+) -> Result<bool, String> {
+    if day_data.date < today {
+        return Ok(false);
+    }
+
+    let weekday = day_data.date.weekday();
+    let template = load_templates(data_dir)?
+        .into_iter()
+        .find(|t| t.weekdays.contains(&weekday));
+
+    match template {
+        Some(template) => {
+            template.apply_to(day_data);
+            Ok(true)
+        }
+        None => Ok(false),
+    }
+}
+
+/// List all day templates.
+///
+/// # Arguments
+/// * `data_dir` - Path to the app data directory
+///
+/// # Errors
+/// Returns an error if the templates file cannot be read.
+#[tauri::command]
+pub async fn list_templates(data_dir: String) -> Result<Vec<DayTemplate>, String> {
+    crate::app_lock::ensure_unlocked()?;
+    let _guard = crate::DAY_FILES_LOCK.lock().await;
+    load_templates(Path::new(&data_dir))
+}
+
+/// Create a template, or replace the template with the same name.
+///
+/// # Arguments
+/// * `template` - The template to store
+/// * `data_dir` - Path to the app data directory
+///
+/// # Errors
+/// Returns an error if the name is empty, one of its weekdays already belongs to
+/// another template, or saving fails.
+#[tauri::command]
+pub async fn save_template(mut template: DayTemplate, data_dir: String) -> Result<(), String> {
+    crate::app_lock::ensure_unlocked()?;
+    template.name = template.name.trim().to_string();
+    if template.name.is_empty() {
+        return Err("Template name cannot be empty".to_string());
+    }
+    template.todos.retain(|text| !text.trim().is_empty());
+
+    let data_dir = PathBuf::from(data_dir);
+    let _guard = crate::DAY_FILES_LOCK.lock().await;
+    let mut templates = load_templates(&data_dir)?;
+
+    for other in templates.iter().filter(|t| t.name != template.name) {
+        if let Some(day) = other
+            .weekdays
+            .iter()
+            .find(|d| template.weekdays.contains(d))
+        {
+            return Err(format!(
+                "{} is already assigned to template \"{}\"",
+                day, other.name
+            ));
+        }
+    }
+
+    match templates.iter_mut().find(|t| t.name == template.name) {
+        Some(existing) => *existing = template,
+        None => templates.push(template),
+    }
+
+    save_templates(&data_dir, &templates)
+}
+
+/// Delete a template. Days it was applied to are unchanged.
+///
+/// # Arguments
+/// * `name` - Name of the template
+/// * `data_dir` - Path to the app data directory
+///
+/// # Errors
+/// Returns an error if the template doesn't exist or saving fails.
+#[tauri::command]
+pub async fn delete_template(name: String, data_dir: String) -> Result<(), String> {
+    crate::app_lock::ensure_unlocked()?;
+    let data_dir = PathBuf::from(data_dir);
+    let _guard = crate::DAY_FILES_LOCK.lock().await;
+    let mut templates = load_templates(&data_dir)?;
+
+    let before = templates.len();
+    templates.retain(|t| t.name != name);
+    if templates.len() == before {
+        return Err(format!("Template \"{}\" not found", name));
+    }
+
+    save_templates(&data_dir, &templates)
+}
+
+/// Apply a template to a day, adding its todos after the existing ones.
+///
+/// # Arguments
+/// * `date` - Date string in YYYY-MM-DD format
+/// * `name` - Name of the template
+/// * `data_dir` - Path to the app data directory
+///
+/// # Returns
+/// The updated DayData.
+///
+/// # Errors
+/// Returns an error if the date is invalid, the template doesn't exist, or saving fails.
+#[tauri::command]
+pub async fn apply_template(
+    date: String,
+    name: String,
+    data_dir: String,
+) -> Result<DayData, String> {
+    crate::app_lock::ensure_unlocked()?;
+    let date = NaiveDate::parse_from_str(&date, "%Y-%m-%d")
+        .map_err(|e| format!("Invalid date format: {}", e))?;
+    let data_dir = PathBuf::from(data_dir);
+
+    let _guard = crate::DAY_FILES_LOCK.lock().await;
+    let template = load_templates(&data_dir)?
+        .into_iter()
+        .find(|t| t.name == name)
+        .ok_or_else(|| format!("Template \"{}\" not found", name))?;
+
+    let mut day_data = read_day_file(&data_dir, date)?;
+    template.apply_to(&mut day_data);
+    write_day_file(&data_dir, &day_data)?;
+
+    Ok(day_data)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{load_day_data, open_day_at, TodoItem};
+    use tempfile::TempDir;
+
+    fn template(name: &str, weekdays: Vec<Weekday>) -> DayTemplate {
+        DayTemplate {
+            name: name.to_string(),
+            todos: vec!["Plan the day".to_string(), "Inbox zero".to_string()],
+            notes: "## Wins\n\n## Blockers\n".to_string(),
+            weekdays,
+        }
+    }
+
+    fn date(s: &str) -> NaiveDate {
+        NaiveDate::parse_from_str(s, "%Y-%m-%d").unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_weekday_template_applied_to_new_day() {
+        let temp_dir = TempDir::new().unwrap();
+        let dir = temp_dir.path();
+        let data_dir = dir.to_string_lossy().to_string();
+        let mut monday_template = template("Monday", vec![Weekday::Mon]);
+        monday_template
+            .todos
+            .push("Triage #bugs +website".to_string());
+        save_template(monday_template, data_dir.clone())
+            .await
+            .unwrap();
+
+        // 2024-01-15 is a Monday
+        let today = date("2024-01-10");
+        let opened = open_day_at(dir, date("2024-01-15"), today).unwrap();
+        assert_eq!(opened.todos.len(), 3);
+        assert_eq!(opened.notes, "## Wins\n\n## Blockers\n");
+        assert_eq!(opened.todos[2].text, "Triage");
+        assert_eq!(opened.todos[2].tags, vec!["bugs".to_string()]);
+        assert_eq!(opened.todos[2].project, Some("website".to_string()));
+
+        // Only applied once: the day has a file now
+        let reopened = open_day_at(dir, date("2024-01-15"), today).unwrap();
+        assert_eq!(reopened.todos.len(), 3);
+
+        // A plain load of a new day doesn't apply it
+        let next_monday = load_day_data("2024-01-22".to_string(), data_dir, None)
+            .await
+            .unwrap();
+        assert!(next_monday.todos.is_empty());
+        assert!(!crate::day_file_path(dir, date("2024-01-22")).exists());
+
+        // Other weekdays and past days stay blank
+        assert!(open_day_at(dir, date("2024-01-16"), today)
+            .unwrap()
+            .todos
+            .is_empty());
+        assert!(open_day_at(dir, date("2024-01-08"), today)
+            .unwrap()
+            .todos
+            .is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_apply_template_on_demand() {
+        let temp_dir = TempDir::new().unwrap();
+        let data_dir = temp_dir.path().to_string_lossy().to_string();
+        save_template(template("Review", Vec::new()), data_dir.clone())
+            .await
+            .unwrap();
+
+        let day_data = DayData {
+            date: NaiveDate::from_ymd_opt(2024, 1, 15).unwrap(),
+            todos: vec![TodoItem::new("Existing".to_string())],
+            notes: "Already written".to_string(),
+        };
+        write_day_file(temp_dir.path(), &day_data).unwrap();
+
+        let applied = apply_template(
+            "2024-01-15".to_string(),
+            "Review".to_string(),
+            data_dir.clone(),
+        )
+        .await
+        .unwrap();
+        let texts: Vec<&str> = applied.todos.iter().map(|t| t.text.as_str()).collect();
+        assert_eq!(texts, vec!["Existing", "Plan the day", "Inbox zero"]);
+        assert_eq!(applied.notes, "Already written");
+
+        let missing = apply_template("2024-01-15".to_string(), "Nope".to_string(), data_dir).await;
+        assert!(missing.unwrap_err().contains("not found"));
+    }
+
+    #[tokio::test]
+    async fn test_save_template_rejects_shared_weekday() {
+        let temp_dir = TempDir::new().unwrap();
+        let data_dir = temp_dir.path().to_string_lossy().to_string();
+        save_template(
+            template("Workday", vec![Weekday::Mon, Weekday::Tue]),
+            data_dir.clone(),
+        )
+        .await
+        .unwrap();
+
+        let clash = save_template(template("Standup", vec![Weekday::Tue]), data_dir.clone()).await;
+        assert!(clash.unwrap_err().contains("already assigned"));
+
+        // Replacing the same template is fine
+        save_template(template("Workday", vec![Weekday::Tue]), data_dir.clone())
+            .await
+            .unwrap();
+        delete_template("Workday".to_string(), data_dir.clone())
+            .await
+            .unwrap();
+        assert!(list_templates(data_dir).await.unwrap().is_empty());
+    }
+}
@@ -0,0 +1,732 @@
+//! Backend-owned todo mutations keyed by todo ID.
+//!
+//! Each command loads the day, applies one validated change and writes the day back
+//! while holding [`crate::DAY_FILES_LOCK`], so clients other than the main window
+//! (CLI, tray, API) can change data without sending whole `DayData` documents.
+
+use crate::collections::Location;
+use crate::{
+    read_day_file, trash, write_day_file, DayData, Deadline, Priority, Subtask, TodoItem,
+    TodoStatus, DAY_FILES_LOCK,
+};
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
+
+/// Partial update for a todo item; fields left out are unchanged
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[serde(default)]
+pub struct TodoPatch {
+    pub text: Option<String>,
+    pub notes: Option<String>,
+    pub status: Option<TodoStatus>,
+    pub move_to_next_day: Option<bool>,
+    pub auto_complete: Option<bool>,
+    pub priority: Option<Priority>,
+    /// Replaces all tags, with or without `#`
+    pub tags: Option<Vec<String>>,
+    /// With or without `+`; empty to remove the project
+    pub project: Option<String>,
+}
+
+fn parse_date(date: &str) -> Result<NaiveDate, String> {
+    NaiveDate::parse_from_str(date, "%Y-%m-%d").map_err(|e| format!("Invalid date format: {}", e))
+}
+
+/// Trim todo text and reject empty text.
+fn validate_text(text: &str) -> Result<String, String> {
+    let trimmed = text.trim();
+    if trimmed.is_empty() {
+        return Err("Todo text cannot be empty".to_string());
+    }
+    Ok(trimmed.to_string())
+}
+
+fn find_subtask_mut<'a>(
+    todo: &'a mut TodoItem,
+    subtask_id: &str,
+) -> Result<&'a mut Subtask, String> {
+    todo.subtasks
+        .iter_mut()
+        .find(|s| s.id == subtask_id)
+        .ok_or_else(|| format!("Subtask with ID {} not found", subtask_id))
+}
+
+fn find_todo_mut<'a>(day_data: &'a mut DayData, todo_id: &str) -> Result<&'a mut TodoItem, String> {
+    let date = day_data.date;
+    day_data
+        .todos
+        .iter_mut()
+        .find(|t| t.id == todo_id)
+        .ok_or_else(|| format!("Todo with ID {} not found on {}", todo_id, date))
+}
+
+/// Load a day, apply `mutate` and write the result back under the day files lock.
+///
+/// Nothing is written if `mutate` returns an error.
+async fn mutate_day<T>(
+    data_dir: &Path,
+    date: NaiveDate,
+    mutate: impl FnOnce(&mut DayData) -> Result<T, String>,
+) -> Result<T, String> {
+    let _guard = DAY_FILES_LOCK.lock().await;
+
+    let mut day_data = read_day_file(data_dir, date)?;
+    let result = mutate(&mut day_data)?;
+    write_day_file(data_dir, &day_data)?;
+
+    Ok(result)
+}
+
+/// Create a todo and append it to a day.
+///
+/// # Arguments
+/// * `date` - Date string in YYYY-MM-DD format
+/// * `text` - The todo item text; surrounding whitespace is trimmed and `#tag` /
+///   `+project` tokens are extracted
+/// * `data_dir` - Path to the app data directory
+///
+/// # Returns
+/// The newly created TodoItem.
+///
+/// # Errors
+/// Returns an error if the date is invalid, the text is empty, or saving fails.
+#[tauri::command]
+pub async fn add_todo(date: String, text: String, data_dir: String) -> Result<TodoItem, String> {
+    crate::app_lock::ensure_unlocked()?;
+    let date = parse_date(&date)?;
+    let text = validate_text(&text)?;
+
+    mutate_day(Path::new(&data_dir), date, |day_data| {
+        let todo = crate::tags::todo_from_input(text);
+        day_data.todos.push(todo.clone());
+        Ok(todo)
+    })
+    .await
+}
+
+/// Apply a partial update to a todo.
+///
+/// # Arguments
+/// * `date` - Date string in YYYY-MM-DD format
+/// * `todo_id` - The unique ID of the todo item
+/// * `patch` - Fields to change; omitted fields keep their current value. `#tag`,
+///   `+project` and `!` tokens in new text are applied like in a new todo, before the
+///   patch's own tags, project and priority.
+/// * `data_dir` - Path to the app data directory
+///
+/// # Returns
+/// The updated TodoItem.
+///
+/// # Errors
+/// Returns an error if the date is invalid, the todo doesn't exist, the new text is
+/// empty, a tag or project name is invalid, the new status is migrated or scheduled
+/// (use a move command instead, which records where the todo went), or saving fails.
+#[tauri::command]
+pub async fn update_todo(
+    date: String,
+    todo_id: String,
+    patch: TodoPatch,
+    data_dir: String,
+) -> Result<TodoItem, String> {
+    crate::app_lock::ensure_unlocked()?;
+    let date = parse_date(&date)?;
+    let text = patch.text.as_deref().map(validate_text).transpose()?;
+    let tags = patch
+        .tags
+        .map(|tags| {
+            let mut tags = tags
+                .iter()
+                .map(|tag| crate::tags::normalize_tag(tag))
+                .collect::<Result<Vec<_>, _>>()?;
+            tags.sort();
+            tags.dedup();
+            Ok::<_, String>(tags)
+        })
+        .transpose()?;
+    let project = patch
+        .project
+        .as_deref()
+        .map(crate::tags::normalize_project)
+        .transpose()?;
+    if matches!(
+        patch.status,
+        Some(TodoStatus::Migrated | TodoStatus::Scheduled)
+    ) {
+        return Err("Migrated and scheduled can only be set by moving the todo".to_string());
+    }
+
+    mutate_day(Path::new(&data_dir), date, |day_data| {
+        let todo = find_todo_mut(day_data, &todo_id)?;
+
+        if let Some(text) = text {
+            let parsed = crate::tags::todo_from_input(text);
+            todo.text = parsed.text;
+            for tag in parsed.tags {
+                if !todo.tags.contains(&tag) {
+                    todo.tags.push(tag);
+                }
+            }
+            todo.tags.sort();
+            if parsed.project.is_some() {
+                todo.project = parsed.project;
+            }
+            if !parsed.priority.is_none() {
+                todo.priority = parsed.priority;
+            }
+        }
+        if let Some(tags) = tags {
+            todo.tags = tags;
+        }
+        if let Some(project) = project {
+            todo.project = project;
+        }
+        if let Some(notes) = patch.notes {
+            todo.notes = notes;
+        }
+        if let Some(status) = patch.status {
+            todo.status = status;
+            todo.sync_completed_at();
+        }
+        if let Some(move_to_next_day) = patch.move_to_next_day {
+            todo.move_to_next_day = move_to_next_day;
+        }
+        if let Some(priority) = patch.priority {
+            todo.priority = priority;
+        }
+        if let Some(auto_complete) = patch.auto_complete {
+            todo.auto_complete = auto_complete;
+            todo.apply_subtask_rules();
+        }
+
+        Ok(todo.clone())
+    })
+    .await
+}
+
+/// Set or clear a todo's deadline.
+///
+/// # Arguments
+/// * `date` - Date string in YYYY-MM-DD format of the day the todo is on
+/// * `todo_id` - The unique ID of the todo item
+/// * `deadline` - The new deadline, or `None` to remove it
+/// * `data_dir` - Path to the app data directory
+///
+/// # Returns
+/// The updated TodoItem.
+///
+/// # Errors
+/// Returns an error if the date is invalid, the todo doesn't exist, or saving fails.
+#[tauri::command]
+pub async fn set_deadline(
+    date: String,
+    todo_id: String,
+    deadline: Option<Deadline>,
+    data_dir: String,
+) -> Result<TodoItem, String> {
+    crate::app_lock::ensure_unlocked()?;
+    let date = parse_date(&date)?;
+
+    mutate_day(Path::new(&data_dir), date, |day_data| {
+        let todo = find_todo_mut(day_data, &todo_id)?;
+        todo.deadline = deadline;
+        Ok(todo.clone())
+    })
+    .await
+}
+
+/// Flip a todo between open and done.
+///
+/// Migrated and scheduled entries only point to where the todo went, so they can't
+/// be toggled.
+///
+/// # Arguments
+/// * `date` - Date string in YYYY-MM-DD format
+/// * `todo_id` - The unique ID of the todo item
+/// * `data_dir` - Path to the app data directory
+///
+/// # Returns
+/// The updated TodoItem.
+///
+/// # Errors
+/// Returns an error if the date is invalid, the todo doesn't exist or has been moved,
+/// or saving fails.
+#[tauri::command]
+pub async fn toggle_todo(
+    date: String,
+    todo_id: String,
+    data_dir: String,
+) -> Result<TodoItem, String> {
+    crate::app_lock::ensure_unlocked()?;
+    let date = parse_date(&date)?;
+
+    mutate_day(Path::new(&data_dir), date, |day_data| {
+        let todo = find_todo_mut(day_data, &todo_id)?;
+        todo.status = match todo.status {
+            TodoStatus::Done => TodoStatus::Open,
+            TodoStatus::Open | TodoStatus::Cancelled => TodoStatus::Done,
+            TodoStatus::Migrated | TodoStatus::Scheduled => {
+                return Err(format!("Todo with ID {} has been moved elsewhere", todo_id));
+            }
+        };
+        todo.sync_completed_at();
+        Ok(todo.clone())
+    })
+    .await
+}
+
+/// Remove a todo from a day and put it in the trash.
+///
+/// # Arguments
+/// * `date` - Date string in YYYY-MM-DD format
+/// * `todo_id` - The unique ID of the todo item
+/// * `data_dir` - Path to the app data directory
+///
+/// # Errors
+/// Returns an error if the date is invalid, the todo doesn't exist, or saving fails.
+#[tauri::command]
+pub async fn delete_todo(date: String, todo_id: String, data_dir: String) -> Result<(), String> {
+    crate::app_lock::ensure_unlocked()?;
+    let date = parse_date(&date)?;
+    let data_dir = Path::new(&data_dir);
+
+    mutate_day(data_dir, date, |day_data| {
+        let index = day_data
+            .todos
+            .iter()
+            .position(|t| t.id == todo_id)
+            .ok_or_else(|| format!("Todo with ID {} not found on {}", todo_id, date))?;
+        let removed = day_data.todos.remove(index);
+        // Trashed before the day is written, so a failure can't lose the todo
+        trash::move_to_trash(data_dir, &Location::Day(date), vec![removed])
+    })
+    .await
+}
+
+/// Reorder the todos of a day.
+///
+/// # Arguments
+/// * `date` - Date string in YYYY-MM-DD format
+/// * `ids` - Every todo ID of the day, in the desired order
+/// * `data_dir` - Path to the app data directory
+///
+/// # Returns
+/// The reordered DayData.
+///
+/// # Errors
+/// Returns an error if the date is invalid, `ids` is not exactly the day's todo IDs
+/// (each once), or saving fails.
+#[tauri::command]
+pub async fn reorder_todos(
+    date: String,
+    ids: Vec<String>,
+    data_dir: String,
+) -> Result<DayData, String> {
+    crate::app_lock::ensure_unlocked()?;
+    let date = parse_date(&date)?;
+
+    mutate_day(Path::new(&data_dir), date, |day_data| {
+        let current: HashSet<&str> = day_data.todos.iter().map(|t| t.id.as_str()).collect();
+        let requested: HashSet<&str> = ids.iter().map(|id| id.as_str()).collect();
+        if requested.len() != ids.len() || requested != current {
+            return Err(format!(
+                "Reorder must list each of the {} todos on {} exactly once",
+                day_data.todos.len(),
+                date
+            ));
+        }
+
+        let mut by_id: HashMap<String, TodoItem> = std::mem::take(&mut day_data.todos)
+            .into_iter()
+            .map(|t| (t.id.clone(), t))
+            .collect();
+        day_data.todos = ids.iter().filter_map(|id| by_id.remove(id)).collect();
+
+        Ok(day_data.clone())
+    })
+    .await
+}
+
+/// Add a checklist step to the end of a todo's subtasks.
+///
+/// # Arguments
+/// * `date` - Date string in YYYY-MM-DD format
+/// * `todo_id` - The unique ID of the parent todo
+/// * `text` - The subtask text; surrounding whitespace is trimmed
+/// * `data_dir` - Path to the app data directory
+///
+/// # Returns
+/// The updated parent TodoItem.
+///
+/// # Errors
+/// Returns an error if the date is invalid, the todo doesn't exist, the text is empty,
+/// or saving fails.
+#[tauri::command]
+pub async fn add_subtask(
+    date: String,
+    todo_id: String,
+    text: String,
+    data_dir: String,
+) -> Result<TodoItem, String> {
+    crate::app_lock::ensure_unlocked()?;
+    let date = parse_date(&date)?;
+    let text = validate_text(&text)?;
+
+    mutate_day(Path::new(&data_dir), date, |day_data| {
+        let todo = find_todo_mut(day_data, &todo_id)?;
+        todo.subtasks.push(Subtask::new(text));
+        todo.apply_subtask_rules();
+        Ok(todo.clone())
+    })
+    .await
+}
+
+/// Change the text of a subtask.
+///
+/// # Arguments
+/// * `date` - Date string in YYYY-MM-DD format
+/// * `todo_id` - The unique ID of the parent todo
+/// * `subtask_id` - The unique ID of the subtask
+/// * `text` - The new text; surrounding whitespace is trimmed
+/// * `data_dir` - Path to the app data directory
+///
+/// # Returns
+/// The updated parent TodoItem.
+///
+/// # Errors
+/// Returns an error if the date is invalid, the todo or subtask doesn't exist, the
+/// text is empty, or saving fails.
+#[tauri::command]
+pub async fn update_subtask(
+    date: String,
+    todo_id: String,
+    subtask_id: String,
+    text: String,
+    data_dir: String,
+) -> Result<TodoItem, String> {
+    crate::app_lock::ensure_unlocked()?;
+    let date = parse_date(&date)?;
+    let text = validate_text(&text)?;
+
+    mutate_day(Path::new(&data_dir), date, |day_data| {
+        let todo = find_todo_mut(day_data, &todo_id)?;
+        find_subtask_mut(todo, &subtask_id)?.text = text;
+        Ok(todo.clone())
+    })
+    .await
+}
+
+/// Check or uncheck a subtask.
+///
+/// If the parent has `auto_complete` enabled, it becomes done once every subtask is
+/// completed and open again when one is unchecked.
+///
+/// # Arguments
+/// * `date` - Date string in YYYY-MM-DD format
+/// * `todo_id` - The unique ID of the parent todo
+/// * `subtask_id` - The unique ID of the subtask
+/// * `data_dir` - Path to the app data directory
+///
+/// # Returns
+/// The updated parent TodoItem.
+///
+/// # Errors
+/// Returns an error if the date is invalid, the todo or subtask doesn't exist, or
+/// saving fails.
+#[tauri::command]
+pub async fn toggle_subtask(
+    date: String,
+    todo_id: String,
+    subtask_id: String,
+    data_dir: String,
+) -> Result<TodoItem, String> {
+    crate::app_lock::ensure_unlocked()?;
+    let date = parse_date(&date)?;
+
+    mutate_day(Path::new(&data_dir), date, |day_data| {
+        let todo = find_todo_mut(day_data, &todo_id)?;
+        let subtask = find_subtask_mut(todo, &subtask_id)?;
+        subtask.completed = !subtask.completed;
+        todo.apply_subtask_rules();
+        Ok(todo.clone())
+    })
+    .await
+}
+
+/// Remove a subtask from a todo.
+///
+/// # Arguments
+/// * `date` - Date string in YYYY-MM-DD format
+/// * `todo_id` - The unique ID of the parent todo
+/// * `subtask_id` - The unique ID of the subtask
+/// * `data_dir` - Path to the app data directory
+///
+/// # Returns
+/// The updated parent TodoItem.
+///
+/// # Errors
+/// Returns an error if the date is invalid, the todo or subtask doesn't exist, or
+/// saving fails.
+#[tauri::command]
+pub async fn delete_subtask(
+    date: String,
+    todo_id: String,
+    subtask_id: String,
+    data_dir: String,
+) -> Result<TodoItem, String> {
+    crate::app_lock::ensure_unlocked()?;
+    let date = parse_date(&date)?;
+
+    mutate_day(Path::new(&data_dir), date, |day_data| {
+        let todo = find_todo_mut(day_data, &todo_id)?;
+        let before = todo.subtasks.len();
+        todo.subtasks.retain(|s| s.id != subtask_id);
+        if todo.subtasks.len() == before {
+            return Err(format!("Subtask with ID {} not found", subtask_id));
+        }
+        todo.apply_subtask_rules();
+        Ok(todo.clone())
+    })
+    .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::load_day_data;
+    use tempfile::TempDir;
+
+    const DATE: &str = "2024-01-15";
+
+    #[tokio::test]
+    async fn test_add_todo_persists() {
+        let temp_dir = TempDir::new().unwrap();
+        let data_dir = temp_dir.path().to_string_lossy().to_string();
+
+        let todo = add_todo(
+            DATE.to_string(),
+            "  Write report ".to_string(),
+            data_dir.clone(),
+        )
+        .await
+        .unwrap();
+        assert_eq!(todo.text, "Write report");
+
+        let loaded = load_day_data(DATE.to_string(), data_dir.clone(), None)
+            .await
+            .unwrap();
+        assert_eq!(loaded.todos.len(), 1);
+        assert_eq!(loaded.todos[0].id, todo.id);
+
+        let empty = add_todo(DATE.to_string(), "   ".to_string(), data_dir).await;
+        assert!(empty.unwrap_err().contains("cannot be empty"));
+    }
+
+    #[tokio::test]
+    async fn test_update_and_toggle_todo() {
+        let temp_dir = TempDir::new().unwrap();
+        let data_dir = temp_dir.path().to_string_lossy().to_string();
+        let todo = add_todo(DATE.to_string(), "Draft".to_string(), data_dir.clone())
+            .await
+            .unwrap();
+
+        let patch = TodoPatch {
+            text: Some("Final".to_string()),
+            notes: Some("See ticket".to_string()),
+            ..Default::default()
+        };
+        let updated = update_todo(DATE.to_string(), todo.id.clone(), patch, data_dir.clone())
+            .await
+            .unwrap();
+        assert_eq!(updated.text, "Final");
+        assert_eq!(updated.notes, "See ticket");
+        assert_eq!(updated.status, TodoStatus::Open);
+
+        let toggled = toggle_todo(DATE.to_string(), todo.id.clone(), data_dir.clone())
+            .await
+            .unwrap();
+        assert_eq!(toggled.status, TodoStatus::Done);
+
+        let loaded = load_day_data(DATE.to_string(), data_dir.clone(), None)
+            .await
+            .unwrap();
+        assert_eq!(loaded.todos[0].text, "Final");
+        assert_eq!(loaded.todos[0].status, TodoStatus::Done);
+
+        // Tokens in new text are parsed; explicit tags and project replace them
+        let patch = TodoPatch {
+            text: Some("Final draft #Writing +book !!".to_string()),
+            ..Default::default()
+        };
+        let updated = update_todo(DATE.to_string(), todo.id.clone(), patch, data_dir.clone())
+            .await
+            .unwrap();
+        assert_eq!(updated.text, "Final draft");
+        assert_eq!(updated.tags, vec!["writing"]);
+        assert_eq!(updated.project.as_deref(), Some("book"));
+        assert_eq!(updated.priority, Priority::Medium);
+
+        let patch = TodoPatch {
+            tags: Some(vec!["#Edit".to_string(), "review".to_string()]),
+            project: Some(String::new()),
+            ..Default::default()
+        };
+        let updated = update_todo(DATE.to_string(), todo.id.clone(), patch, data_dir.clone())
+            .await
+            .unwrap();
+        assert_eq!(updated.tags, vec!["edit", "review"]);
+        assert!(updated.project.is_none());
+
+        let migrated = TodoPatch {
+            status: Some(TodoStatus::Migrated),
+            ..Default::default()
+        };
+        let rejected = update_todo(
+            DATE.to_string(),
+            todo.id.clone(),
+            migrated,
+            data_dir.clone(),
+        );
+        assert!(rejected.await.unwrap_err().contains("moving the todo"));
+
+        let missing = toggle_todo(DATE.to_string(), "missing".to_string(), data_dir).await;
+        assert!(missing.unwrap_err().contains("not found"));
+    }
+
+    #[tokio::test]
+    async fn test_delete_todo_moves_to_trash() {
+        let temp_dir = TempDir::new().unwrap();
+        let data_dir = temp_dir.path().to_string_lossy().to_string();
+        let todo = add_todo(DATE.to_string(), "Obsolete".to_string(), data_dir.clone())
+            .await
+            .unwrap();
+
+        delete_todo(DATE.to_string(), todo.id.clone(), data_dir.clone())
+            .await
+            .unwrap();
+
+        let loaded = load_day_data(DATE.to_string(), data_dir.clone(), None)
+            .await
+            .unwrap();
+        assert!(loaded.todos.is_empty());
+
+        let trash = trash::list_trash(data_dir).await.unwrap();
+        assert_eq!(trash.len(), 1);
+        assert_eq!(trash[0].todo.id, todo.id);
+    }
+
+    #[tokio::test]
+    async fn test_reorder_todos() {
+        let temp_dir = TempDir::new().unwrap();
+        let data_dir = temp_dir.path().to_string_lossy().to_string();
+        let mut ids = Vec::new();
+        for text in ["One", "Two", "Three"] {
+            let todo = add_todo(DATE.to_string(), text.to_string(), data_dir.clone())
+                .await
+                .unwrap();
+            ids.push(todo.id);
+        }
+
+        ids.reverse();
+        let day_data = reorder_todos(DATE.to_string(), ids.clone(), data_dir.clone())
+            .await
+            .unwrap();
+        let texts: Vec<&str> = day_data.todos.iter().map(|t| t.text.as_str()).collect();
+        assert_eq!(texts, vec!["Three", "Two", "One"]);
+
+        // Missing or duplicated IDs are rejected without touching the file
+        let partial = reorder_todos(DATE.to_string(), ids[..2].to_vec(), data_dir.clone()).await;
+        assert!(partial.is_err());
+        let duplicated = vec![ids[0].clone(), ids[0].clone(), ids[1].clone()];
+        assert!(
+            reorder_todos(DATE.to_string(), duplicated, data_dir.clone())
+                .await
+                .is_err()
+        );
+
+        let loaded = load_day_data(DATE.to_string(), data_dir, None)
+            .await
+            .unwrap();
+        assert_eq!(loaded.todos[0].text, "Three");
+    }
+
+    #[tokio::test]
+    async fn test_subtasks_auto_complete_parent() {
+        let temp_dir = TempDir::new().unwrap();
+        let data_dir = temp_dir.path().to_string_lossy().to_string();
+        let todo = add_todo(DATE.to_string(), "Release".to_string(), data_dir.clone())
+            .await
+            .unwrap();
+
+        let patch = TodoPatch {
+            auto_complete: Some(true),
+            ..Default::default()
+        };
+        update_todo(DATE.to_string(), todo.id.clone(), patch, data_dir.clone())
+            .await
+            .unwrap();
+        for step in ["Tag", "Publish"] {
+            add_subtask(
+                DATE.to_string(),
+                todo.id.clone(),
+                step.to_string(),
+                data_dir.clone(),
+            )
+            .await
+            .unwrap();
+        }
+
+        let loaded = load_day_data(DATE.to_string(), data_dir.clone(), None)
+            .await
+            .unwrap();
+        let subtask_ids: Vec<String> = loaded.todos[0]
+            .subtasks
+            .iter()
+            .map(|s| s.id.clone())
+            .collect();
+
+        let mut parent = None;
+        for subtask_id in &subtask_ids {
+            parent = Some(
+                toggle_subtask(
+                    DATE.to_string(),
+                    todo.id.clone(),
+                    subtask_id.clone(),
+                    data_dir.clone(),
+                )
+                .await
+                .unwrap(),
+            );
+        }
+        let parent = parent.unwrap();
+        assert_eq!(parent.status, TodoStatus::Done);
+        assert!(parent.completed_at.is_some());
+
+        // Unchecking a step reopens the parent
+        let parent = toggle_subtask(
+            DATE.to_string(),
+            todo.id.clone(),
+            subtask_ids[0].clone(),
+            data_dir.clone(),
+        )
+        .await
+        .unwrap();
+        assert_eq!(parent.status, TodoStatus::Open);
+
+        // Subtasks travel with the todo when it moves
+        crate::move_todo_to_date(
+            todo.id.clone(),
+            DATE.to_string(),
+            "2024-01-16".to_string(),
+            data_dir.clone(),
+        )
+        .await
+        .unwrap();
+        let moved = load_day_data("2024-01-16".to_string(), data_dir, None)
+            .await
+            .unwrap();
+        assert_eq!(moved.todos[0].subtasks.len(), 2);
+        assert!(moved.todos[0].subtasks[1].completed);
+    }
+}
@@ -0,0 +1,311 @@
+//! Trash for deleted todo items.
+//!
+//! When a save removes a todo from a day or collection, the removed item is kept in
+//! `trash.json` together with where it came from, so it can be restored later.
+//! Entries older than [`TRASH_RETENTION_DAYS`] are purged automatically whenever the
+//! trash is read.
+
+use crate::collections::Location;
+use crate::{day_file_path, read_day_file, DayData, TodoItem};
+use chrono::{DateTime, Duration, Local};
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// File in the data directory holding trashed todos
+pub(crate) const TRASH_FILE: &str = "trash.json";
+
+/// Trashed todos older than this are purged permanently
+const TRASH_RETENTION_DAYS: i64 = 30;
+
+/// A deleted todo item waiting in the trash
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct TrashEntry {
+    pub todo: TodoItem,
+    /// The day or collection the todo was deleted from
+    pub origin: Location,
+    pub deleted_at: DateTime<Local>,
+}
+
+fn trash_path(data_dir: &Path) -> PathBuf {
+    data_dir.join(TRASH_FILE)
+}
+
+/// Read the trash, dropping entries past the retention period.
+fn load_trash(data_dir: &Path, now: DateTime<Local>) -> Result<Vec<TrashEntry>, String> {
+    let file_path = trash_path(data_dir);
+    if !file_path.exists() {
+        return Ok(Vec::new());
+    }
+
+    let content =
+        fs::read_to_string(&file_path).map_err(|e| format!("Failed to read trash: {}", e))?;
+    let content = crate::encryption::open(data_dir, &content)?;
+    let mut entries: Vec<TrashEntry> =
+        serde_json::from_str(&content).map_err(|e| format!("Failed to parse trash: {}", e))?;
+
+    let cutoff = now - Duration::days(TRASH_RETENTION_DAYS);
+    let before = entries.len();
+    entries.retain(|entry| entry.deleted_at > cutoff);
+    if entries.len() != before {
+        save_trash(data_dir, &entries)?;
+    }
+
+    Ok(entries)
+}
+
+fn save_trash(data_dir: &Path, entries: &[TrashEntry]) -> Result<(), String> {
+    let json_content = serde_json::to_string_pretty(entries)
+        .map_err(|e| format!("Failed to serialize trash: {}", e))?;
+    let json_content = crate::encryption::seal(data_dir, &json_content)?;
+
+    fs::write(trash_path(data_dir), json_content)
+        .map_err(|e| format!("Failed to write trash: {}", e))
+}
+
+/// Put todos deleted from `origin` into the trash.
+pub(crate) fn move_to_trash(
+    data_dir: &Path,
+    origin: &Location,
+    todos: Vec<TodoItem>,
+) -> Result<(), String> {
+    if todos.is_empty() {
+        return Ok(());
+    }
+
+    let now = Local::now();
+    let mut entries = load_trash(data_dir, now)?;
+    entries.extend(todos.into_iter().map(|todo| TrashEntry {
+        todo,
+        origin: origin.clone(),
+        deleted_at: now,
+    }));
+
+    save_trash(data_dir, &entries)
+}
+
+/// Compare a day about to be saved with the version on disk and trash removed todos.
+pub(crate) fn track_deletions(data_dir: &Path, day_data: &DayData) -> Result<(), String> {
+    let stored = if day_file_path(data_dir, day_data.date).exists() {
+        read_day_file(data_dir, day_data.date)?.todos
+    } else {
+        Vec::new()
+    };
+    track_removed(
+        data_dir,
+        &Location::Day(day_data.date),
+        stored,
+        &day_data.todos,
+    )
+}
+
+/// Trash the `stored` todos of `origin` that are missing from `kept`.
+///
+/// Todos that reappear in `kept` (e.g. restored by hand) are dropped from the trash so
+/// they can't be restored twice.
+pub(crate) fn track_removed(
+    data_dir: &Path,
+    origin: &Location,
+    stored: Vec<TodoItem>,
+    kept: &[TodoItem],
+) -> Result<(), String> {
+    let kept_ids: HashSet<&str> = kept.iter().map(|t| t.id.as_str()).collect();
+    let removed: Vec<TodoItem> = stored
+        .into_iter()
+        .filter(|todo| !kept_ids.contains(todo.id.as_str()))
+        .collect();
+
+    let mut entries = load_trash(data_dir, Local::now())?;
+    let before = entries.len();
+    entries.retain(|entry| !kept_ids.contains(entry.todo.id.as_str()));
+    if entries.len() != before {
+        save_trash(data_dir, &entries)?;
+    }
+
+    move_to_trash(data_dir, origin, removed)
+}
+
+/// List the todos currently in the trash, most recently deleted first.
+///
+/// # Arguments
+/// * `data_dir` - Path to the app data directory
+///
+/// # Errors
+/// Returns an error if the trash file cannot be read.
+#[tauri::command]
+pub async fn list_trash(data_dir: String) -> Result<Vec<TrashEntry>, String> {
+    crate::app_lock::ensure_unlocked()?;
+    // Reading may purge expired entries, which rewrites the trash
+    let _guard = crate::DAY_FILES_LOCK.lock().await;
+    let mut entries = load_trash(Path::new(&data_dir), Local::now())?;
+    entries.sort_by_key(|entry| std::cmp::Reverse(entry.deleted_at));
+    Ok(entries)
+}
+
+/// Restore a trashed todo to the end of the day or collection it was deleted from.
+///
+/// A todo from a named collection that has since been deleted recreates the
+/// collection.
+/// # Arguments
+/// * `todo_id` - The unique ID of the trashed todo
+/// * `data_dir` - Path to the app data directory
+///
+/// # Returns
+/// The restored trash entry, including where the todo was put back.
+///
+/// # Errors
+/// Returns an error if the todo is not in the trash or file operations fail.
+#[tauri::command]
+pub async fn restore_from_trash(todo_id: String, data_dir: String) -> Result<TrashEntry, String> {
+    crate::app_lock::ensure_unlocked()?;
+    let data_dir = PathBuf::from(data_dir);
+    let _guard = crate::DAY_FILES_LOCK.lock().await;
+    let mut entries = load_trash(&data_dir, Local::now())?;
+
+    let index = entries
+        .iter()
+        .position(|entry| entry.todo.id == todo_id)
+        .ok_or_else(|| format!("Todo with ID {} not found in trash", todo_id))?;
+    let entry = entries.remove(index);
+
+    let mut list = entry.origin.open_or_recreate(&data_dir)?;
+    if !list.todos_mut().iter().any(|t| t.id == entry.todo.id) {
+        list.todos_mut().push(entry.todo.clone());
+        list.save(&data_dir)?;
+    }
+
+    save_trash(&data_dir, &entries)?;
+
+    Ok(entry)
+}
+
+/// Permanently delete everything in the trash.
+///
+/// # Arguments
+/// * `data_dir` - Path to the app data directory
+///
+/// # Returns
+/// The number of todos that were removed.
+///
+/// # Errors
+/// Returns an error if the trash file cannot be read or written.
+#[tauri::command]
+pub async fn empty_trash(data_dir: String) -> Result<usize, String> {
+    crate::app_lock::ensure_unlocked()?;
+    let data_dir = PathBuf::from(data_dir);
+    let _guard = crate::DAY_FILES_LOCK.lock().await;
+    let count = load_trash(&data_dir, Local::now())?.len();
+    save_trash(&data_dir, &[])?;
+    Ok(count)
+}
+
+/// Get how many days trashed todos are kept, so the frontend doesn't hardcode it.
+#[tauri::command]
+pub fn get_trash_retention_days() -> i64 {
+    TRASH_RETENTION_DAYS
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{create_todo_item, load_day_data, move_todo_to_date, save_day_data};
+    use chrono::NaiveDate;
+    use tempfile::TempDir;
+
+    fn date() -> NaiveDate {
+        NaiveDate::from_ymd_opt(2024, 1, 15).unwrap()
+    }
+
+    async fn save_with(todos: Vec<TodoItem>, data_dir: &str) {
+        let day_data = DayData {
+            date: date(),
+            todos,
+            notes: String::new(),
+        };
+        save_day_data(day_data, data_dir.to_string()).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_removed_todo_goes_to_trash_and_restores() {
+        let temp_dir = TempDir::new().unwrap();
+        let data_dir = temp_dir.path().to_string_lossy().to_string();
+
+        let keep = create_todo_item("Keep".to_string()).await.unwrap();
+        let delete = create_todo_item("Delete".to_string()).await.unwrap();
+        save_with(vec![keep.clone(), delete.clone()], &data_dir).await;
+        save_with(vec![keep.clone()], &data_dir).await;
+
+        let trash = list_trash(data_dir.clone()).await.unwrap();
+        assert_eq!(trash.len(), 1);
+        assert_eq!(trash[0].todo.id, delete.id);
+        assert_eq!(trash[0].origin, Location::Day(date()));
+
+        let entry = restore_from_trash(delete.id.clone(), data_dir.clone())
+            .await
+            .unwrap();
+        assert_eq!(entry.origin, Location::Day(date()));
+
+        let loaded = load_day_data("2024-01-15".to_string(), data_dir.clone(), None)
+            .await
+            .unwrap();
+        assert_eq!(loaded.todos.len(), 2);
+        assert_eq!(loaded.todos[1].id, delete.id);
+        assert!(list_trash(data_dir).await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_moved_todo_is_not_trashed() {
+        let temp_dir = TempDir::new().unwrap();
+        let data_dir = temp_dir.path().to_string_lossy().to_string();
+
+        let todo = create_todo_item("Move me".to_string()).await.unwrap();
+        save_with(vec![todo.clone()], &data_dir).await;
+
+        move_todo_to_date(
+            todo.id.clone(),
+            "2024-01-15".to_string(),
+            "2024-01-16".to_string(),
+            data_dir.clone(),
+        )
+        .await
+        .unwrap();
+
+        assert!(list_trash(data_dir).await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_empty_trash() {
+        let temp_dir = TempDir::new().unwrap();
+        let data_dir = temp_dir.path().to_string_lossy().to_string();
+
+        let todo = create_todo_item("Gone".to_string()).await.unwrap();
+        save_with(vec![todo], &data_dir).await;
+        save_with(vec![], &data_dir).await;
+
+        assert_eq!(empty_trash(data_dir.clone()).await.unwrap(), 1);
+        assert!(list_trash(data_dir.clone()).await.unwrap().is_empty());
+
+        let result = restore_from_trash("missing".to_string(), data_dir).await;
+        assert!(result.unwrap_err().contains("not found in trash"));
+    }
+
+    #[tokio::test]
+    async fn test_expired_entries_are_purged() {
+        let temp_dir = TempDir::new().unwrap();
+        let todo = create_todo_item("Old".to_string()).await.unwrap();
+        let entries = vec![TrashEntry {
+            todo,
+            origin: Location::Day(date()),
+            deleted_at: Local::now() - Duration::days(TRASH_RETENTION_DAYS + 1),
+        }];
+        save_trash(temp_dir.path(), &entries).unwrap();
+
+        assert!(load_trash(temp_dir.path(), Local::now())
+            .unwrap()
+            .is_empty());
+        // The purge is persisted
+        let content = fs::read_to_string(trash_path(temp_dir.path())).unwrap();
+        assert_eq!(content.trim(), "[]");
+    }
+}
@@ -0,0 +1,940 @@
+//! WebDAV sync target, e.g. a Nextcloud folder.
+//!
+//! Mirrors the JSON files at the top of the data directory to a WebDAV collection.
+//! Remote changes are detected by ETag and local changes by content hash, both compared
+//! with the state after the last sync. Uploads are conditional (`If-Match` /
+//! `If-None-Match`), so a file changed on the server in the meantime is never
+//! overwritten blindly. When a file changed on both sides, the two versions are merged
+//! by todo ID against the last synced copy kept in `webdav_base/`; for the same field
+//! of the same todo the local version wins.
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::fs;
+use std::path::{Path, PathBuf};
+use tauri::Emitter;
+
+/// Preference file holding the WebDAV settings
+const WEBDAV_SETTINGS_FILE: &str = "webdav.json";
+
+/// File holding the ETag and hash of every file after the last sync
+const WEBDAV_STATE_FILE: &str = "webdav_state.json";
+
+/// Directory holding the last synced copy of every file, the base for merges
+const WEBDAV_BASE_DIR: &str = "webdav_base";
+
+/// How often the background task checks whether a sync is due
+const CHECK_INTERVAL: std::time::Duration = std::time::Duration::from_secs(60);
+
+/// How long a single request to the server may take
+const REQUEST_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(30);
+
+/// Keeps the scheduler and `sync_webdav` from syncing at the same time, since the
+/// network calls run without `DAY_FILES_LOCK`
+static SYNC_LOCK: tokio::sync::Mutex<()> = tokio::sync::Mutex::const_new(());
+
+fn default_interval_minutes() -> u32 {
+    15
+}
+
+/// WebDAV sync settings
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct WebDavSettings {
+    #[serde(default)]
+    pub enabled: bool,
+    /// URL of the collection (folder) to mirror into
+    #[serde(default)]
+    pub url: String,
+    #[serde(default)]
+    pub username: String,
+    #[serde(default)]
+    pub password: String,
+    /// Minutes between automatic syncs
+    #[serde(default = "default_interval_minutes")]
+    pub interval_minutes: u32,
+}
+
+impl Default for WebDavSettings {
+    fn default() -> Self {
+        WebDavSettings {
+            enabled: false,
+            url: String::new(),
+            username: String::new(),
+            password: String::new(),
+            interval_minutes: default_interval_minutes(),
+        }
+    }
+}
+
+/// A file as it was after the last sync
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+struct SyncedFile {
+    etag: String,
+    hash: u64,
+}
+
+/// What a sync did, by file name
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq)]
+pub struct WebDavReport {
+    pub uploaded: Vec<String>,
+    pub downloaded: Vec<String>,
+    pub deleted_local: Vec<String>,
+    pub deleted_remote: Vec<String>,
+    /// Files changed on both sides and merged
+    pub conflicts: Vec<String>,
+}
+
+impl WebDavReport {
+    /// Whether local files were changed, so the frontend should reload.
+    fn changed_local(&self) -> bool {
+        !self.downloaded.is_empty() || !self.deleted_local.is_empty() || !self.conflicts.is_empty()
+    }
+}
+
+fn load_json_or_default<T: Default + for<'de> Deserialize<'de>>(
+    path: &Path,
+    what: &str,
+) -> Result<T, String> {
+    if !path.exists() {
+        return Ok(T::default());
+    }
+
+    let content =
+        fs::read_to_string(path).map_err(|e| format!("Failed to read {}: {}", what, e))?;
+    serde_json::from_str(&content).map_err(|e| format!("Failed to parse {}: {}", what, e))
+}
+
+fn load_settings(data_dir: &Path) -> Result<WebDavSettings, String> {
+    load_json_or_default(&data_dir.join(WEBDAV_SETTINGS_FILE), "WebDAV settings")
+}
+
+fn load_state(data_dir: &Path) -> Result<BTreeMap<String, SyncedFile>, String> {
+    load_json_or_default(&data_dir.join(WEBDAV_STATE_FILE), "WebDAV state")
+}
+
+fn save_state(data_dir: &Path, state: &BTreeMap<String, SyncedFile>) -> Result<(), String> {
+    let json_content = serde_json::to_string_pretty(state)
+        .map_err(|e| format!("Failed to serialize WebDAV state: {}", e))?;
+    fs::write(data_dir.join(WEBDAV_STATE_FILE), json_content)
+        .map_err(|e| format!("Failed to write WebDAV state: {}", e))
+}
+
+fn content_hash(content: &[u8]) -> u64 {
+    crate::peer_sync::stable_hash(content)
+}
+
+fn base_path(data_dir: &Path, name: &str) -> PathBuf {
+    data_dir.join(WEBDAV_BASE_DIR).join(name)
+}
+
+/// The mirrored files in the data directory and their contents.
+fn local_files(data_dir: &Path) -> Result<BTreeMap<String, Vec<u8>>, String> {
+    let entries =
+        fs::read_dir(data_dir).map_err(|e| format!("Failed to read data directory: {}", e))?;
+
+    let mut files = BTreeMap::new();
+    for entry in entries.filter_map(|entry| entry.ok()) {
+        let name = entry.file_name().to_string_lossy().to_string();
+        if !entry.path().is_file()
+            || !name.ends_with(".json")
+            || crate::LOCAL_ONLY_FILES.contains(&name.as_str())
+        {
+            continue;
+        }
+        let content =
+            fs::read(entry.path()).map_err(|e| format!("Failed to read {}: {}", name, e))?;
+        files.insert(name, content);
+    }
+    Ok(files)
+}
+
+/// Write a downloaded or merged file, through `write_day_file` for day files so the
+/// change is kept in the day's history.
+fn write_local(data_dir: &Path, name: &str, content: &[u8]) -> Result<(), String> {
+    let is_day = name
+        .strip_suffix(".json")
+        .is_some_and(|stem| chrono::NaiveDate::parse_from_str(stem, "%Y-%m-%d").is_ok());
+    if is_day {
+        let json = crate::encryption::open(data_dir, &String::from_utf8_lossy(content))?;
+        let day_data: crate::DayData =
+            serde_json::from_str(&json).map_err(|e| format!("Failed to parse {}: {}", name, e))?;
+        return crate::write_day_file(data_dir, &day_data);
+    }
+
+    let file_path = data_dir.join(name);
+    let temp_path = file_path.with_extension("json.tmp");
+    fs::write(&temp_path, content).map_err(|e| format!("Failed to write file: {}", e))?;
+    fs::rename(&temp_path, &file_path).map_err(|e| format!("Failed to write file: {}", e))
+}
+
+/// Merge a file changed both locally and remotely, decrypting and re-encrypting it if
+/// encryption is enabled.
+fn merge_contents(
+    data_dir: &Path,
+    base: Option<&[u8]>,
+    local: &[u8],
+    remote: &[u8],
+) -> Result<Vec<u8>, String> {
+    let parse = |content: &[u8]| -> Result<Option<Value>, String> {
+        let json = crate::encryption::open(data_dir, &String::from_utf8_lossy(content))?;
+        Ok(serde_json::from_str(&json).ok())
+    };
+    let (Some(ours), Some(theirs)) = (parse(local)?, parse(remote)?) else {
+        // Not JSON after all: keep the local version
+        return Ok(local.to_vec());
+    };
+    // A base sealed with a previous passphrase just makes this a two-way merge
+    let base = base.and_then(|base| parse(base).ok().flatten());
+
+    let merged =
+        crate::git_sync::merge_value(base.as_ref(), Some(&ours), Some(&theirs)).unwrap_or(ours);
+    let json_content = serde_json::to_string_pretty(&merged)
+        .map_err(|e| format!("Failed to serialize data: {}", e))?;
+    Ok(crate::encryption::seal(data_dir, &json_content)?.into_bytes())
+}
+
+/// Inner text of every element named `name`, whatever its namespace prefix.
+fn xml_elements<'a>(xml: &'a str, name: &str) -> Vec<&'a str> {
+    let mut found = Vec::new();
+    let mut rest = xml;
+    while let Some(start) = rest.find('<') {
+        rest = &rest[start + 1..];
+        let tag_end = rest.find(['>', ' ', '/']).unwrap_or(rest.len());
+        let tag = &rest[..tag_end];
+        let local = tag.rsplit(':').next().unwrap_or(tag);
+        if local != name || tag.starts_with('/') {
+            continue;
+        }
+
+        let Some(open_end) = rest.find('>') else {
+            break;
+        };
+        if rest[..open_end].ends_with('/') {
+            found.push("");
+            continue;
+        }
+        let body = &rest[open_end + 1..];
+        let close = format!("</{}>", tag);
+        match body.find(&close) {
+            Some(end) => {
+                found.push(&body[..end]);
+                rest = &body[end + close.len()..];
+            }
+            None => break,
+        }
+    }
+    found
+}
+
+fn percent_decode(text: &str) -> String {
+    let bytes = text.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        let hex = bytes
+            .get(i + 1..i + 3)
+            .and_then(|h| std::str::from_utf8(h).ok())
+            .and_then(|h| u8::from_str_radix(h, 16).ok());
+        match (bytes[i], hex) {
+            (b'%', Some(byte)) => {
+                decoded.push(byte);
+                i += 3;
+            }
+            (byte, _) => {
+                decoded.push(byte);
+                i += 1;
+            }
+        }
+    }
+    String::from_utf8_lossy(&decoded).to_string()
+}
+
+/// Normalize an ETag to its quoted strong form, as sent back in `If-Match`.
+fn normalize_etag(etag: &str) -> String {
+    let etag = etag.trim().replace("&quot;", "\"");
+    let etag = etag.strip_prefix("W/").unwrap_or(&etag);
+    format!("\"{}\"", etag.trim_matches('"'))
+}
+
+/// Result of a conditional upload
+enum PutOutcome {
+    Stored(String),
+    /// The server's copy changed since we last saw it
+    Conflict,
+}
+
+/// A WebDAV collection
+struct WebDavClient {
+    http: reqwest::Client,
+    base_url: String,
+    username: String,
+    password: String,
+}
+
+impl WebDavClient {
+    fn new(settings: &WebDavSettings) -> Result<Self, String> {
+        let url = settings.url.trim();
+        if !(url.starts_with("https://") || url.starts_with("http://")) {
+            return Err(format!("Invalid WebDAV URL: {}", url));
+        }
+
+        let http = reqwest::Client::builder()
+            .timeout(REQUEST_TIMEOUT)
+            .build()
+            .map_err(|e| format!("Failed to create HTTP client: {}", e))?;
+
+        Ok(WebDavClient {
+            http,
+            base_url: format!("{}/", url.trim_end_matches('/')),
+            username: settings.username.clone(),
+            password: settings.password.clone(),
+        })
+    }
+
+    fn request(&self, method: &str, name: &str) -> reqwest::RequestBuilder {
+        let method = reqwest::Method::from_bytes(method.as_bytes()).unwrap_or(reqwest::Method::GET);
+        self.http
+            .request(method, format!("{}{}", self.base_url, name))
+            .basic_auth(&self.username, Some(&self.password))
+    }
+
+    async fn send(
+        &self,
+        request: reqwest::RequestBuilder,
+        what: &str,
+    ) -> Result<reqwest::Response, String> {
+        request
+            .send()
+            .await
+            .map_err(|e| format!("Failed to {}: {}", what, e))
+    }
+
+    fn etag_header(response: &reqwest::Response) -> Option<String> {
+        response
+            .headers()
+            .get(reqwest::header::ETAG)
+            .and_then(|value| value.to_str().ok())
+            .map(normalize_etag)
+    }
+
+    /// The files in the collection with their ETags, creating the collection if needed.
+    async fn list(&self) -> Result<HashMap<String, String>, String> {
+        const PROPFIND_BODY: &str = "<?xml version=\"1.0\" encoding=\"utf-8\"?>\
+            <d:propfind xmlns:d=\"DAV:\"><d:prop><d:getetag/><d:resourcetype/></d:prop></d:propfind>";
+
+        let request = self
+            .request("PROPFIND", "")
+            .header("Depth", "1")
+            .header(reqwest::header::CONTENT_TYPE, "application/xml")
+            .body(PROPFIND_BODY);
+        let response = self.send(request, "list WebDAV folder").await?;
+
+        if response.status() == reqwest::StatusCode::NOT_FOUND {
+            let created = self
+                .send(self.request("MKCOL", ""), "create WebDAV folder")
+                .await?;
+            if !created.status().is_success() {
+                return Err(format!(
+                    "Failed to create WebDAV folder: {}",
+                    created.status()
+                ));
+            }
+            return Ok(HashMap::new());
+        }
+        if !response.status().is_success() {
+            return Err(format!(
+                "Failed to list WebDAV folder: {}",
+                response.status()
+            ));
+        }
+
+        let xml = response
+            .text()
+            .await
+            .map_err(|e| format!("Failed to list WebDAV folder: {}", e))?;
+        let mut files = HashMap::new();
+        for entry in xml_elements(&xml, "response") {
+            if !xml_elements(entry, "collection").is_empty() {
+                continue;
+            }
+            let (Some(href), Some(etag)) = (
+                xml_elements(entry, "href").first().copied(),
+                xml_elements(entry, "getetag").first().copied(),
+            ) else {
+                continue;
+            };
+            let name = percent_decode(
+                href.trim()
+                    .trim_end_matches('/')
+                    .rsplit('/')
+                    .next()
+                    .unwrap_or(""),
+            );
+            if !name.is_empty() {
+                files.insert(name, normalize_etag(etag));
+            }
+        }
+        Ok(files)
+    }
+
+    async fn get(&self, name: &str) -> Result<(Vec<u8>, String), String> {
+        let response = self
+            .send(self.request("GET", name), "download file")
+            .await?;
+        if !response.status().is_success() {
+            return Err(format!(
+                "Failed to download {}: {}",
+                name,
+                response.status()
+            ));
+        }
+
+        let etag = Self::etag_header(&response)
+            .ok_or_else(|| format!("Server sent no ETag for {}", name))?;
+        let content = response
+            .bytes()
+            .await
+            .map_err(|e| format!("Failed to download {}: {}", name, e))?;
+        Ok((content.to_vec(), etag))
+    }
+
+    /// Upload a file if the server's copy still has `expected` ETag (or doesn't exist
+    /// when `None`).
+    async fn put(
+        &self,
+        name: &str,
+        content: Vec<u8>,
+        expected: Option<&str>,
+    ) -> Result<PutOutcome, String> {
+        let request = match expected {
+            Some(etag) => self
+                .request("PUT", name)
+                .header(reqwest::header::IF_MATCH, etag),
+            None => self
+                .request("PUT", name)
+                .header(reqwest::header::IF_NONE_MATCH, "*"),
+        };
+        let response = self.send(request.body(content), "upload file").await?;
+
+        if response.status() == reqwest::StatusCode::PRECONDITION_FAILED {
+            return Ok(PutOutcome::Conflict);
+        }
+        if !response.status().is_success() {
+            return Err(format!("Failed to upload {}: {}", name, response.status()));
+        }
+
+        // Not every server returns the new ETag with the upload
+        match Self::etag_header(&response) {
+            Some(etag) => Ok(PutOutcome::Stored(etag)),
+            None => {
+                let head = self.send(self.request("HEAD", name), "check file").await?;
+                Self::etag_header(&head)
+                    .map(PutOutcome::Stored)
+                    .ok_or_else(|| format!("Server sent no ETag for {}", name))
+            }
+        }
+    }
+
+    /// Delete a file if it still has the `expected` ETag.
+    ///
+    /// # Returns
+    /// False if the file changed on the server in the meantime.
+    async fn delete(&self, name: &str, expected: &str) -> Result<bool, String> {
+        let request = self
+            .request("DELETE", name)
+            .header(reqwest::header::IF_MATCH, expected);
+        let response = self.send(request, "delete file").await?;
+
+        match response.status() {
+            reqwest::StatusCode::PRECONDITION_FAILED => Ok(false),
+            reqwest::StatusCode::NOT_FOUND => Ok(true),
+            status if status.is_success() => Ok(true),
+            status => Err(format!("Failed to delete {}: {}", name, status)),
+        }
+    }
+}
+
+/// Upload a local file, returning what is now on both sides or `None` if the file
+/// changed on the server since the listing (the next sync merges it).
+async fn upload(
+    client: &WebDavClient,
+    name: &str,
+    content: &[u8],
+    expected: Option<&str>,
+    report: &mut WebDavReport,
+) -> Result<Option<(Vec<u8>, String)>, String> {
+    match client.put(name, content.to_vec(), expected).await? {
+        PutOutcome::Stored(etag) => {
+            report.uploaded.push(name.to_string());
+            Ok(Some((content.to_vec(), etag)))
+        }
+        PutOutcome::Conflict => Ok(None),
+    }
+}
+
+/// Write a file from the server unless it changed locally since `expected` was read,
+/// holding `DAY_FILES_LOCK` only for this local step.
+///
+/// # Returns
+/// The file as now on disk, or `None` if it changed locally in the meantime.
+async fn store_if_unchanged(
+    data_dir: &Path,
+    name: &str,
+    expected: Option<&Vec<u8>>,
+    content: &[u8],
+) -> Result<Option<Vec<u8>>, String> {
+    let _guard = crate::DAY_FILES_LOCK.lock().await;
+    if fs::read(data_dir.join(name)).ok().as_ref() != expected {
+        return Ok(None);
+    }
+
+    write_local(data_dir, name, content)?;
+    // Day files are re-serialized when written
+    fs::read(data_dir.join(name))
+        .map(Some)
+        .map_err(|e| format!("Failed to read {}: {}", name, e))
+}
+
+/// Download a remote file into the data directory, unless the local copy changed
+/// since `expected` was read (the next sync merges it).
+async fn download(
+    client: &WebDavClient,
+    data_dir: &Path,
+    name: &str,
+    expected: Option<&Vec<u8>>,
+    report: &mut WebDavReport,
+) -> Result<Option<(Vec<u8>, String)>, String> {
+    let (content, etag) = client.get(name).await?;
+    let Some(on_disk) = store_if_unchanged(data_dir, name, expected, &content).await? else {
+        return Ok(None);
+    };
+    report.downloaded.push(name.to_string());
+    Ok(Some((on_disk, etag)))
+}
+
+/// Bring the data directory and the WebDAV collection in line with each other.
+///
+/// Network calls run without `DAY_FILES_LOCK`; it is only taken to read and write local
+/// files, and a file edited locally while the sync runs is left for the next one.
+async fn sync(data_dir: &Path, settings: &WebDavSettings) -> Result<WebDavReport, String> {
+    let _sync_guard = SYNC_LOCK.lock().await;
+    let client = WebDavClient::new(settings)?;
+    let remote = client.list().await?;
+    let local = {
+        let _guard = crate::DAY_FILES_LOCK.lock().await;
+        local_files(data_dir)?
+    };
+    let mut state = load_state(data_dir)?;
+    let mut report = WebDavReport::default();
+    fs::create_dir_all(data_dir.join(WEBDAV_BASE_DIR))
+        .map_err(|e| format!("Failed to create directory: {}", e))?;
+
+    let names: BTreeSet<String> = remote
+        .keys()
+        .chain(local.keys())
+        .chain(state.keys())
+        .filter(|name| name.ends_with(".json") && !crate::LOCAL_ONLY_FILES.contains(&name.as_str()))
+        .cloned()
+        .collect();
+
+    for name in names {
+        let synced = state.get(&name).cloned();
+        let local_content = local.get(&name);
+        let remote_etag = remote.get(&name);
+        let local_changed = match (&synced, local_content) {
+            (Some(synced), Some(content)) => synced.hash != content_hash(content),
+            (None, None) => false,
+            _ => true,
+        };
+        let remote_changed = synced.as_ref().map(|s| &s.etag) != remote_etag;
+
+        // The content both sides agree on after this file is synced
+        let settled: Option<(Vec<u8>, String)> = match (local_content, remote_etag) {
+            (None, None) => None,
+            _ if !local_changed && !remote_changed => continue,
+
+            // Deleted on one side and unchanged on the other
+            (Some(content), None) if !local_changed => {
+                let _guard = crate::DAY_FILES_LOCK.lock().await;
+                if fs::read(data_dir.join(&name)).ok().as_ref() != Some(content) {
+                    continue;
+                }
+                fs::remove_file(data_dir.join(&name))
+                    .map_err(|e| format!("Failed to delete {}: {}", name, e))?;
+                report.deleted_local.push(name.clone());
+                None
+            }
+            (None, Some(etag)) if !remote_changed => {
+                if client.delete(&name, etag).await? {
+                    report.deleted_remote.push(name.clone());
+                    None
+                } else {
+                    match download(&client, data_dir, &name, None, &mut report).await? {
+                        Some(settled) => Some(settled),
+                        None => continue,
+                    }
+                }
+            }
+
+            // New or changed on one side only; an edit wins over a deletion
+            (Some(content), None) => {
+                match upload(&client, &name, content, None, &mut report).await? {
+                    Some(settled) => Some(settled),
+                    None => continue,
+                }
+            }
+            (Some(content), Some(etag)) if !remote_changed => {
+                match upload(&client, &name, content, Some(etag), &mut report).await? {
+                    Some(settled) => Some(settled),
+                    None => continue,
+                }
+            }
+            (None, Some(_)) => match download(&client, data_dir, &name, None, &mut report).await? {
+                Some(settled) => Some(settled),
+                None => continue,
+            },
+            (Some(_), Some(_)) if !local_changed => {
+                match download(&client, data_dir, &name, local_content, &mut report).await? {
+                    Some(settled) => Some(settled),
+                    None => continue,
+                }
+            }
+
+            // Changed on both sides
+            (Some(content), Some(_)) => {
+                let (remote_content, remote_etag) = client.get(&name).await?;
+                let base = fs::read(base_path(data_dir, &name)).ok();
+                let merged = merge_contents(data_dir, base.as_deref(), content, &remote_content)?;
+
+                let PutOutcome::Stored(etag) = client
+                    .put(&name, merged.clone(), Some(&remote_etag))
+                    .await?
+                else {
+                    continue;
+                };
+                let Some(on_disk) =
+                    store_if_unchanged(data_dir, &name, local_content, &merged).await?
+                else {
+                    continue;
+                };
+                report.conflicts.push(name.clone());
+                Some((on_disk, etag))
+            }
+        };
+
+        match settled {
+            Some((content, etag)) => {
+                fs::write(base_path(data_dir, &name), &content)
+                    .map_err(|e| format!("Failed to write {}: {}", name, e))?;
+                state.insert(
+                    name,
+                    SyncedFile {
+                        etag,
+                        hash: content_hash(&content),
+                    },
+                );
+            }
+            None => {
+                let _ = fs::remove_file(base_path(data_dir, &name));
+                state.remove(&name);
+            }
+        }
+    }
+
+    save_state(data_dir, &state)?;
+    Ok(report)
+}
+
+/// Background task syncing with the WebDAV server every `interval_minutes`, started
+/// from `setup`.
+pub async fn run_webdav_scheduler(app: tauri::AppHandle) {
+    let mut last_sync: Option<std::time::Instant> = None;
+
+    loop {
+        tokio::time::sleep(CHECK_INTERVAL).await;
+
+        let result: Result<(), String> = async {
+            let data_dir = crate::resolve_data_dir(&app)?;
+            let settings = load_settings(&data_dir)?;
+            let interval =
+                std::time::Duration::from_secs(u64::from(settings.interval_minutes) * 60);
+            if !settings.enabled || last_sync.is_some_and(|last| last.elapsed() < interval) {
+                return Ok(());
+            }
+            last_sync = Some(std::time::Instant::now());
+
+            let report = sync(&data_dir, &settings).await?;
+            if report.changed_local() {
+                app.emit("webdav-sync-complete", report)
+                    .map_err(|e| format!("Failed to emit WebDAV sync event: {}", e))?;
+            }
+            Ok(())
+        }
+        .await;
+
+        if let Err(e) = result {
+            #[cfg(debug_assertions)]
+            eprintln!("WebDAV sync failed: {}", e);
+            let _ = e;
+        }
+    }
+}
+
+/// Load the WebDAV settings.
+///
+/// # Arguments
+/// * `data_dir` - Path to the app data directory
+///
+/// # Errors
+/// Returns an error if the settings file cannot be read.
+#[tauri::command]
+pub async fn load_webdav_settings(data_dir: String) -> Result<WebDavSettings, String> {
+    load_settings(Path::new(&data_dir))
+}
+
+/// Save the WebDAV settings.
+///
+/// # Arguments
+/// * `settings` - The settings to store
+/// * `data_dir` - Path to the app data directory
+///
+/// # Errors
+/// Returns an error if sync is enabled with an invalid URL or saving fails.
+#[tauri::command]
+pub async fn save_webdav_settings(
+    settings: WebDavSettings,
+    data_dir: String,
+) -> Result<(), String> {
+    if settings.enabled {
+        WebDavClient::new(&settings)?;
+    }
+
+    let json_content = serde_json::to_string_pretty(&settings)
+        .map_err(|e| format!("Failed to serialize WebDAV settings: {}", e))?;
+    fs::write(
+        Path::new(&data_dir).join(WEBDAV_SETTINGS_FILE),
+        json_content,
+    )
+    .map_err(|e| format!("Failed to write WebDAV settings: {}", e))
+}
+
+/// Sync with the WebDAV server now.
+///
+/// # Arguments
+/// * `data_dir` - Path to the app data directory
+///
+/// # Returns
+/// The files uploaded, downloaded, deleted and merged.
+///
+/// # Errors
+/// Returns an error if WebDAV sync is disabled, the server can't be reached or
+/// refuses a request, or file operations fail.
+#[tauri::command]
+pub async fn sync_webdav(data_dir: String) -> Result<WebDavReport, String> {
+    crate::app_lock::ensure_unlocked()?;
+    let data_dir = Path::new(&data_dir);
+    let settings = load_settings(data_dir)?;
+    if !settings.enabled {
+        return Err("WebDAV sync is not enabled".to_string());
+    }
+
+    sync(data_dir, &settings).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{read_day_file, write_day_file, DayData, TodoItem, TodoStatus};
+    use chrono::NaiveDate;
+    use std::sync::Arc;
+    use tempfile::TempDir;
+    use tokio::io::{AsyncBufReadExt, AsyncReadExt, AsyncWriteExt, BufReader};
+    use tokio::net::TcpListener;
+    use tokio::sync::Mutex;
+
+    /// Files on the stand-in server, with their ETag counters
+    type Files = Arc<Mutex<HashMap<String, (Vec<u8>, u64)>>>;
+
+    /// A minimal WebDAV server: PROPFIND, GET, HEAD, PUT, DELETE and MKCOL on a single
+    /// collection at `/dav/`, with ETags and conditional requests.
+    async fn spawn_server() -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}/dav/", listener.local_addr().unwrap());
+        let files: Files = Arc::default();
+        let next_etag = Arc::new(Mutex::new(0u64));
+
+        tokio::spawn(async move {
+            while let Ok((stream, _)) = listener.accept().await {
+                let (files, next_etag) = (files.clone(), next_etag.clone());
+                tokio::spawn(async move {
+                    let (reader, mut writer) = stream.into_split();
+                    let mut reader = BufReader::new(reader);
+                    let mut request_line = String::new();
+                    reader.read_line(&mut request_line).await.unwrap();
+                    let mut headers = HashMap::new();
+                    loop {
+                        let mut line = String::new();
+                        reader.read_line(&mut line).await.unwrap();
+                        let Some((key, value)) = line.trim_end().split_once(": ") else {
+                            break;
+                        };
+                        headers.insert(key.to_lowercase(), value.to_string());
+                    }
+                    let length = headers
+                        .get("content-length")
+                        .map_or(0, |l| l.parse().unwrap());
+                    let mut body = vec![0; length];
+                    reader.read_exact(&mut body).await.unwrap();
+
+                    let mut parts = request_line.split_whitespace();
+                    let method = parts.next().unwrap().to_string();
+                    let name = parts
+                        .next()
+                        .unwrap()
+                        .trim_start_matches("/dav/")
+                        .to_string();
+                    let mut files = files.lock().await;
+                    let current = files.get(&name).map(|(_, etag)| format!("\"{}\"", etag));
+                    let precondition_failed = headers
+                        .get("if-match")
+                        .is_some_and(|expected| Some(expected) != current.as_ref())
+                        || (headers.contains_key("if-none-match") && current.is_some());
+
+                    let (status, etag, content) = if !headers.contains_key("authorization") {
+                        ("401 Unauthorized", None, Vec::new())
+                    } else if precondition_failed {
+                        ("412 Precondition Failed", None, Vec::new())
+                    } else {
+                        match method.as_str() {
+                            "PROPFIND" => {
+                                let mut xml = String::from("<?xml version=\"1.0\"?><d:multistatus xmlns:d=\"DAV:\"><d:response><d:href>/dav/</d:href><d:propstat><d:prop><d:resourcetype><d:collection/></d:resourcetype></d:prop></d:propstat></d:response>");
+                                for (file, (_, etag)) in files.iter() {
+                                    xml.push_str(&format!("<d:response><d:href>/dav/{}</d:href><d:propstat><d:prop><d:getetag>&quot;{}&quot;</d:getetag><d:resourcetype/></d:prop></d:propstat></d:response>", file, etag));
+                                }
+                                xml.push_str("</d:multistatus>");
+                                ("207 Multi-Status", None, xml.into_bytes())
+                            }
+                            "GET" | "HEAD" => match files.get(&name) {
+                                Some((content, _)) if method == "GET" => {
+                                    ("200 OK", current.clone(), content.clone())
+                                }
+                                Some(_) => ("200 OK", current.clone(), Vec::new()),
+                                None => ("404 Not Found", None, Vec::new()),
+                            },
+                            "PUT" => {
+                                let mut next = next_etag.lock().await;
+                                *next += 1;
+                                files.insert(name.clone(), (body, *next));
+                                ("201 Created", Some(format!("\"{}\"", next)), Vec::new())
+                            }
+                            "DELETE" => {
+                                files.remove(&name);
+                                ("204 No Content", None, Vec::new())
+                            }
+                            _ => ("201 Created", None, Vec::new()),
+                        }
+                    };
+
+                    let mut response = format!(
+                        "HTTP/1.1 {}\r\nContent-Length: {}\r\nConnection: close\r\n",
+                        status,
+                        content.len()
+                    );
+                    if let Some(etag) = etag {
+                        response.push_str(&format!("ETag: {}\r\n", etag));
+                    }
+                    response.push_str("\r\n");
+                    writer.write_all(response.as_bytes()).await.unwrap();
+                    if method != "HEAD" {
+                        writer.write_all(&content).await.unwrap();
+                    }
+                });
+            }
+        });
+        url
+    }
+
+    #[test]
+    fn test_xml_and_etag_parsing() {
+        let xml =
+            "<D:multistatus xmlns:D=\"DAV:\"><D:response><D:href>/dav/2024-01-15.json</D:href>\
+                   <D:getetag>W/&quot;abc&quot;</D:getetag></D:response></D:multistatus>";
+        let responses = xml_elements(xml, "response");
+        assert_eq!(responses.len(), 1);
+        assert_eq!(
+            xml_elements(responses[0], "href"),
+            vec!["/dav/2024-01-15.json"]
+        );
+        assert_eq!(
+            normalize_etag(xml_elements(responses[0], "getetag")[0]),
+            "\"abc\""
+        );
+        assert_eq!(
+            percent_decode("Books%20to%20read.json"),
+            "Books to read.json"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_two_devices_sync_through_server() {
+        let url = spawn_server().await;
+        let settings = WebDavSettings {
+            enabled: true,
+            url,
+            username: "sam".to_string(),
+            password: "secret".to_string(),
+            interval_minutes: 15,
+        };
+        let laptop_dir = TempDir::new().unwrap();
+        let desktop_dir = TempDir::new().unwrap();
+        let (laptop, desktop) = (laptop_dir.path(), desktop_dir.path());
+
+        let date = NaiveDate::from_ymd_opt(2024, 1, 15).unwrap();
+        let day_data = DayData {
+            date,
+            todos: vec![TodoItem::new("Email".to_string())],
+            notes: String::new(),
+        };
+        write_day_file(laptop, &day_data).unwrap();
+        fs::write(laptop.join("zoom_level.json"), "{\"zoom_level\": 1.2}").unwrap();
+        fs::write(laptop.join(WEBDAV_SETTINGS_FILE), "{}").unwrap();
+
+        let report = sync(laptop, &settings).await.unwrap();
+        assert_eq!(report.uploaded, vec!["2024-01-15.json", "zoom_level.json"]);
+        let report = sync(desktop, &settings).await.unwrap();
+        assert_eq!(report.downloaded.len(), 2);
+        assert_eq!(
+            sync(desktop, &settings).await.unwrap(),
+            WebDavReport::default()
+        );
+
+        // Both devices change the same day
+        let mut on_laptop = read_day_file(laptop, date).unwrap();
+        on_laptop.todos[0].status = TodoStatus::Done;
+        write_day_file(laptop, &on_laptop).unwrap();
+        let mut on_desktop = read_day_file(desktop, date).unwrap();
+        on_desktop.todos.push(TodoItem::new("Gym".to_string()));
+        write_day_file(desktop, &on_desktop).unwrap();
+
+        sync(laptop, &settings).await.unwrap();
+        let report = sync(desktop, &settings).await.unwrap();
+        assert_eq!(report.conflicts, vec!["2024-01-15.json"]);
+        let report = sync(laptop, &settings).await.unwrap();
+        assert_eq!(report.downloaded, vec!["2024-01-15.json"]);
+
+        for dir in [laptop, desktop] {
+            let merged = read_day_file(dir, date).unwrap();
+            assert_eq!(merged.todos.len(), 2);
+            assert_eq!(merged.todos[0].status, TodoStatus::Done);
+        }
+
+        // Deletions propagate
+        fs::remove_file(laptop.join("zoom_level.json")).unwrap();
+        let report = sync(laptop, &settings).await.unwrap();
+        assert_eq!(report.deleted_remote, vec!["zoom_level.json"]);
+        let report = sync(desktop, &settings).await.unwrap();
+        assert_eq!(report.deleted_local, vec!["zoom_level.json"]);
+        assert!(!desktop.join("zoom_level.json").exists());
+    }
+}
@@ -0,0 +1,447 @@
+//! Workspaces.
+//!
+//! A workspace is a named data directory, so work todos can live in a shared synced
+//! folder while personal ones stay local. The registry of workspaces and the active one
+//! are kept in `workspaces.json` in the app config directory, outside any workspace.
+//! Everything else (day files, collections, preferences, snapshots) lives in the active
+//! workspace's root, so each workspace has its own preferences. The "Default" workspace
+//! starts out in the platform app data directory, where data lived before workspaces
+//! existed; `set_data_dir` points the active workspace anywhere else.
+
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use tauri::{Emitter, Manager};
+
+/// File in the app config directory holding the workspace registry
+const WORKSPACES_FILE: &str = "workspaces.json";
+
+/// Name of the workspace using the platform app data directory
+const DEFAULT_WORKSPACE: &str = "Default";
+
+/// A named data directory
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct Workspace {
+    pub name: String,
+    /// Directory holding the workspace's day files and preferences
+    pub root: PathBuf,
+}
+
+/// All known workspaces and the one in use
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct WorkspaceRegistry {
+    /// Name of the active workspace
+    pub active: String,
+    pub workspaces: Vec<Workspace>,
+}
+
+impl WorkspaceRegistry {
+    fn with_default(default_root: &Path) -> Self {
+        WorkspaceRegistry {
+            active: DEFAULT_WORKSPACE.to_string(),
+            workspaces: vec![Workspace {
+                name: DEFAULT_WORKSPACE.to_string(),
+                root: default_root.to_path_buf(),
+            }],
+        }
+    }
+
+    fn find(&self, name: &str) -> Option<&Workspace> {
+        self.workspaces
+            .iter()
+            .find(|w| w.name.eq_ignore_ascii_case(name))
+    }
+
+    pub(crate) fn active_workspace(&self) -> Result<&Workspace, String> {
+        self.find(&self.active)
+            .ok_or_else(|| format!("Active workspace \"{}\" not found", self.active))
+    }
+
+    /// Register a new workspace. Names are unique regardless of case.
+    fn add(&mut self, name: &str, root: PathBuf) -> Result<Workspace, String> {
+        let name = name.trim();
+        if name.is_empty() {
+            return Err("Workspace name cannot be empty".to_string());
+        }
+        if self.find(name).is_some() {
+            return Err(format!("Workspace \"{}\" already exists", name));
+        }
+        if !root.is_absolute() {
+            return Err(format!(
+                "Workspace directory must be an absolute path: {}",
+                root.display()
+            ));
+        }
+
+        let workspace = Workspace {
+            name: name.to_string(),
+            root,
+        };
+        self.workspaces.push(workspace.clone());
+        Ok(workspace)
+    }
+
+    /// Point the active workspace at another directory.
+    fn set_active_root(&mut self, root: PathBuf) -> Result<(), String> {
+        let active = self.active.clone();
+        let workspace = self
+            .workspaces
+            .iter_mut()
+            .find(|w| w.name.eq_ignore_ascii_case(&active))
+            .ok_or_else(|| format!("Active workspace \"{}\" not found", active))?;
+        workspace.root = root;
+        Ok(())
+    }
+
+    /// Make the named workspace the active one.
+    fn activate(&mut self, name: &str) -> Result<Workspace, String> {
+        let workspace = self
+            .find(name)
+            .cloned()
+            .ok_or_else(|| format!("Workspace \"{}\" not found", name))?;
+        self.active = workspace.name.clone();
+        Ok(workspace)
+    }
+}
+
+/// Load the registry, or a registry with only the default workspace if there is none.
+pub(crate) fn load_registry(
+    config_dir: &Path,
+    default_root: &Path,
+) -> Result<WorkspaceRegistry, String> {
+    let file_path = config_dir.join(WORKSPACES_FILE);
+    if !file_path.exists() {
+        return Ok(WorkspaceRegistry::with_default(default_root));
+    }
+
+    let content =
+        fs::read_to_string(&file_path).map_err(|e| format!("Failed to read workspaces: {}", e))?;
+    serde_json::from_str(&content).map_err(|e| format!("Failed to parse workspaces: {}", e))
+}
+
+pub(crate) fn save_registry(config_dir: &Path, registry: &WorkspaceRegistry) -> Result<(), String> {
+    let json_content = serde_json::to_string_pretty(registry)
+        .map_err(|e| format!("Failed to serialize workspaces: {}", e))?;
+
+    fs::create_dir_all(config_dir)
+        .map_err(|e| format!("Failed to create config directory: {}", e))?;
+    fs::write(config_dir.join(WORKSPACES_FILE), json_content)
+        .map_err(|e| format!("Failed to write workspaces: {}", e))
+}
+
+/// The app config directory (holding the registry) and the platform app data
+/// directory (the default workspace's root).
+pub(crate) fn app_dirs(app: &tauri::AppHandle) -> Result<(PathBuf, PathBuf), String> {
+    let config_dir = app
+        .path()
+        .app_config_dir()
+        .map_err(|e| format!("Failed to get app config directory: {}", e))?;
+    let default_root = app
+        .path()
+        .app_data_dir()
+        .map_err(|e| format!("Failed to get app data directory: {}", e))?;
+    Ok((config_dir, default_root))
+}
+
+/// Root directory of the active workspace.
+pub(crate) fn active_root(app: &tauri::AppHandle) -> Result<PathBuf, String> {
+    let (config_dir, default_root) = app_dirs(app)?;
+    let registry = load_registry(&config_dir, &default_root)?;
+    Ok(registry.active_workspace()?.root.clone())
+}
+
+/// Where a new workspace lives unless a directory is given: a directory named after it
+/// next to the platform app data directory, outside the Default workspace's root.
+fn default_workspace_root(default_root: &Path, name: &str) -> PathBuf {
+    let base = default_root
+        .file_name()
+        .map(|dir| format!("{}-workspaces", dir.to_string_lossy()))
+        .unwrap_or_else(|| "workspaces".to_string());
+    default_root
+        .with_file_name(base)
+        .join(crate::collections::slugify(name))
+}
+
+/// Tell the frontend to reload from another data directory.
+fn announce_data_dir(app: &tauri::AppHandle, data_dir: &str) {
+    if let Err(e) = app.emit("data-dir-changed", data_dir.to_string()) {
+        #[cfg(debug_assertions)]
+        eprintln!("Failed to emit data directory change: {}", e);
+        let _ = e;
+    }
+}
+
+/// Create a directory if needed and check that files can be written to it.
+fn check_writable(dir: &Path) -> Result<(), String> {
+    fs::create_dir_all(dir).map_err(|e| format!("Failed to create data directory: {}", e))?;
+
+    let probe = dir.join(".write-test");
+    fs::write(&probe, b"").map_err(|e| format!("Data directory is not writable: {}", e))?;
+    fs::remove_file(&probe).map_err(|e| format!("Data directory is not writable: {}", e))
+}
+
+pub(crate) fn copy_recursively(from: &Path, to: &Path) -> Result<(), String> {
+    if from.is_dir() {
+        fs::create_dir_all(to).map_err(|e| format!("Failed to create directory: {}", e))?;
+        let entries = fs::read_dir(from).map_err(|e| format!("Failed to read directory: {}", e))?;
+        for entry in entries.filter_map(|entry| entry.ok()) {
+            copy_recursively(&entry.path(), &to.join(entry.file_name()))?;
+        }
+        Ok(())
+    } else {
+        fs::copy(from, to)
+            .map(|_| ())
+            .map_err(|e| format!("Failed to copy {}: {}", from.display(), e))
+    }
+}
+
+/// Move a workspace's data to another directory.
+///
+/// Moves every top-level file (day files, collections, preferences, trash) and the
+/// snapshots, day history and CRDT day document directories; other subdirectories are
+/// left alone. Everything is copied before anything is removed, so a failure leaves the
+/// original data intact.
+///
+/// # Returns
+/// The number of files and directories moved.
+fn migrate_data(from: &Path, to: &Path) -> Result<usize, String> {
+    if !from.exists() || (to.exists() && fs::canonicalize(from).ok() == fs::canonicalize(to).ok()) {
+        return Ok(0);
+    }
+
+    let entries =
+        fs::read_dir(from).map_err(|e| format!("Failed to read data directory: {}", e))?;
+    let names: Vec<std::ffi::OsString> = entries
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| {
+            entry.path().is_file()
+                || [
+                    crate::snapshots::SNAPSHOTS_DIR,
+                    crate::history::HISTORY_DIR,
+                    crate::crdt::CRDT_DIR,
+                ]
+                .iter()
+                .any(|dir| entry.file_name() == *dir)
+        })
+        .map(|entry| entry.file_name())
+        .collect();
+
+    if let Some(existing) = names.iter().find(|name| to.join(name).exists()) {
+        return Err(format!(
+            "{} already contains {}",
+            to.display(),
+            existing.to_string_lossy()
+        ));
+    }
+
+    for name in &names {
+        copy_recursively(&from.join(name), &to.join(name))?;
+    }
+    for name in &names {
+        let path = from.join(name);
+        let removed = if path.is_dir() {
+            fs::remove_dir_all(&path)
+        } else {
+            fs::remove_file(&path)
+        };
+        removed.map_err(|e| format!("Failed to remove {}: {}", path.display(), e))?;
+    }
+
+    Ok(names.len())
+}
+
+/// List the workspaces and which one is active.
+///
+/// # Arguments
+/// * `app` - Tauri app handle for locating the registry
+///
+/// # Errors
+/// Returns an error if the registry cannot be read.
+#[tauri::command]
+pub async fn list_workspaces(app: tauri::AppHandle) -> Result<WorkspaceRegistry, String> {
+    let (config_dir, default_root) = app_dirs(&app)?;
+    load_registry(&config_dir, &default_root)
+}
+
+/// Create a workspace. The active workspace is unchanged.
+///
+/// # Arguments
+/// * `name` - Unique name of the workspace
+/// * `root` - Absolute path of its data directory; defaults to a directory named after
+///   the workspace next to the app data directory
+/// * `app` - Tauri app handle for locating the registry
+///
+/// # Returns
+/// The new workspace.
+///
+/// # Errors
+/// Returns an error if the name is empty or taken, the path is relative, or the
+/// directory or registry cannot be written.
+#[tauri::command]
+pub async fn create_workspace(
+    name: String,
+    root: Option<String>,
+    app: tauri::AppHandle,
+) -> Result<Workspace, String> {
+    let (config_dir, default_root) = app_dirs(&app)?;
+    let mut registry = load_registry(&config_dir, &default_root)?;
+
+    let root = root
+        .map(PathBuf::from)
+        .unwrap_or_else(|| default_workspace_root(&default_root, &name));
+    let workspace = registry.add(&name, root)?;
+
+    fs::create_dir_all(&workspace.root)
+        .map_err(|e| format!("Failed to create workspace directory: {}", e))?;
+    save_registry(&config_dir, &registry)?;
+
+    Ok(workspace)
+}
+
+/// Switch to another workspace.
+///
+/// Emits "data-dir-changed" with the new data directory so the frontend reloads.
+/// Background tasks pick up the new directory on their next pass.
+///
+/// # Arguments
+/// * `name` - Name of the workspace
+/// * `app` - Tauri app handle for locating the registry
+///
+/// # Returns
+/// The workspace's data directory.
+///
+/// # Errors
+/// Returns an error if the workspace doesn't exist or the registry cannot be written.
+#[tauri::command]
+pub async fn switch_workspace(name: String, app: tauri::AppHandle) -> Result<String, String> {
+    let (config_dir, default_root) = app_dirs(&app)?;
+    let mut registry = load_registry(&config_dir, &default_root)?;
+
+    registry.activate(&name)?;
+    save_registry(&config_dir, &registry)?;
+
+    let data_dir = crate::resolve_data_dir(&app)?.to_string_lossy().to_string();
+    announce_data_dir(&app, &data_dir);
+
+    Ok(data_dir)
+}
+
+/// Use a custom directory, such as a git repository or synced folder, as the active
+/// workspace's data directory.
+///
+/// Emits "data-dir-changed" with the new data directory so the frontend reloads.
+///
+/// # Arguments
+/// * `path` - Absolute path of the new data directory, created if missing
+/// * `move_existing` - Move day files, preferences and snapshots from the current
+///   directory; otherwise they stay where they are and the new directory is used as is
+/// * `app` - Tauri app handle for locating the registry
+///
+/// # Returns
+/// The new data directory.
+///
+/// # Errors
+/// Returns an error if the path is relative or not writable, moving would overwrite
+/// files in the new directory, or file operations fail.
+#[tauri::command]
+pub async fn set_data_dir(
+    path: String,
+    move_existing: bool,
+    app: tauri::AppHandle,
+) -> Result<String, String> {
+    let new_root = PathBuf::from(path.trim());
+    if !new_root.is_absolute() {
+        return Err(format!(
+            "Data directory must be an absolute path: {}",
+            new_root.display()
+        ));
+    }
+    check_writable(&new_root)?;
+
+    let (config_dir, default_root) = app_dirs(&app)?;
+    let mut registry = load_registry(&config_dir, &default_root)?;
+
+    {
+        let _guard = crate::DAY_FILES_LOCK.lock().await;
+        if move_existing {
+            migrate_data(&registry.active_workspace()?.root, &new_root)?;
+        }
+        registry.set_active_root(new_root.clone())?;
+        save_registry(&config_dir, &registry)?;
+    }
+
+    let data_dir = new_root.to_string_lossy().to_string();
+    announce_data_dir(&app, &data_dir);
+
+    Ok(data_dir)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_registry_add_and_activate() {
+        let temp_dir = TempDir::new().unwrap();
+        let config_dir = temp_dir.path().join("config");
+        let default_root = temp_dir.path().join("data");
+
+        let mut registry = load_registry(&config_dir, &default_root).unwrap();
+        assert_eq!(registry.active_workspace().unwrap().root, default_root);
+
+        let work_root = temp_dir.path().join("Dropbox/todos");
+        registry.add("Work", work_root.clone()).unwrap();
+        assert!(registry.add("work", work_root.clone()).is_err());
+        assert!(registry.add("Relative", PathBuf::from("todos")).is_err());
+        assert!(registry.add("  ", work_root.clone()).is_err());
+
+        let active = registry.activate("WORK").unwrap();
+        assert_eq!(active.root, work_root);
+        assert!(registry.activate("Personal").is_err());
+        save_registry(&config_dir, &registry).unwrap();
+
+        let reloaded = load_registry(&config_dir, &default_root).unwrap();
+        assert_eq!(reloaded, registry);
+        assert_eq!(reloaded.active_workspace().unwrap().name, "Work");
+
+        // New workspaces default to a directory outside the Default root
+        let personal_root = default_workspace_root(&default_root, "Personal Stuff");
+        assert!(!personal_root.starts_with(&default_root));
+        assert_eq!(
+            personal_root,
+            temp_dir.path().join("data-workspaces/personal-stuff")
+        );
+    }
+
+    #[test]
+    fn test_migrate_data() {
+        let temp_dir = TempDir::new().unwrap();
+        let from = temp_dir.path().join("old");
+        let to = temp_dir.path().join("synced/todos");
+        fs::create_dir_all(from.join("snapshots/2024-01-15")).unwrap();
+        fs::create_dir_all(from.join("workspaces/work")).unwrap();
+        fs::write(from.join("2024-01-15.json"), "{}").unwrap();
+        fs::write(from.join("zoom_level.json"), "{}").unwrap();
+        fs::write(from.join("snapshots/2024-01-15/2024-01-15.json"), "{}").unwrap();
+        fs::create_dir_all(from.join("history/2024-01-15")).unwrap();
+        fs::write(from.join("history/2024-01-15/000001.json"), "{}").unwrap();
+        check_writable(&to).unwrap();
+
+        assert_eq!(migrate_data(&from, &to).unwrap(), 4);
+        assert!(to.join("2024-01-15.json").exists());
+        assert!(to.join("history/2024-01-15/000001.json").exists());
+        assert!(!from.join("history").exists());
+        assert!(to.join("snapshots/2024-01-15/2024-01-15.json").exists());
+        assert!(!from.join("zoom_level.json").exists());
+        // Other workspaces nested in the old directory stay put
+        assert!(from.join("workspaces/work").exists());
+        assert!(!to.join(".write-test").exists());
+
+        // Never overwrites data already in the destination
+        fs::write(from.join("2024-01-15.json"), "{}").unwrap();
+        let clash = migrate_data(&from, &to);
+        assert!(clash.unwrap_err().contains("already contains"));
+        assert!(from.join("2024-01-15.json").exists());
+    }
+}
@@ -47,6 +47,8 @@ let selectedTodo = null;
 let dataDir = '';
 let pomodoroInterval = null;
 let saveNotesTimeout = null;
+let appLocked = false; // True while the backend is locked and the day is hidden
+let lastActivityReport = 0; // When user activity was last reported to the backend
 
 // Calendar state
 let calendarDate = new Date(); // Date for which month is displayed
@@ -79,6 +81,9 @@ let minZoom = 0.5;
 let maxZoom = 3.0;
 let zoomSaveTimeout = null; // Debounce timer for zoom saves
 
+// How long deleted todos stay in the trash, fetched from backend
+let trashRetentionDays = 30;
+
 // Dark mode state
 let darkMode = false;
 
@@ -134,6 +139,9 @@ async function initApp() {
         // Get the app data directory
         dataDir = await window.invoke('get_app_data_dir');
         
+        // Ask for the passphrase before anything reads encrypted files
+        await ensureUnlocked();
+        
         // Run one-time migration of calendar events to todos
         try {
             await window.invoke('migrate_calendar_events_to_todos', {
@@ -150,12 +158,20 @@ async function initApp() {
         // Load zoom limits from backend (single source of truth)
         await loadZoomLimits();
         
+        await loadTrashRetentionDays();
+        
         // Load zoom preference
         await loadZoomPreference();
         
         // Load and display app version
         await loadAppVersion();
         
+        // Refresh when the backend carries todos over to a new day. Registered before
+        // the first load, so a startup rollover finishing in between isn't missed
+        await listenForRollover();
+        await listenForDataDirChange();
+        await listenForAppLock();
+        
         // Load today's data
         await loadDayData(currentDate);
         
@@ -177,6 +193,106 @@ async function initApp() {
     }
 }
 
+// Reload the visible day and calendar after an automatic rollover
+async function listenForRollover() {
+    const listen = window.__TAURI__.event?.listen;
+    if (typeof listen !== 'function') {
+        return;
+    }
+    
+    await listen('rollover-complete', async () => {
+        await loadDayData(currentDate);
+        await updateCalendar();
+    });
+    
+    // Days changed by LAN peer sync
+    await listen('peer-sync-complete', async () => {
+        await loadDayData(currentDate);
+        await updateCalendar();
+    });
+    
+    // Files downloaded or merged by WebDAV sync
+    await listen('webdav-sync-complete', async () => {
+        await loadDayData(currentDate);
+        await updateCalendar();
+    });
+}
+
+// Reload everything when the backend switches to another data directory
+async function listenForDataDirChange() {
+    const listen = window.__TAURI__.event?.listen;
+    if (typeof listen !== 'function') {
+        return;
+    }
+    
+    await listen('data-dir-changed', async (event) => {
+        dataDir = event.payload;
+        await ensureUnlocked();
+        await loadDarkModePreference();
+        zoomLevel = await window.invoke('load_zoom_preference');
+        applyZoom();
+        await loadDayData(currentDate);
+        await updateCalendar();
+    });
+}
+
+// Hide the day when the backend locks, and reload it once unlocked
+async function listenForAppLock() {
+    const listen = window.__TAURI__.event?.listen;
+    if (typeof listen !== 'function') {
+        return;
+    }
+    
+    await listen('app-locked', async () => {
+        appLocked = true;
+        currentDayData = { date: formatDate(currentDate), todos: [], notes: '' };
+        updateUI();
+        
+        // Like a lock screen: keep asking until the passphrase is given
+        while (!(await ensureUnlocked())) {
+            // Cancelled; ask again
+        }
+        appLocked = false;
+        await loadDayData(currentDate);
+        await updateCalendar();
+    });
+    
+    // Keep the idle timer from locking while the window is in use
+    const reportActivity = () => {
+        const now = Date.now();
+        if (now - lastActivityReport > 30000) {
+            lastActivityReport = now;
+            window.invoke('record_activity').catch(() => {});
+        }
+    };
+    document.addEventListener('keydown', reportActivity);
+    document.addEventListener('mousedown', reportActivity);
+    document.addEventListener('wheel', reportActivity);
+}
+
+// Prompt for the passphrase until an encrypted data directory is unlocked.
+// Resolves to false if the prompt was cancelled.
+async function ensureUnlocked() {
+    const status = await window.invoke('load_encryption_status', { dataDir: dataDir });
+    if (!status.enabled || status.unlocked) {
+        return true;
+    }
+    
+    let message = 'Your notes are encrypted. Enter your passphrase to unlock them.';
+    while (true) {
+        const passphrase = await customPassphrase(message, '🔒 Unlock');
+        if (passphrase === null) {
+            return false;
+        }
+        try {
+            await window.invoke('unlock', { passphrase: passphrase, dataDir: dataDir });
+            return true;
+        } catch (error) {
+            message = `${error}. Try again.`;
+        }
+    }
+}
+
 // Set up event listeners
 function setupEventListeners() {
     
@@ -303,7 +419,7 @@ function setupEventListeners() {
 async function loadDayData(date) {
     try {
         const dateString = formatDate(date);
-        currentDayData = await window.invoke('load_day_data', { 
+        currentDayData = await window.invoke('open_day', { 
             date: dateString, 
             dataDir: dataDir 
         });
@@ -323,6 +439,10 @@ async function loadDayData(date) {
 
 // Save current day data
 async function saveDayData() {
+    // Never save the blanked-out day shown while locked
+    if (appLocked) {
+        return;
+    }
     try {
         await window.invoke('save_day_data', {
             dayData: currentDayData,
@@ -331,8 +451,8 @@ async function saveDayData() {
         
         // Update calendar todo counts for the current day only if changed
         const dateStr = formatDate(currentDate);
-        const total = currentDayData.todos ? currentDayData.todos.length : 0;
-        const completed = currentDayData.todos ? currentDayData.todos.filter(todo => todo.completed).length : 0;
+        const total = currentDayData.todos ? currentDayData.todos.filter(todo => !isTodoMoved(todo)).length : 0;
+        const completed = currentDayData.todos ? currentDayData.todos.filter(isTodoDone).length : 0;
         const hasNotes = currentDayData.notes && currentDayData.notes.trim().length > 0;
         const prevCounts = calendarTodoCounts[dateStr] || { total: null, completed: null, hasNotes: null };
         if (prevCounts.total !== total || prevCounts.completed !== completed || prevCounts.hasNotes !== hasNotes) {
@@ -377,13 +497,13 @@ function createTodoElement(todo, index) {
     
     // Create elements manually to use proper event listeners
     const checkbox = document.createElement('div');
-    checkbox.className = `todo-checkbox ${todo.completed ? 'completed' : ''}`;
+    checkbox.className = `todo-checkbox ${isTodoDone(todo) ? 'completed' : ''}`;
     checkbox.addEventListener('click', () => {
         toggleTodo(index);
     });
     
     const todoText = document.createElement('div');
-    todoText.className = `todo-text ${todo.completed ? 'completed' : ''}`;
+    todoText.className = `todo-text ${TODO_STATUS_CLASSES[todo.status] || ''}`;
     todoText.textContent = todo.text;
     todoText.addEventListener('click', () => {
         selectTodo(index);
@@ -404,6 +524,44 @@ function createTodoElement(todo, index) {
         todoText.appendChild(notesIndicator);
     }
     
+    // Show priority, tags, project, reminder and deadline
+    const labels = [
+        ...(PRIORITY_MARKERS[todo.priority] ? [PRIORITY_MARKERS[todo.priority]] : []),
+        ...(todo.tags || []).map(tag => `#${tag}`),
+        ...(todo.project ? [`+${todo.project}`] : []),
+        ...(todo.remind_at ? [`⏰ ${todo.remind_at.slice(11, 16)}`] : []),
+        ...(todo.deadline ? [`due ${todo.deadline.date}${todo.deadline.time ? ' ' + todo.deadline.time.slice(0, 5) : ''}`] : []),
+    ];
+    labels.forEach(label => {
+        const labelEl = document.createElement('span');
+        labelEl.className = 'todo-label';
+        labelEl.textContent = label;
+        todoText.appendChild(labelEl);
+    });
+    
+    // Show checklist progress for todos with subtasks
+    const subtasks = todo.subtasks || [];
+    if (subtasks.length > 0) {
+        const doneCount = subtasks.filter(subtask => subtask.completed).length;
+        const subtaskIndicator = document.createElement('span');
+        subtaskIndicator.className = 'subtask-indicator';
+        subtaskIndicator.textContent = `☑ ${doneCount}/${subtasks.length}`;
+        subtaskIndicator.title = subtasks
+            .map(subtask => `${subtask.completed ? '☑' : '☐'} ${subtask.text}`)
+            .join('\n');
+        todoText.appendChild(subtaskIndicator);
+    }
+    
+    // Show how often an open todo has been carried forward
+    const timesPostponed = (todo.migrated_through || []).length;
+    if (timesPostponed > 0 && !isTodoMoved(todo)) {
+        const postponedIndicator = document.createElement('span');
+        postponedIndicator.className = 'postponed-indicator';
+        postponedIndicator.textContent = `>${timesPostponed}`;
+        postponedIndicator.title = `Postponed ${timesPostponed} time${timesPostponed === 1 ? '' : 's'}`;
+        todoText.appendChild(postponedIndicator);
+    }
+    
     const actionsDiv = document.createElement('div');
     actionsDiv.className = 'todo-actions';
     
@@ -427,7 +585,7 @@ function createTodoElement(todo, index) {
             
             // Use custom confirm dialog
             customConfirm(
-                `Delete this todo?\n\n"${currentDayData.todos[index].text}"\n\nThis cannot be undone.`,
+                `Delete this todo?\n\n"${currentDayData.todos[index].text}"\n\nIt will be kept in the trash for ${trashRetentionDays} days.`,
                 '🗑️ Delete Todo'
             ).then(userConfirmed => {
                 
@@ -756,9 +914,40 @@ function formatDateYYYYMMDD(date) {
     return `${year}-${month}-${day}`;
 }
 
+// Marker shown after the todo text for each priority, matching the `!` syntax
+const PRIORITY_MARKERS = {
+    low: '!',
+    medium: '!!',
+    high: '!!!',
+    urgent: '!!!!',
+};
+
+// CSS class applied to the todo text for each bullet journal status
+const TODO_STATUS_CLASSES = {
+    done: 'completed',
+    migrated: 'migrated',
+    scheduled: 'scheduled',
+    cancelled: 'cancelled'
+};
+
+// Check whether a todo has been completed
+function isTodoDone(todo) {
+    return todo.status === 'done';
+}
+
+// Check whether a todo is only a pointer to where it was moved (migrated/scheduled)
+function isTodoMoved(todo) {
+    return todo.status === 'migrated' || todo.status === 'scheduled';
+}
+
 // Toggle todo completion
 function toggleTodo(index) {
-    currentDayData.todos[index].completed = !currentDayData.todos[index].completed;
+    const todo = currentDayData.todos[index];
+    // Migrated and scheduled entries only point to where the todo went
+    if (isTodoMoved(todo)) {
+        return;
+    }
+    todo.status = isTodoDone(todo) ? 'open' : 'done';
     renderTodoList();
     saveDayData();
 }
@@ -778,35 +967,22 @@ async function moveTodoToNextDay(index) {
         // Calculate next day
         const nextDay = new Date(currentDate);
         nextDay.setDate(nextDay.getDate() + 1);
-        const nextDateString = formatDate(nextDay);
-        
-        // Load next day's data
-        const nextDayData = await window.invoke('load_day_data', { 
-            date: nextDateString, 
-            dataDir: dataDir 
-        });
         
-        // Add todo to next day (reset completion status)
-        const movedTodo = {
-            ...todo,
-            completed: false,
-            move_to_next_day: false
-        };
-        nextDayData.todos.push(movedTodo);
+        // Reset completion status before handing the todo to the backend
+        todo.status = 'open';
+        todo.move_to_next_day = false;
+        await saveDayData();
         
-        // Save next day's data
-        await window.invoke('save_day_data', {
-            dayData: nextDayData,
+        // Move via backend so the todo isn't treated as deleted
+        await window.invoke('move_todo_to_date', {
+            todoId: todo.id,
+            fromDate: formatDate(currentDate),
+            toDate: formatDate(nextDay),
             dataDir: dataDir
         });
         
-        // Remove from current day
-        currentDayData.todos.splice(index, 1);
-        
-        // Update UI and save current day
-        renderTodoList();
-        await saveDayData();
-        
+        // Reload current day and update UI
+        await loadDayData(currentDate);
         
     } catch (error) {
         console.error('Failed to move todo to next day:', error);
@@ -854,7 +1030,7 @@ async function goToToday() {
 
 // Update pomodoro button state
 function updatePomodoroButton() {
-    const hasSelectedTodo = selectedTodo !== null && !currentDayData.todos[selectedTodo]?.completed;
+    const hasSelectedTodo = selectedTodo !== null && currentDayData.todos[selectedTodo]?.status === 'open';
     startPomodoroBtn.disabled = !hasSelectedTodo;
     
     if (hasSelectedTodo) {
@@ -982,7 +1158,7 @@ function startCountdown(totalSeconds) {
                             '✅ Complete Task?'
                         ).then(shouldComplete => {
                             if (shouldComplete) {
-                                currentDayData.todos[selectedTodo].completed = true;
+                                currentDayData.todos[selectedTodo].status = 'done';
                                 selectedTodo = null;
                                 renderTodoList();
                                 updatePomodoroButton();
@@ -1277,8 +1453,8 @@ async function addCalendarTodo(date, todoText) {
     try {
         const dateStr = formatDate(date);
         
-        // Load the day data for the date
-        const dayData = await window.invoke('load_day_data', { 
+        // Open the day, so a new one starts from its template
+        const dayData = await window.invoke('open_day', { 
             date: dateStr, 
             dataDir: dataDir 
         });
@@ -1333,37 +1509,30 @@ async function loadCalendarTodoCounts() {
     const firstDay = new Date(calendarDate.getFullYear(), calendarDate.getMonth(), 1);
     const startDate = new Date(firstDay);
     startDate.setDate(firstDay.getDate() - firstDay.getDay()); // Start from Sunday
+    const endDate = new Date(startDate);
+    endDate.setDate(startDate.getDate() + 41);
     
-    // Load todos for all 42 days shown in calendar
-    const loadPromises = [];
-    for (let i = 0; i < 42; i++) {
-        const date = new Date(startDate);
-        date.setDate(startDate.getDate() + i);
-        const dateStr = formatDate(date);
+    // Read the stored days in one go; days without a file have no counts
+    try {
+        const days = await window.invoke('load_day_range', {
+            startDate: formatDate(startDate),
+            endDate: formatDate(endDate),
+            dataDir: dataDir
+        });
         
-        // Load day data for this date
-        const promise = window.invoke('load_day_data', { 
-            date: dateStr, 
-            dataDir: dataDir 
-        }).then(dayData => {
+        days.forEach(dayData => {
             // Count total and completed todos
-            const total = dayData.todos ? dayData.todos.length : 0;
-            const completed = dayData.todos ? dayData.todos.filter(todo => todo.completed).length : 0;
+            const total = dayData.todos.filter(todo => !isTodoMoved(todo)).length;
+            const completed = dayData.todos.filter(isTodoDone).length;
             
             // Check if day has notes
             const hasNotes = dayData.notes && dayData.notes.trim().length > 0;
             
-            calendarTodoCounts[dateStr] = { total, completed, hasNotes };
-        }).catch(error => {
-            // If loading fails, assume no todos and no notes
-            calendarTodoCounts[dateStr] = { total: 0, completed: 0, hasNotes: false };
+            calendarTodoCounts[dayData.date] = { total, completed, hasNotes };
         });
-        
-        loadPromises.push(promise);
+    } catch (error) {
+        console.error('Failed to load calendar counts:', error);
     }
-    
-    // Wait for all loads to complete
-    await Promise.all(loadPromises);
 }
 
 // Panel resizing functions
@@ -1547,6 +1716,52 @@ function customConfirm(message, title = '🤔 Confirm') {
     });
 }
 
+// Like customConfirm, with a password field; resolves to the entered text or null
+function customPassphrase(message, title = '🔒 Passphrase') {
+    return new Promise((resolve) => {
+        const modal = document.getElementById('custom-modal');
+        const titleEl = document.getElementById('modal-title');
+        const messageEl = document.getElementById('modal-message');
+        const inputEl = document.getElementById('modal-input');
+        const okBtn = document.getElementById('modal-ok');
+        const cancelBtn = document.getElementById('modal-cancel');
+        
+        titleEl.textContent = title;
+        messageEl.textContent = message;
+        inputEl.value = '';
+        
+        cancelBtn.classList.remove('hidden');
+        inputEl.classList.remove('hidden');
+        okBtn.textContent = 'Unlock';
+        cancelBtn.textContent = 'Cancel';
+        
+        modal.classList.remove('hidden');
+        inputEl.focus();
+        
+        const close = (value) => {
+            modal.classList.add('hidden');
+            inputEl.classList.add('hidden');
+            okBtn.removeEventListener('click', handleOk);
+            cancelBtn.removeEventListener('click', handleCancel);
+            document.removeEventListener('keydown', handleKey);
+            resolve(value);
+        };
+        const handleOk = () => close(inputEl.value);
+        const handleCancel = () => close(null);
+        const handleKey = (e) => {
+            if (e.key === 'Escape') {
+                close(null);
+            } else if (e.key === 'Enter') {
+                close(inputEl.value);
+            }
+        };
+        
+        okBtn.addEventListener('click', handleOk);
+        cancelBtn.addEventListener('click', handleCancel);
+        document.addEventListener('keydown', handleKey);
+    });
+}
+
 // Zoom functions
 function zoomIn() {
     if (zoomLevel < maxZoom) {
@@ -1696,6 +1911,14 @@ async function loadZoomLimits() {
     }
 }
 
+async function loadTrashRetentionDays() {
+    try {
+        trashRetentionDays = await window.invoke('get_trash_retention_days');
+    } catch (error) {
+        console.error('Failed to load trash retention, using default:', error);
+    }
+}
+
 /**
  * Load and display the application version from the backend.
  * 
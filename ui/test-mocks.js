@@ -15,7 +15,11 @@ class MockTauriAPI {
             case 'get_app_data_dir':
                 return this.appDataDir;
                 
+            case 'get_trash_retention_days':
+                return 30;
+                
             case 'load_day_data':
+            case 'open_day':
                 return this.loadDayData(params.date, params.dataDir);
                 
             case 'save_day_data':
@@ -68,7 +72,7 @@ class MockTauriAPI {
         return {
             id: this.generateMockUuid(),
             text: text,
-            completed: false,
+            status: 'open',
             created_at: now,
             move_to_next_day: false
         };
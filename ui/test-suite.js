@@ -77,7 +77,7 @@ describe('Todo Management', () => {
         const result = await window.invoke('create_todo_item', { text });
         
         assert.equal(result.text, text);
-        assert.equal(result.completed, false);
+        assert.equal(result.status, 'open');
         assert.equal(result.move_to_next_day, false);
         assert.truthy(result.id);
         assert.truthy(result.created_at);
@@ -120,26 +120,26 @@ describe('Todo Management', () => {
     test('should toggle todo completion', () => {
         currentDayData = {
             todos: [
-                { id: '1', text: 'Test todo', completed: false },
-                { id: '2', text: 'Another todo', completed: true }
+                { id: '1', text: 'Test todo', status: 'open' },
+                { id: '2', text: 'Another todo', status: 'done' }
             ],
             notes: ''
         };
         
         // Toggle first todo
         toggleTodo(0);
-        assert.truthy(currentDayData.todos[0].completed);
+        assert.equal(currentDayData.todos[0].status, 'done');
         
         // Toggle second todo
         toggleTodo(1);
-        assert.falsy(currentDayData.todos[1].completed);
+        assert.equal(currentDayData.todos[1].status, 'open');
     });
 
     test('should select and deselect todos', () => {
         currentDayData = {
             todos: [
-                { id: '1', text: 'Test todo 1', completed: false },
-                { id: '2', text: 'Test todo 2', completed: false }
+                { id: '1', text: 'Test todo 1', status: 'open' },
+                { id: '2', text: 'Test todo 2', status: 'open' }
             ],
             notes: ''
         };
@@ -203,7 +203,7 @@ describe('Data Persistence', () => {
         const complexTodo = {
             id: 'test-complex-todo',
             text: 'Complex todo with émojis 🚀 and "quotes"',
-            completed: true,
+            status: 'done',
             created_at: new Date().toISOString(),
             move_to_next_day: true
         };
@@ -224,7 +224,7 @@ describe('Data Persistence', () => {
         const loadedTodo = loadedData.todos[0];
         assert.equal(loadedTodo.id, complexTodo.id);
         assert.equal(loadedTodo.text, complexTodo.text);
-        assert.equal(loadedTodo.completed, complexTodo.completed);
+        assert.equal(loadedTodo.status, complexTodo.status);
         assert.equal(loadedTodo.move_to_next_day, complexTodo.move_to_next_day);
     });
 });
@@ -270,7 +270,7 @@ describe('Pomodoro Timer', () => {
     test('should start pomodoro with valid todo selected', async () => {
         currentDayData = {
             todos: [
-                { id: '1', text: 'Work on project', completed: false }
+                { id: '1', text: 'Work on project', status: 'open' }
             ],
             notes: ''
         };
@@ -320,7 +320,7 @@ describe('Pomodoro Timer', () => {
         
         // Todo selected
         currentDayData = {
-            todos: [{ id: '1', text: 'Test task', completed: false }],
+            todos: [{ id: '1', text: 'Test task', status: 'open' }],
             notes: ''
         };
         selectedTodo = 0;
@@ -550,7 +550,7 @@ describe('Calendar Functionality', () => {
         const originalInvoke = window.invoke;
         let todoCreated = false;
         window.invoke = async (command, args) => {
-            if (command === 'load_day_data') {
+            if (command === 'open_day') {
                 return { todos: [], notes: '', date: args.date };
             } else if (command === 'save_day_data') {
                 todoCreated = true;
@@ -627,7 +627,7 @@ describe('Calendar Functionality', () => {
         let savedData = null;
         
         window.invoke = async (command, args) => {
-            if (command === 'load_day_data') {
+            if (command === 'open_day') {
                 return { todos: [], notes: '', date: args.date };
             } else if (command === 'save_day_data') {
                 savedData = args.dayData;
@@ -637,7 +637,7 @@ describe('Calendar Functionality', () => {
                 return {
                     id: 'test_todo_123',
                     text: args.text,
-                    completed: false,
+                    status: 'open',
                     created_at: new Date().toISOString(),
                     move_to_next_day: false,
                     notes: ''
@@ -654,7 +654,7 @@ describe('Calendar Functionality', () => {
         assert.truthy(savedData);
         assert.lengthOf(savedData.todos, 1);
         assert.equal(savedData.todos[0].text, '📅 Doctor appointment');
-        assert.falsy(savedData.todos[0].completed);
+        assert.equal(savedData.todos[0].status, 'open');
         assert.equal(savedData.todos[0].id, 'test_todo_123');
         assert.truthy(savedData.todos[0].created_at);
         
@@ -867,7 +867,7 @@ describe('Integration Tests', () => {
         
         // Toggle completion
         toggleTodo(0);
-        assert.truthy(currentDayData.todos[0].completed);
+        assert.equal(currentDayData.todos[0].status, 'done');
         
         // Save data
         const dataDir = await window.invoke('get_app_data_dir');
@@ -883,7 +883,7 @@ describe('Integration Tests', () => {
         });
         
         assert.lengthOf(loadedData.todos, 1);
-        assert.truthy(loadedData.todos[0].completed);
+        assert.equal(loadedData.todos[0].status, 'done');
         assert.equal(loadedData.todos[0].text, 'Integration test todo');
     });
 
@@ -933,7 +933,7 @@ describe('Todo Item Editing and Notes', () => {
                 id: 'test-id-1',
                 text: 'Original text',
                 notes: '',
-                completed: false,
+                status: 'open',
                 created_at: new Date().toISOString(),
                 move_to_next_day: false
             }],
@@ -984,7 +984,7 @@ describe('Todo Item Editing and Notes', () => {
         const legacyTodo = {
             id: 'legacy-id',
             text: 'Legacy todo',
-            completed: false,
+            status: 'open',
             created_at: new Date().toISOString(),
             move_to_next_day: false
             // Note: no notes field
@@ -1011,7 +1011,7 @@ describe('Todo Item Editing and Notes', () => {
             id: 'test-1',
             text: 'Todo with notes',
             notes: 'Some notes here',
-            completed: false,
+            status: 'open',
             created_at: new Date().toISOString(),
             move_to_next_day: false
         };
@@ -1020,7 +1020,7 @@ describe('Todo Item Editing and Notes', () => {
             id: 'test-2',
             text: 'Todo without notes',
             notes: '',
-            completed: false,
+            status: 'open',
             created_at: new Date().toISOString(),
             move_to_next_day: false
         };
@@ -1035,7 +1035,7 @@ describe('Todo Item Editing and Notes', () => {
             id: 'test-1',
             text: 'Test todo',
             notes: '   \n\t  ',
-            completed: false,
+            status: 'open',
             created_at: new Date().toISOString(),
             move_to_next_day: false
         };
@@ -1054,7 +1054,7 @@ Line 3`;
             id: 'test-1',
             text: 'Test todo',
             notes: multilineNotes,
-            completed: false,
+            status: 'open',
             created_at: new Date().toISOString(),
             move_to_next_day: false
         };
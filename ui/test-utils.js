@@ -288,7 +288,7 @@ const testUtils = {
             todos.push({
                 id: `test-todo-${i}`,
                 text: `Test Todo ${i}`,
-                completed: i % 2 === 0,
+                status: i % 2 === 0 ? 'done' : 'open',
                 created_at: new Date().toISOString(),
                 move_to_next_day: false
             });